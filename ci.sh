@@ -37,3 +37,4 @@ RUST_BACKTRACE=1 cargo build --target aarch64-apple-darwin
 RUST_BACKTRACE=1 cargo build --target x86_64-unknown-freebsd
 
 RUST_BACKTRACE=1 cargo test
+RUST_BACKTRACE=1 cargo test --features zeroize
@@ -141,3 +141,69 @@ include!(concat!(env!("OUT_DIR"), "/pkcs11_bindings.rs"));
 // bindgen generates u64::MAX value for ~0UL macro definition, it's not valid on 32bit ulong platforms.
 // This is a workaround for that.
 pub const CK_UNAVAILABLE_INFORMATION: CK_ULONG = CK_ULONG::MAX;
+
+// PKCS #11 3.2 definitions, which are not part of the vendored 3.0 headers yet.
+
+pub const CKK_ML_KEM: CK_KEY_TYPE = 0x49;
+pub const CKK_ML_DSA: CK_KEY_TYPE = 0x4a;
+
+pub const CKA_PARAMETER_SET: CK_ATTRIBUTE_TYPE = 0x61d;
+
+pub const CKM_ML_KEM_KEY_PAIR_GEN: CK_MECHANISM_TYPE = 0x0f;
+pub const CKM_ML_KEM: CK_MECHANISM_TYPE = 0x17;
+pub const CKM_ML_DSA_KEY_PAIR_GEN: CK_MECHANISM_TYPE = 0x1c;
+pub const CKM_ML_DSA: CK_MECHANISM_TYPE = 0x1d;
+
+pub type CK_ML_DSA_PARAMETER_SET_TYPE = CK_ULONG;
+pub const CKP_ML_DSA_44: CK_ML_DSA_PARAMETER_SET_TYPE = 0x1;
+pub const CKP_ML_DSA_65: CK_ML_DSA_PARAMETER_SET_TYPE = 0x2;
+pub const CKP_ML_DSA_87: CK_ML_DSA_PARAMETER_SET_TYPE = 0x3;
+
+pub type CK_ML_KEM_PARAMETER_SET_TYPE = CK_ULONG;
+pub const CKP_ML_KEM_512: CK_ML_KEM_PARAMETER_SET_TYPE = 0x1;
+pub const CKP_ML_KEM_768: CK_ML_KEM_PARAMETER_SET_TYPE = 0x2;
+pub const CKP_ML_KEM_1024: CK_ML_KEM_PARAMETER_SET_TYPE = 0x3;
+
+pub type CK_C_EncapsulateKey = ::std::option::Option<
+    unsafe extern "C" fn(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: *mut CK_MECHANISM,
+        hPublicKey: CK_OBJECT_HANDLE,
+        pTemplate: *mut CK_ATTRIBUTE,
+        ulAttributeCount: CK_ULONG,
+        pCiphertext: *mut CK_BYTE,
+        pulCiphertextLen: *mut CK_ULONG,
+        phKey: *mut CK_OBJECT_HANDLE,
+    ) -> CK_RV,
+>;
+pub type CK_C_DecapsulateKey = ::std::option::Option<
+    unsafe extern "C" fn(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: *mut CK_MECHANISM,
+        hPrivateKey: CK_OBJECT_HANDLE,
+        pTemplate: *mut CK_ATTRIBUTE,
+        ulAttributeCount: CK_ULONG,
+        pCiphertext: *mut CK_BYTE,
+        ulCiphertextLen: CK_ULONG,
+        phKey: *mut CK_OBJECT_HANDLE,
+    ) -> CK_RV,
+>;
+
+/// The beginning of the PKCS #11 3.2 function list: the 3.0 functions, followed by the
+/// first functions added in 3.2. Only meant to be read through a pointer to the function
+/// list returned by the library, which is longer.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CK_FUNCTION_LIST_3_2 {
+    pub function_list_30: CK_FUNCTION_LIST_3_0,
+    pub C_EncapsulateKey: CK_C_EncapsulateKey,
+    pub C_DecapsulateKey: CK_C_DecapsulateKey,
+}
+
+#[test]
+fn layout_CK_FUNCTION_LIST_3_2() {
+    assert_eq!(
+        ::std::mem::size_of::<CK_FUNCTION_LIST_3_2>(),
+        ::std::mem::size_of::<CK_FUNCTION_LIST_3_0>() + 2 * ::std::mem::size_of::<usize>()
+    );
+}
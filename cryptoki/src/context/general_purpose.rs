@@ -33,6 +33,26 @@ pub(super) fn get_library_info(ctx: &Pkcs11) -> Result<Info> {
     }
 }
 
+macro_rules! check_fn_30 {
+    ($pkcs11:expr, $func_name:ident) => {{
+        $pkcs11
+            .impl_
+            .function_list_30
+            .as_ref()
+            .map_or(false, |list| paste! { list.[<C_ $func_name>] }.is_some())
+    }};
+}
+
+macro_rules! check_fn_32 {
+    ($pkcs11:expr, $func_name:ident) => {{
+        $pkcs11
+            .impl_
+            .function_list_32
+            .as_ref()
+            .map_or(false, |list| paste! { list.[<C_ $func_name>] }.is_some())
+    }};
+}
+
 macro_rules! check_fn {
     ($pkcs11:expr, $func_name:ident) => {{
         let func = paste! { $pkcs11
@@ -116,6 +136,28 @@ pub enum Function {
     GetFunctionStatus,
     CancelFunction,
     WaitForSlotEvent,
+    MessageEncryptInit,
+    EncryptMessage,
+    EncryptMessageBegin,
+    EncryptMessageNext,
+    MessageEncryptFinal,
+    MessageDecryptInit,
+    DecryptMessage,
+    DecryptMessageBegin,
+    DecryptMessageNext,
+    MessageDecryptFinal,
+    MessageSignInit,
+    SignMessage,
+    SignMessageBegin,
+    SignMessageNext,
+    MessageSignFinal,
+    MessageVerifyInit,
+    VerifyMessage,
+    VerifyMessageBegin,
+    VerifyMessageNext,
+    MessageVerifyFinal,
+    EncapsulateKey,
+    DecapsulateKey,
 }
 
 impl Display for Function {
@@ -195,5 +237,27 @@ pub(super) fn is_fn_supported(ctx: &Pkcs11, function: Function) -> bool {
         Function::GetFunctionStatus => check_fn!(ctx, GetFunctionStatus),
         Function::CancelFunction => check_fn!(ctx, CancelFunction),
         Function::WaitForSlotEvent => check_fn!(ctx, WaitForSlotEvent),
+        Function::MessageEncryptInit => check_fn_30!(ctx, MessageEncryptInit),
+        Function::EncryptMessage => check_fn_30!(ctx, EncryptMessage),
+        Function::EncryptMessageBegin => check_fn_30!(ctx, EncryptMessageBegin),
+        Function::EncryptMessageNext => check_fn_30!(ctx, EncryptMessageNext),
+        Function::MessageEncryptFinal => check_fn_30!(ctx, MessageEncryptFinal),
+        Function::MessageDecryptInit => check_fn_30!(ctx, MessageDecryptInit),
+        Function::DecryptMessage => check_fn_30!(ctx, DecryptMessage),
+        Function::DecryptMessageBegin => check_fn_30!(ctx, DecryptMessageBegin),
+        Function::DecryptMessageNext => check_fn_30!(ctx, DecryptMessageNext),
+        Function::MessageDecryptFinal => check_fn_30!(ctx, MessageDecryptFinal),
+        Function::MessageSignInit => check_fn_30!(ctx, MessageSignInit),
+        Function::SignMessage => check_fn_30!(ctx, SignMessage),
+        Function::SignMessageBegin => check_fn_30!(ctx, SignMessageBegin),
+        Function::SignMessageNext => check_fn_30!(ctx, SignMessageNext),
+        Function::MessageSignFinal => check_fn_30!(ctx, MessageSignFinal),
+        Function::MessageVerifyInit => check_fn_30!(ctx, MessageVerifyInit),
+        Function::VerifyMessage => check_fn_30!(ctx, VerifyMessage),
+        Function::VerifyMessageBegin => check_fn_30!(ctx, VerifyMessageBegin),
+        Function::VerifyMessageNext => check_fn_30!(ctx, VerifyMessageNext),
+        Function::MessageVerifyFinal => check_fn_30!(ctx, MessageVerifyFinal),
+        Function::EncapsulateKey => check_fn_32!(ctx, EncapsulateKey),
+        Function::DecapsulateKey => check_fn_32!(ctx, DecapsulateKey),
     }
 }
@@ -10,6 +10,34 @@ macro_rules! get_pkcs11 {
     };
 }
 
+/// Same as get_pkcs11! but for the functions added in PKCS #11 3.0, which are only available
+/// through the 3.0 interface of the library.
+macro_rules! get_pkcs11_30 {
+    ($pkcs11:expr, $func_name:ident) => {
+        ($pkcs11
+            .impl_
+            .function_list_30
+            .as_ref()
+            .ok_or(crate::error::Error::LibraryVersionNotSupported)?
+            .$func_name
+            .ok_or(crate::error::Error::NullFunctionPointer)?)
+    };
+}
+
+/// Same as get_pkcs11! but for the functions added in PKCS #11 3.2, which are only available
+/// through the 3.2 interface of the library.
+macro_rules! get_pkcs11_32 {
+    ($pkcs11:expr, $func_name:ident) => {
+        ($pkcs11
+            .impl_
+            .function_list_32
+            .as_ref()
+            .ok_or(crate::error::Error::LibraryVersionNotSupported)?
+            .$func_name
+            .ok_or(crate::error::Error::NullFunctionPointer)?)
+    };
+}
+
 /// Same as get_pkcs11! but does not attempt to apply '?' syntactic sugar.
 /// Suitable only if the caller can't return a Result.
 macro_rules! get_pkcs11_func {
@@ -30,7 +58,7 @@ pub use locking::*;
 
 use crate::error::{Error, Result, Rv};
 
-use log::error;
+use log::{debug, error};
 use std::fmt;
 use std::mem;
 use std::path::Path;
@@ -44,12 +72,18 @@ pub(crate) struct Pkcs11Impl {
     // valid.
     _pkcs11_lib: cryptoki_sys::Pkcs11,
     pub(crate) function_list: cryptoki_sys::CK_FUNCTION_LIST,
+    // The PKCS #11 3.0 function list, if the library provides the 3.0 interface.
+    pub(crate) function_list_30: Option<cryptoki_sys::CK_FUNCTION_LIST_3_0>,
+    // The PKCS #11 3.2 function list, if the library provides the 3.2 interface.
+    pub(crate) function_list_32: Option<cryptoki_sys::CK_FUNCTION_LIST_3_2>,
 }
 
 impl fmt::Debug for Pkcs11Impl {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Pkcs11Impl")
             .field("function_list", &self.function_list)
+            .field("function_list_30", &self.function_list_30)
+            .field("function_list_32", &self.function_list_32)
             .finish()
     }
 }
@@ -117,16 +151,77 @@ impl Pkcs11 {
             .into_result(Function::GetFunctionList)?;
 
         let list_ptr = *list.as_ptr();
+        let function_list_30 = Self::get_function_list_30(&pkcs11_lib);
+        let function_list_32 = Self::get_function_list_32(&pkcs11_lib);
 
         Ok(Pkcs11 {
             impl_: Arc::new(Pkcs11Impl {
                 _pkcs11_lib: pkcs11_lib,
                 function_list: *list_ptr,
+                function_list_30,
+                function_list_32,
             }),
             initialized: Arc::new(RwLock::new(false)),
         })
     }
 
+    // Libraries implementing PKCS #11 3.0 export C_GetInterface, through which the 3.0
+    // function list is obtained. Older libraries do not, and only provide the 2.40 functions.
+    unsafe fn get_function_list_30(
+        pkcs11_lib: &cryptoki_sys::Pkcs11,
+    ) -> Option<cryptoki_sys::CK_FUNCTION_LIST_3_0> {
+        let get_interface = pkcs11_lib.C_GetInterface.as_ref().ok()?;
+        let mut version = cryptoki_sys::CK_VERSION { major: 3, minor: 0 };
+        let mut interface = ptr::null_mut();
+
+        let rv = Rv::from(get_interface(
+            b"PKCS 11\0".as_ptr() as *mut _,
+            &mut version,
+            &mut interface,
+            0,
+        ));
+        if let Rv::Error(error) = rv {
+            debug!("Failed to get the PKCS #11 3.0 interface: {}", error);
+            return None;
+        }
+        if interface.is_null() || (*interface).pFunctionList.is_null() {
+            return None;
+        }
+
+        Some(*((*interface).pFunctionList as *const cryptoki_sys::CK_FUNCTION_LIST_3_0))
+    }
+
+    // Same as get_function_list_30, for the functions added in PKCS #11 3.2. Most libraries
+    // do not provide this interface yet, which is not an error.
+    unsafe fn get_function_list_32(
+        pkcs11_lib: &cryptoki_sys::Pkcs11,
+    ) -> Option<cryptoki_sys::CK_FUNCTION_LIST_3_2> {
+        let get_interface = pkcs11_lib.C_GetInterface.as_ref().ok()?;
+        let mut version = cryptoki_sys::CK_VERSION { major: 3, minor: 2 };
+        let mut interface = ptr::null_mut();
+
+        let rv = Rv::from(get_interface(
+            b"PKCS 11\0".as_ptr() as *mut _,
+            &mut version,
+            &mut interface,
+            0,
+        ));
+        if let Rv::Error(error) = rv {
+            debug!("Failed to get the PKCS #11 3.2 interface: {}", error);
+            return None;
+        }
+        if interface.is_null() || (*interface).pFunctionList.is_null() {
+            return None;
+        }
+        // Do not read past the end of an older function list
+        let list_version = *((*interface).pFunctionList as *const cryptoki_sys::CK_VERSION);
+        if (list_version.major, list_version.minor) < (3, 2) {
+            return None;
+        }
+
+        Some(*((*interface).pFunctionList as *const cryptoki_sys::CK_FUNCTION_LIST_3_2))
+    }
+
     /// Initialize the PKCS11 library
     pub fn initialize(&self, init_args: CInitializeArgs) -> Result<()> {
         let mut init_lock = self
@@ -84,7 +84,8 @@ impl Pkcs11 {
 
     /// Initialize a token
     ///
-    /// Currently will use an empty label for all tokens.
+    /// The label is padded with spaces to the 32 bytes of a PKCS#11 label, or
+    /// truncated to them without splitting a UTF-8 character.
     pub fn init_token(&self, slot: Slot, pin: &AuthPin, label: &str) -> Result<()> {
         let label = label_from_str(label);
         unsafe {
@@ -125,6 +126,35 @@ impl Pkcs11 {
     }
 
     /// Get all mechanisms support by a slot
+    ///
+    /// Mechanisms unknown to this crate, such as vendor defined ones, are
+    /// also returned. Their raw value is available by dereferencing them and
+    /// their information can be queried with [`Pkcs11::get_mechanism_info`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cryptoki::context::{CInitializeArgs, Pkcs11};
+    /// use cryptoki::mechanism::MechanismType;
+    ///
+    /// # fn main() -> testresult::TestResult {
+    /// let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+    /// pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    /// let slot = pkcs11.get_slots_with_token()?[0];
+    ///
+    /// let mechanisms = pkcs11.get_mechanism_list(slot)?;
+    /// let mechanism = if mechanisms.contains(&MechanismType::AES_GCM) {
+    ///     MechanismType::AES_GCM
+    /// } else {
+    ///     MechanismType::AES_CBC_PAD
+    /// };
+    /// println!("Encrypting with {}", mechanism);
+    /// for vendor_mechanism in mechanisms.iter().filter(|m| m.is_vendor_defined()) {
+    ///     println!("{}", vendor_mechanism);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn get_mechanism_list(&self, slot: Slot) -> Result<Vec<MechanismType>> {
         let mut mechanism_count = 0;
 
@@ -153,7 +183,7 @@ impl Pkcs11 {
 
         Ok(mechanisms
             .into_iter()
-            .filter_map(|type_| type_.try_into().ok())
+            .map(MechanismType::new_unchecked)
             .collect())
     }
 
@@ -182,11 +212,37 @@ impl Pkcs11 {
     }
 
     /// Wait for slot events (insertion or removal of a token)
+    ///
+    /// This blocks until the state of a token changes, and returns its slot.
+    /// See [`Pkcs11::get_slot_event`] for the non-blocking version.
+    ///
+    /// Not all libraries support this function: it then fails with
+    /// [`RvError::FunctionNotSupported`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use cryptoki::context::{CInitializeArgs, Pkcs11};
+    ///
+    /// # fn main() -> testresult::TestResult {
+    /// let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+    /// pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    ///
+    /// loop {
+    ///     let slot = pkcs11.wait_for_slot_event()?;
+    ///     let present = pkcs11.get_slot_info(slot)?.token_present();
+    ///     println!("Token {} slot {}", if present { "inserted in" } else { "removed from" }, slot);
+    /// }
+    /// # }
+    /// ```
     pub fn wait_for_slot_event(&self) -> Result<Slot> {
         self.wait_for_slot_event_impl(0)
     }
 
     /// Get the latest slot event (insertion or removal of a token)
+    ///
+    /// This returns immediately: `None` means that no token state changed
+    /// since the last event was returned.
     pub fn get_slot_event(&self) -> Result<Option<Slot>> {
         match self.wait_for_slot_event_impl(CKF_DONT_BLOCK) {
             Err(Error::Pkcs11(RvError::NoEvent, Function::WaitForSlotEvent)) => Ok(None),
@@ -51,6 +51,10 @@ pub enum Error {
 
     /// The PKCS11 library has already been initialized
     AlreadyInitialized,
+
+    /// The function is not available in the version of the PKCS11 interface provided by the
+    /// library, e.g. a PKCS11 3.0 function with a 2.40 library.
+    LibraryVersionNotSupported,
 }
 
 impl fmt::Display for Error {
@@ -68,6 +72,10 @@ impl fmt::Display for Error {
             Error::InvalidValue => write!(f, "The value is not one of the expected options"),
             Error::PinNotSet => write!(f, "Pin has not been set before trying to log in"),
             Error::AlreadyInitialized => write!(f, "PKCS11 library has already been initialized"),
+            Error::LibraryVersionNotSupported => write!(
+                f,
+                "Function not provided by the PKCS11 interface version of the library"
+            ),
         }
     }
 }
@@ -86,7 +94,8 @@ impl std::error::Error for Error {
             | Error::NullFunctionPointer
             | Error::PinNotSet
             | Error::InvalidValue
-            | Error::AlreadyInitialized => None,
+            | Error::AlreadyInitialized
+            | Error::LibraryVersionNotSupported => None,
         }
     }
 }
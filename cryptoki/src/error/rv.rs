@@ -137,3 +137,20 @@ impl Rv {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn random_seed_not_supported() {
+        assert!(matches!(
+            Rv::from(CKR_RANDOM_SEED_NOT_SUPPORTED).into_result(Function::SeedRandom),
+            Err(Error::Pkcs11(
+                RvError::RandomSeedNotSupported,
+                Function::SeedRandom
+            ))
+        ));
+        assert!(Rv::from(CKR_OK).into_result(Function::SeedRandom).is_ok());
+    }
+}
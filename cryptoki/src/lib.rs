@@ -61,6 +61,8 @@ pub mod error;
 pub mod mechanism;
 pub mod object;
 pub mod session;
+#[cfg(feature = "signature-traits")]
+pub mod signer;
 pub mod slot;
 pub mod types;
 
@@ -88,3 +90,22 @@ fn label_from_str(label: &str) -> [CK_UTF8CHAR; 32] {
     }
     lab
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn label_padding() {
+        let label = label_from_str("Test Token");
+        assert_eq!(&label[..10], b"Test Token");
+        assert_eq!(label[10..], [b' '; 22]);
+        assert_eq!(string_from_blank_padded(&label), "Test Token");
+
+        // The 2-byte character at offset 31 does not fit
+        let long = format!("{}\u{e9}", "a".repeat(31));
+        let label = label_from_str(&long);
+        assert_eq!(label[..31], [b'a'; 31]);
+        assert_eq!(label[31], b' ');
+    }
+}
@@ -2,13 +2,29 @@
 // SPDX-License-Identifier: Apache-2.0
 //! AEAD block cipher mechanism types
 
+use crate::error::{Error, Result};
 use crate::types::Ulong;
 use cryptoki_sys::*;
-use std::convert::TryInto;
+use log::error;
+use std::convert::{TryFrom, TryInto};
 use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr;
 use std::slice;
 
+/// Authentication tag lengths, in bits, allowed by PKCS#11 for GCM.
+const GCM_TAG_BITS: [CK_ULONG; 7] = [32, 64, 96, 104, 112, 120, 128];
+
+/// MAC lengths, in bytes, allowed by PKCS#11 for CCM.
+const CCM_MAC_LENGTHS: [u64; 7] = [4, 6, 8, 10, 12, 14, 16];
+
 /// Parameters for AES-GCM.
+///
+/// The tag length is given in **bits** (`ulTagBits`), not bytes: passing `16`
+/// instead of `128` for a full-length tag is a common mistake.  The token does
+/// not return the tag separately either; on encryption it is appended to the
+/// ciphertext, so the output is `tag_bits / 8` bytes longer than the
+/// plaintext, and on decryption it is expected at the end of the input.
 #[derive(Debug, Clone, Copy)]
 #[repr(transparent)]
 pub struct GcmParams<'a> {
@@ -23,20 +39,32 @@ impl<'a> GcmParams<'a> {
     ///
     /// `iv` - The initialization vector.  This must be non-empty.  In PKCS#11
     /// 2.40, the maximum length of the IV is 256 bytes.  A 12-byte IV may be
-    /// processed more efficiently than other lengths.
+    /// processed more efficiently than other lengths, but other lengths (such
+    /// as 16 bytes) are passed through to the token unchanged.
     ///
     /// `aad` - The additional authenticated data.  This data is authenticated
-    /// but not encrypted.  This may be between 0 and 2^32-1 bytes.
+    /// but not encrypted.  This may be between 0 and 2^32-1 bytes.  An empty
+    /// slice is passed to the token as a null pointer with a zero length.
     ///
     /// `tag_bits` - The length, in **bits**, of the authentication tag.  Must
-    /// be between 0 and 128.  The tag is appended to the end of the
-    /// ciphertext.
+    /// be one of 32, 64, 96, 104, 112, 120 or 128.  The tag is appended to the
+    /// end of the ciphertext.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function panics if the length of `iv` or `aad` does not
-    /// fit into an [Ulong].
-    pub fn new(iv: &'a [u8], aad: &'a [u8], tag_bits: Ulong) -> Self {
+    /// This function returns [`Error::InvalidValue`] if `iv` is empty or if
+    /// `tag_bits` is not one of the values listed above, and
+    /// [`Error::TryFromInt`] if the length of `iv` or `aad` does not fit into
+    /// an [Ulong].
+    pub fn new(iv: &'a [u8], aad: &'a [u8], tag_bits: Ulong) -> Result<Self> {
+        if iv.is_empty() {
+            error!("GCM initialization vector must not be empty");
+            return Err(Error::InvalidValue);
+        }
+        if !GCM_TAG_BITS.contains(&*tag_bits) {
+            error!("GCM tag length of {} bits is not supported", tag_bits);
+            return Err(Error::InvalidValue);
+        }
         // The ulIvBits parameter seems to be missing from the 2.40 spec,
         // although it is included in the header file.  In [1], OASIS clarified
         // that the header file is normative.  In 3.0, they added the parameter
@@ -53,23 +81,21 @@ impl<'a> GcmParams<'a> {
         // set it to zero.
         //
         // [1]: https://www.oasis-open.org/committees/document.php?document_id=58032&wg_abbrev=pkcs11
-        GcmParams {
+        Ok(GcmParams {
             inner: CK_GCM_PARAMS {
                 pIv: iv.as_ptr() as *mut _,
-                ulIvLen: iv
-                    .len()
-                    .try_into()
-                    .expect("iv length does not fit in CK_ULONG"),
+                ulIvLen: iv.len().try_into()?,
                 ulIvBits: 0,
-                pAAD: aad.as_ptr() as *mut _,
-                ulAADLen: aad
-                    .len()
-                    .try_into()
-                    .expect("aad length does not fit in CK_ULONG"),
+                pAAD: if aad.is_empty() {
+                    ptr::null_mut()
+                } else {
+                    aad.as_ptr() as *mut _
+                },
+                ulAADLen: aad.len().try_into()?,
                 ulTagBits: tag_bits.into(),
             },
             _marker: PhantomData,
-        }
+        })
     }
 
     /// The initialization vector.
@@ -80,7 +106,10 @@ impl<'a> GcmParams<'a> {
 
     /// The additional authenticated data.
     pub fn aad(&self) -> &'a [u8] {
-        // SAEFTY: In the constructor, the AAD always comes from a &'a [u8]
+        if self.inner.pAAD.is_null() {
+            return &[];
+        }
+        // SAFETY: In the constructor, a non-null AAD always comes from a &'a [u8]
         unsafe { slice::from_raw_parts(self.inner.pAAD, self.inner.ulAADLen as _) }
     }
 
@@ -89,3 +118,359 @@ impl<'a> GcmParams<'a> {
         self.inner.ulTagBits.into()
     }
 }
+
+/// Parameters for AES-CCM.
+///
+/// CCM needs the length of the data to process before the operation starts,
+/// so the parameters must be constructed for a given message length.  As with
+/// GCM, on encryption the MAC is appended to the ciphertext, and on decryption
+/// it is expected at the end of the input.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct CcmParams<'a> {
+    inner: CK_CCM_PARAMS,
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> CcmParams<'a> {
+    /// Construct CCM parameters.
+    ///
+    /// # Arguments
+    ///
+    /// `data_len` - The length, in bytes, of the data to encrypt or decrypt.
+    /// When decrypting, this is the length of the ciphertext without the MAC.
+    ///
+    /// `nonce` - The nonce.  Its length must be between 7 and 13 bytes.
+    ///
+    /// `aad` - The additional authenticated data.  This data is authenticated
+    /// but not encrypted.  An empty slice is passed to the token as a null
+    /// pointer with a zero length.
+    ///
+    /// `mac_len` - The length, in **bytes**, of the MAC.  It must be one of 4,
+    /// 6, 8, 10, 12, 14 or 16.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if the length of `nonce`
+    /// or `mac_len` is out of range, and [`Error::TryFromInt`] if one of the lengths does
+    /// not fit into an [Ulong].
+    pub fn new(data_len: u64, nonce: &'a [u8], aad: &'a [u8], mac_len: u64) -> Result<Self> {
+        if !(7..=13).contains(&nonce.len()) {
+            error!(
+                "CCM nonce must be between 7 and 13 bytes long, not {}",
+                nonce.len()
+            );
+            return Err(Error::InvalidValue);
+        }
+        if !CCM_MAC_LENGTHS.contains(&mac_len) {
+            error!(
+                "CCM MAC length must be one of {:?} bytes, not {}",
+                CCM_MAC_LENGTHS, mac_len
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(CcmParams {
+            inner: CK_CCM_PARAMS {
+                ulDataLen: usize::try_from(data_len)?.try_into()?,
+                pNonce: nonce.as_ptr() as *mut _,
+                ulNonceLen: nonce.len().try_into()?,
+                pAAD: if aad.is_empty() {
+                    ptr::null_mut()
+                } else {
+                    aad.as_ptr() as *mut _
+                },
+                ulAADLen: aad.len().try_into()?,
+                ulMACLen: usize::try_from(mac_len)?.try_into()?,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The length, in bytes, of the data to process.
+    pub fn data_len(&self) -> Ulong {
+        self.inner.ulDataLen.into()
+    }
+
+    /// The nonce.
+    pub fn nonce(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the nonce always comes from a &'a [u8]
+        unsafe { slice::from_raw_parts(self.inner.pNonce, self.inner.ulNonceLen as _) }
+    }
+
+    /// The additional authenticated data.
+    pub fn aad(&self) -> &'a [u8] {
+        if self.inner.pAAD.is_null() {
+            return &[];
+        }
+        // SAFETY: In the constructor, a non-null AAD always comes from a &'a [u8]
+        unsafe { slice::from_raw_parts(self.inner.pAAD, self.inner.ulAADLen as _) }
+    }
+
+    /// The length, in bytes, of the MAC.
+    pub fn mac_len(&self) -> Ulong {
+        self.inner.ulMACLen.into()
+    }
+}
+
+/// Function used by the token to generate the IV of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct GeneratorFunction {
+    val: CK_GENERATOR_FUNCTION,
+}
+
+impl GeneratorFunction {
+    /// The IV is provided by the application and not modified by the token.
+    pub const NO_GENERATE: GeneratorFunction = GeneratorFunction {
+        val: CKG_NO_GENERATE,
+    };
+    /// The token generates the IV in a way of its own choosing.
+    pub const GENERATE: GeneratorFunction = GeneratorFunction { val: CKG_GENERATE };
+    /// The token generates the non-fixed part of the IV with a counter.
+    pub const GENERATE_COUNTER: GeneratorFunction = GeneratorFunction {
+        val: CKG_GENERATE_COUNTER,
+    };
+    /// The token generates the non-fixed part of the IV randomly.
+    pub const GENERATE_RANDOM: GeneratorFunction = GeneratorFunction {
+        val: CKG_GENERATE_RANDOM,
+    };
+}
+
+impl Deref for GeneratorFunction {
+    type Target = CK_GENERATOR_FUNCTION;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl From<GeneratorFunction> for CK_GENERATOR_FUNCTION {
+    fn from(generator: GeneratorFunction) -> Self {
+        *generator
+    }
+}
+
+impl TryFrom<CK_GENERATOR_FUNCTION> for GeneratorFunction {
+    type Error = Error;
+
+    fn try_from(generator: CK_GENERATOR_FUNCTION) -> Result<Self> {
+        match generator {
+            CKG_NO_GENERATE => Ok(GeneratorFunction::NO_GENERATE),
+            CKG_GENERATE => Ok(GeneratorFunction::GENERATE),
+            CKG_GENERATE_COUNTER => Ok(GeneratorFunction::GENERATE_COUNTER),
+            CKG_GENERATE_RANDOM => Ok(GeneratorFunction::GENERATE_RANDOM),
+            other => {
+                error!(
+                    "Generator function type {} is not one of the valid values.",
+                    other
+                );
+                Err(Error::InvalidValue)
+            }
+        }
+    }
+}
+
+/// Per-message parameters for AES-GCM with the message-based API of PKCS#11
+/// 3.0.
+///
+/// Unlike [`GcmParams`], the IV and tag buffers are mutable: with a
+/// generator function other than [`GeneratorFunction::NO_GENERATE`], the token
+/// writes the IV it chose into the IV buffer, and on encryption it writes the
+/// tag into the tag buffer instead of appending it to the ciphertext.  Some
+/// tokens require the IV to be generated this way, e.g. in FIPS mode.
+///
+/// The buffers can be read back with [`GcmMessageParams::iv`] and
+/// [`GcmMessageParams::tag`] once the message has been processed, or directly
+/// after the parameters are dropped.
+///
+/// This structure wraps a `CK_GCM_MESSAGE_PARAMS` structure.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct GcmMessageParams<'a> {
+    inner: CK_GCM_MESSAGE_PARAMS,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> GcmMessageParams<'a> {
+    /// Construct GCM message parameters.
+    ///
+    /// # Arguments
+    ///
+    /// `iv` - The IV buffer.  This must be non-empty.  It is read by the token
+    /// with [`GeneratorFunction::NO_GENERATE`], and written to otherwise.
+    ///
+    /// `iv_fixed_bits` - The number of leading bits of `iv` that the token
+    /// keeps unchanged when generating the IV.
+    ///
+    /// `iv_generator` - How the token generates the IV.
+    ///
+    /// `tag` - The tag buffer.  Its length, in bytes, sets the length of the
+    /// tag; it must be one of 4, 8, 12, 13, 14, 15 or 16.  On encryption the
+    /// token writes the tag into it, on decryption it must hold the tag.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `iv` is empty, if
+    /// `iv_fixed_bits` is larger than the IV, or if the length of `tag` is
+    /// not one of the values listed above.
+    pub fn new(
+        iv: &'a mut [u8],
+        iv_fixed_bits: Ulong,
+        iv_generator: GeneratorFunction,
+        tag: &'a mut [u8],
+    ) -> Result<Self> {
+        if iv.is_empty() {
+            error!("GCM initialization vector must not be empty");
+            return Err(Error::InvalidValue);
+        }
+        if usize::try_from(*iv_fixed_bits)? > iv.len() * 8 {
+            error!(
+                "{} fixed bits do not fit in a {}-byte IV",
+                *iv_fixed_bits,
+                iv.len()
+            );
+            return Err(Error::InvalidValue);
+        }
+        let tag_bits = CK_ULONG::try_from(tag.len())? * 8;
+        if !GCM_TAG_BITS.contains(&tag_bits) {
+            error!("GCM tag length {} bytes is not supported", tag.len());
+            return Err(Error::InvalidValue);
+        }
+        Ok(GcmMessageParams {
+            inner: CK_GCM_MESSAGE_PARAMS {
+                pIv: iv.as_mut_ptr(),
+                ulIvLen: iv.len().try_into()?,
+                ulIvFixedBits: *iv_fixed_bits,
+                ivGenerator: *iv_generator,
+                pTag: tag.as_mut_ptr(),
+                ulTagBits: tag_bits,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The IV buffer.
+    pub fn iv(&self) -> &[u8] {
+        // SAFETY: In the constructor, the IV always comes from a &'a mut [u8]
+        unsafe { slice::from_raw_parts(self.inner.pIv, self.inner.ulIvLen as _) }
+    }
+
+    /// The number of leading bits of the IV kept unchanged by the token.
+    pub fn iv_fixed_bits(&self) -> Ulong {
+        self.inner.ulIvFixedBits.into()
+    }
+
+    /// How the token generates the IV.
+    pub fn iv_generator(&self) -> GeneratorFunction {
+        GeneratorFunction {
+            val: self.inner.ivGenerator,
+        }
+    }
+
+    /// The tag buffer.
+    pub fn tag(&self) -> &[u8] {
+        // SAFETY: In the constructor, the tag always comes from a &'a mut [u8]
+        unsafe { slice::from_raw_parts(self.inner.pTag, (self.inner.ulTagBits / 8) as _) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gcm_tag_bits() {
+        let iv = [0; 12];
+        for tag_bits in GCM_TAG_BITS {
+            let params = GcmParams::new(&iv, &[], tag_bits.into()).unwrap();
+            assert_eq!(*params.tag_bits(), tag_bits);
+        }
+        for tag_bits in [0, 8, 100, 129] {
+            assert!(GcmParams::new(&iv, &[], tag_bits.into()).is_err());
+        }
+    }
+
+    #[test]
+    fn gcm_iv_and_aad() {
+        assert!(GcmParams::new(&[], &[], 128.into()).is_err());
+
+        let iv = [1; 16];
+        let params = GcmParams::new(&iv, &[], 128.into()).unwrap();
+        assert_eq!(params.iv(), &iv);
+        assert!(params.inner.pAAD.is_null());
+        assert_eq!(params.inner.ulAADLen, 0);
+        assert!(params.aad().is_empty());
+
+        let aad = [2; 5];
+        let params = GcmParams::new(&iv, &aad, 128.into()).unwrap();
+        assert_eq!(params.aad(), &aad);
+    }
+
+    #[test]
+    fn ccm_nonce() {
+        let nonce = [3; 14];
+        for len in 7..=13 {
+            let params = CcmParams::new(32, &nonce[..len], &[], 16).unwrap();
+            assert_eq!(params.nonce(), &nonce[..len]);
+            assert_eq!(*params.data_len(), 32);
+            assert_eq!(*params.mac_len(), 16);
+            assert!(params.aad().is_empty());
+        }
+        assert!(CcmParams::new(32, &nonce[..6], &[], 16).is_err());
+        assert!(CcmParams::new(32, &nonce, &[], 16).is_err());
+    }
+
+    #[test]
+    fn ccm_mac_len() {
+        let nonce = [3; 12];
+        for mac_len in CCM_MAC_LENGTHS {
+            let params = CcmParams::new(32, &nonce, &[], mac_len).unwrap();
+            assert_eq!(*params.mac_len(), mac_len);
+        }
+        for mac_len in [0, 2, 5, 15, 17, 128] {
+            assert!(CcmParams::new(32, &nonce, &[], mac_len).is_err());
+        }
+    }
+
+    #[test]
+    fn gcm_message_buffers() {
+        let mut iv = [0; 12];
+        let mut tag = [0; 16];
+        let params = GcmMessageParams::new(
+            &mut iv,
+            32.into(),
+            GeneratorFunction::GENERATE_COUNTER,
+            &mut tag,
+        )
+        .unwrap();
+        assert_eq!(params.iv(), &[0; 12]);
+        assert_eq!(*params.iv_fixed_bits(), 32);
+        assert_eq!(params.iv_generator(), GeneratorFunction::GENERATE_COUNTER);
+        assert_eq!(params.inner.ulTagBits, 128);
+
+        // the token writes through the pointers
+        unsafe {
+            *params.inner.pIv = 1;
+            *params.inner.pTag.add(15) = 2;
+        }
+        assert_eq!(params.iv()[0], 1);
+        assert_eq!(params.tag()[15], 2);
+        assert_eq!(iv[0], 1);
+        assert_eq!(tag[15], 2);
+
+        let mut tag = [0; 5];
+        assert!(
+            GcmMessageParams::new(&mut iv, 0.into(), GeneratorFunction::GENERATE, &mut tag)
+                .is_err()
+        );
+        let mut tag = [0; 16];
+        assert!(
+            GcmMessageParams::new(&mut iv, 97.into(), GeneratorFunction::GENERATE, &mut tag)
+                .is_err()
+        );
+        assert!(
+            GcmMessageParams::new(&mut [], 0.into(), GeneratorFunction::GENERATE, &mut tag)
+                .is_err()
+        );
+    }
+}
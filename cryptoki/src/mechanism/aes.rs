@@ -0,0 +1,187 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! AES mechanism types
+
+use super::Mechanism;
+use crate::error::{Error, Result};
+use crate::types::Ulong;
+use cryptoki_sys::*;
+use log::error;
+use std::convert::{TryFrom, TryInto};
+
+/// Parameters for AES in counter mode.
+///
+/// The counter block is incremented as a big-endian integer in its
+/// `counter_bits` least significant bits; the remaining bits are left
+/// untouched.
+///
+/// This structure wraps a `CK_AES_CTR_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct AesCtrParams {
+    inner: CK_AES_CTR_PARAMS,
+}
+
+impl AesCtrParams {
+    /// Construct AES-CTR parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter_bits` - The number of bits of the counter block that are
+    ///   incremented, between 1 and 128.
+    ///
+    /// * `cb` - The initial counter block.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `counter_bits` is out
+    /// of range.
+    pub fn new(counter_bits: u64, cb: [u8; 16]) -> Result<Self> {
+        if !(1..=128).contains(&counter_bits) {
+            error!(
+                "AES-CTR counter bits must be between 1 and 128, not {}",
+                counter_bits
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_AES_CTR_PARAMS {
+                ulCounterBits: usize::try_from(counter_bits)?.try_into()?,
+                cb,
+            },
+        })
+    }
+
+    /// Construct AES-CTR parameters from a nonce, followed by a counter
+    /// starting at zero in the remaining bytes of the counter block.
+    ///
+    /// This is the common layout of the counter block, e.g. a 12-byte nonce
+    /// and a 32-bit counter.  The counter never overflows into the nonce: the
+    /// token fails once all its values are used.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `nonce` is not between
+    /// 0 and 15 bytes long.
+    pub fn with_nonce(nonce: &[u8]) -> Result<Self> {
+        if nonce.len() >= 16 {
+            error!(
+                "AES-CTR nonce must be shorter than 16 bytes, got {}",
+                nonce.len()
+            );
+            return Err(Error::InvalidValue);
+        }
+        let mut cb = [0; 16];
+        cb[..nonce.len()].copy_from_slice(nonce);
+        Self::new(((16 - nonce.len()) * 8).try_into()?, cb)
+    }
+
+    /// The number of bits of the counter block that are incremented.
+    pub fn counter_bits(&self) -> Ulong {
+        self.inner.ulCounterBits.into()
+    }
+
+    /// The initial counter block.
+    pub fn cb(&self) -> [u8; 16] {
+        self.inner.cb
+    }
+}
+
+impl From<AesCtrParams> for Mechanism<'_> {
+    fn from(params: AesCtrParams) -> Self {
+        Mechanism::AesCtr(params)
+    }
+}
+
+/// Parameters for AES in XTS mode (IEEE P1619).
+///
+/// The parameter of the mechanism is the 16-byte tweak of the data unit to
+/// process.  PKCS#11 has no notion of sector size: each encryption or
+/// decryption processes a single data unit, so the data passed to the token
+/// must be exactly one sector long, and the parameters must be constructed
+/// anew for each sector.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct AesXtsParams {
+    tweak: [u8; 16],
+}
+
+impl AesXtsParams {
+    /// Construct AES-XTS parameters from a tweak.
+    pub fn new(tweak: [u8; 16]) -> Self {
+        Self { tweak }
+    }
+
+    /// Construct AES-XTS parameters for the data unit, e.g. the disk sector,
+    /// numbered `data_unit`.
+    ///
+    /// As in IEEE P1619, the tweak is the data unit number, encoded as a
+    /// little-endian integer.
+    pub fn with_data_unit(data_unit: u64) -> Self {
+        let mut tweak = [0; 16];
+        tweak[..8].copy_from_slice(&data_unit.to_le_bytes());
+        Self { tweak }
+    }
+
+    /// The tweak.
+    pub fn tweak(&self) -> [u8; 16] {
+        self.tweak
+    }
+}
+
+impl From<AesXtsParams> for Mechanism<'_> {
+    fn from(params: AesXtsParams) -> Self {
+        Mechanism::AesXts(params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn xts_data_unit() {
+        let params = AesXtsParams::with_data_unit(0x0102);
+        assert_eq!(params.tweak()[..3], [0x02, 0x01, 0]);
+        assert_eq!(params.tweak()[3..], [0; 13]);
+        assert_eq!(AesXtsParams::new([9; 16]).tweak(), [9; 16]);
+    }
+
+    #[test]
+    fn ctr_counter_bits() {
+        let cb = [7; 16];
+        for counter_bits in [1u8, 32, 64, 128] {
+            let params = AesCtrParams::new(counter_bits.into(), cb).unwrap();
+            assert_eq!(*params.counter_bits(), counter_bits.into());
+            assert_eq!(params.cb(), cb);
+        }
+        assert!(AesCtrParams::new(0, cb).is_err());
+        assert!(AesCtrParams::new(129, cb).is_err());
+    }
+
+    #[test]
+    fn ctr_mechanism() {
+        let mechanism: Mechanism = AesCtrParams::new(32, [9; 16]).unwrap().into();
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_AES_CTR);
+        assert_eq!(raw.ulParameterLen as usize, size_of::<CK_AES_CTR_PARAMS>());
+        let raw_params = unsafe { &*(raw.pParameter as *const CK_AES_CTR_PARAMS) };
+        assert_eq!(raw_params.ulCounterBits, 32);
+        assert_eq!(raw_params.cb, [9; 16]);
+    }
+
+    #[test]
+    fn ctr_with_nonce() {
+        let params = AesCtrParams::with_nonce(&[7; 12]).unwrap();
+        assert_eq!(*params.counter_bits(), 32);
+        assert_eq!(
+            params.cb(),
+            [7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 0, 0, 0, 0]
+        );
+
+        let params = AesCtrParams::with_nonce(&[]).unwrap();
+        assert_eq!(*params.counter_bits(), 128);
+        assert!(AesCtrParams::with_nonce(&[0; 16]).is_err());
+    }
+}
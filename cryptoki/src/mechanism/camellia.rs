@@ -0,0 +1,117 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Camellia mechanism types
+
+use super::Mechanism;
+use crate::error::{Error, Result};
+use crate::types::Ulong;
+use cryptoki_sys::*;
+use log::error;
+use std::convert::{TryFrom, TryInto};
+
+/// Parameters for Camellia in counter mode.
+///
+/// As with [`AesCtrParams`](super::aes::AesCtrParams), the counter block is
+/// incremented as a big-endian integer in its `counter_bits` least
+/// significant bits.
+///
+/// This structure wraps a `CK_CAMELLIA_CTR_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct CamelliaCtrParams {
+    inner: CK_CAMELLIA_CTR_PARAMS,
+}
+
+impl CamelliaCtrParams {
+    /// Construct Camellia-CTR parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter_bits` - The number of bits of the counter block that are
+    ///   incremented, between 1 and 128.
+    ///
+    /// * `cb` - The initial counter block.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `counter_bits` is out
+    /// of range.
+    pub fn new(counter_bits: u64, cb: [u8; 16]) -> Result<Self> {
+        if !(1..=128).contains(&counter_bits) {
+            error!(
+                "Camellia-CTR counter bits must be between 1 and 128, not {}",
+                counter_bits
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_CAMELLIA_CTR_PARAMS {
+                ulCounterBits: usize::try_from(counter_bits)?.try_into()?,
+                cb,
+            },
+        })
+    }
+
+    /// The number of bits of the counter block that are incremented.
+    pub fn counter_bits(&self) -> Ulong {
+        self.inner.ulCounterBits.into()
+    }
+
+    /// The initial counter block.
+    pub fn cb(&self) -> [u8; 16] {
+        self.inner.cb
+    }
+}
+
+impl From<CamelliaCtrParams> for Mechanism<'_> {
+    fn from(params: CamelliaCtrParams) -> Self {
+        Mechanism::CamelliaCtr(params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mechanism::MechanismType;
+    use std::mem::size_of;
+
+    #[test]
+    fn ctr_params() {
+        assert!(matches!(
+            CamelliaCtrParams::new(0, [0; 16]),
+            Err(Error::InvalidValue)
+        ));
+        let params = CamelliaCtrParams::new(32, [0x22; 16]).unwrap();
+        let mechanism = Mechanism::from(params);
+        assert_eq!(mechanism.mechanism_type(), MechanismType::CAMELLIA_CTR);
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_CAMELLIA_CTR);
+        assert_eq!(
+            raw.ulParameterLen as usize,
+            size_of::<CK_CAMELLIA_CTR_PARAMS>()
+        );
+        let raw_params = unsafe { &*(raw.pParameter as *const CK_CAMELLIA_CTR_PARAMS) };
+        assert_eq!(raw_params.ulCounterBits, 32);
+        assert_eq!(raw_params.cb, [0x22; 16]);
+    }
+
+    #[test]
+    fn mechanism_types() {
+        for (raw, mechanism_type) in [
+            (CKM_CAMELLIA_CTR, MechanismType::CAMELLIA_CTR),
+            (CKM_CAMELLIA_MAC, MechanismType::CAMELLIA_MAC),
+            (
+                CKM_CAMELLIA_MAC_GENERAL,
+                MechanismType::CAMELLIA_MAC_GENERAL,
+            ),
+        ] {
+            assert_eq!(MechanismType::try_from(raw).unwrap(), mechanism_type);
+            assert_eq!(*mechanism_type, raw);
+        }
+        assert_eq!(
+            Mechanism::camellia_mac_general(8).unwrap().mechanism_type(),
+            MechanismType::CAMELLIA_MAC_GENERAL
+        );
+        assert!(Mechanism::camellia_mac_general(17).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! ChaCha20 and Salsa20 mechanism types
+
+use super::Mechanism;
+use crate::error::{Error, Result};
+use cryptoki_sys::*;
+use log::error;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::slice;
+
+/// Parameters for the ChaCha20 stream cipher.
+///
+/// The block counter and the nonce together make up the last 128 bits of the
+/// ChaCha20 state: either a 32-bit counter with a 96-bit nonce (RFC 8439), or
+/// a 64-bit counter with a 64-bit nonce (original construction).
+///
+/// This structure wraps a `CK_CHACHA20_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct ChaCha20Params<'a> {
+    inner: CK_CHACHA20_PARAMS,
+    /// Marker type to ensure we don't outlive the block counter and nonce
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ChaCha20Params<'a> {
+    /// Construct ChaCha20 parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_counter` - The initial block counter, 4 or 8 bytes long.
+    ///
+    /// * `nonce` - The nonce, 12 or 8 bytes long respectively.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if the lengths are not
+    /// one of the accepted combinations.
+    pub fn new(block_counter: &'a [u8], nonce: &'a [u8]) -> Result<Self> {
+        if !matches!((block_counter.len(), nonce.len()), (4, 12) | (8, 8)) {
+            error!(
+                "ChaCha20 block counter and nonce must be 4 and 12 or 8 and 8 bytes long, got {} and {}",
+                block_counter.len(),
+                nonce.len()
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_CHACHA20_PARAMS {
+                pBlockCounter: block_counter.as_ptr() as *mut _,
+                blockCounterBits: (block_counter.len() * 8).try_into()?,
+                pNonce: nonce.as_ptr() as *mut _,
+                ulNonceBits: (nonce.len() * 8).try_into()?,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The initial block counter.
+    pub fn block_counter(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the block counter always comes from a
+        // &'a [u8]
+        unsafe {
+            slice::from_raw_parts(
+                self.inner.pBlockCounter,
+                self.inner.blockCounterBits as usize / 8,
+            )
+        }
+    }
+
+    /// The nonce.
+    pub fn nonce(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the nonce always comes from a &'a [u8]
+        unsafe { slice::from_raw_parts(self.inner.pNonce, self.inner.ulNonceBits as usize / 8) }
+    }
+}
+
+impl<'a> From<ChaCha20Params<'a>> for Mechanism<'a> {
+    fn from(params: ChaCha20Params<'a>) -> Self {
+        Mechanism::ChaCha20(params)
+    }
+}
+
+/// Parameters for the ChaCha20-Poly1305 and Salsa20-Poly1305 AEAD mechanisms.
+///
+/// The 16-byte Poly1305 tag is appended to the ciphertext on encryption, and
+/// expected at the end of the ciphertext on decryption.
+///
+/// This structure wraps a `CK_SALSA20_CHACHA20_POLY1305_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct ChaCha20Poly1305Params<'a> {
+    inner: CK_SALSA20_CHACHA20_POLY1305_PARAMS,
+    /// Marker type to ensure we don't outlive the nonce and AAD
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ChaCha20Poly1305Params<'a> {
+    /// Construct ChaCha20-Poly1305 or Salsa20-Poly1305 parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce.  It must be 12 bytes long (RFC 8439) or 8 bytes
+    ///   long (original construction), or 24 bytes long for XSalsa20.
+    ///
+    /// * `aad` - The additional authenticated data.  It may be empty.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if the nonce length is
+    /// not one of the accepted lengths.
+    pub fn new(nonce: &'a [u8], aad: &'a [u8]) -> Result<Self> {
+        if !matches!(nonce.len(), 8 | 12 | 24) {
+            error!(
+                "ChaCha20-Poly1305 nonce must be 8, 12 or 24 bytes long, got {}",
+                nonce.len()
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_SALSA20_CHACHA20_POLY1305_PARAMS {
+                pNonce: nonce.as_ptr() as *mut _,
+                ulNonceLen: nonce.len().try_into()?,
+                pAAD: if aad.is_empty() {
+                    null_mut()
+                } else {
+                    aad.as_ptr() as *mut _
+                },
+                ulAADLen: aad.len().try_into()?,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The nonce.
+    pub fn nonce(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the nonce always comes from a &'a [u8]
+        unsafe { slice::from_raw_parts(self.inner.pNonce, self.inner.ulNonceLen as _) }
+    }
+
+    /// The additional authenticated data.
+    pub fn aad(&self) -> &'a [u8] {
+        if self.inner.pAAD.is_null() {
+            &[]
+        } else {
+            // SAFETY: In the constructor, a non-null AAD always comes from a
+            // &'a [u8]
+            unsafe { slice::from_raw_parts(self.inner.pAAD, self.inner.ulAADLen as _) }
+        }
+    }
+}
+
+/// The parameters are converted to a [`Mechanism::ChaCha20Poly1305`]: use
+/// [`Mechanism::Salsa20Poly1305`] explicitly for Salsa20-Poly1305.
+impl<'a> From<ChaCha20Poly1305Params<'a>> for Mechanism<'a> {
+    fn from(params: ChaCha20Poly1305Params<'a>) -> Self {
+        Mechanism::ChaCha20Poly1305(params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn chacha20_counter_and_nonce() {
+        let params = ChaCha20Params::new(&[1, 0, 0, 0], &[2; 12]).unwrap();
+        assert_eq!(params.block_counter(), [1, 0, 0, 0]);
+        assert_eq!(params.nonce(), [2; 12]);
+        assert_eq!(params.inner.blockCounterBits, 32);
+        assert_eq!(params.inner.ulNonceBits, 96);
+
+        let params = ChaCha20Params::new(&[0; 8], &[3; 8]).unwrap();
+        assert_eq!(params.block_counter().len(), 8);
+        assert_eq!(params.nonce(), [3; 8]);
+
+        assert!(ChaCha20Params::new(&[0; 4], &[0; 8]).is_err());
+        assert!(ChaCha20Params::new(&[0; 8], &[0; 12]).is_err());
+        assert!(ChaCha20Params::new(&[], &[0; 16]).is_err());
+    }
+
+    #[test]
+    fn chacha20_poly1305_nonce() {
+        for len in [8, 12, 24] {
+            let nonce = vec![0; len];
+            let params = ChaCha20Poly1305Params::new(&nonce, &[]).unwrap();
+            assert_eq!(params.nonce().len(), len);
+            assert!(params.aad().is_empty());
+        }
+        for len in [0, 7, 16, 32] {
+            let nonce = vec![0; len];
+            assert!(ChaCha20Poly1305Params::new(&nonce, &[]).is_err());
+        }
+
+        let aad = [1, 2, 3];
+        let params = ChaCha20Poly1305Params::new(&[0; 12], &aad).unwrap();
+        assert_eq!(params.aad(), aad);
+    }
+
+    #[test]
+    fn chacha20_poly1305_mechanism() {
+        let nonce = [4; 12];
+        let aad = [5; 20];
+        let mechanism = Mechanism::from(ChaCha20Poly1305Params::new(&nonce, &aad).unwrap());
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_CHACHA20_POLY1305);
+        assert_eq!(
+            raw.ulParameterLen as usize,
+            size_of::<CK_SALSA20_CHACHA20_POLY1305_PARAMS>()
+        );
+        let raw_params =
+            unsafe { &*(raw.pParameter as *const CK_SALSA20_CHACHA20_POLY1305_PARAMS) };
+        assert_eq!(raw_params.pNonce as *const u8, nonce.as_ptr());
+        assert_eq!(raw_params.ulNonceLen, 12);
+        assert_eq!(raw_params.pAAD as *const u8, aad.as_ptr());
+        assert_eq!(raw_params.ulAADLen, 20);
+    }
+}
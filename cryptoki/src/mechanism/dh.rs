@@ -0,0 +1,237 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Diffie-Hellman mechanism types
+
+use crate::object::Attribute;
+use crate::types::Ulong;
+use cryptoki_sys::*;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::ptr;
+use std::slice;
+
+/// PKCS #3 Diffie-Hellman domain parameters
+///
+/// As for DSA, the domain parameters are not passed to
+/// [`Mechanism::DhPkcsKeyPairGen`]: they are attributes of the public key
+/// template, see [`DhPkcsParams::attributes`].
+///
+/// [`Mechanism::DhPkcsKeyPairGen`]: super::Mechanism::DhPkcsKeyPairGen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DhPkcsParams<'a> {
+    prime: &'a [u8],
+    base: &'a [u8],
+}
+
+impl<'a> DhPkcsParams<'a> {
+    /// Construct PKCS #3 Diffie-Hellman domain parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prime` - The prime `p`, as a big-endian integer.
+    ///
+    /// * `base` - The base `g`, as a big-endian integer.
+    pub fn new(prime: &'a [u8], base: &'a [u8]) -> Self {
+        Self { prime, base }
+    }
+
+    /// The prime `p`.
+    pub fn prime(&self) -> &'a [u8] {
+        self.prime
+    }
+
+    /// The base `g`.
+    pub fn base(&self) -> &'a [u8] {
+        self.base
+    }
+
+    /// The attributes holding the domain parameters in a key template.
+    pub fn attributes(&self) -> [Attribute; 2] {
+        [
+            Attribute::Prime(self.prime.to_vec()),
+            Attribute::Base(self.base.to_vec()),
+        ]
+    }
+}
+
+/// PKCS #3 Diffie-Hellman key derivation parameters
+///
+/// The parameter of [`Mechanism::DhPkcsDerive`] is the public value of the
+/// other party, as a big-endian integer.  The bytes are passed to the token
+/// untouched: depending on the token, the leading zero bytes may have to be
+/// stripped, or kept so that the value is as long as the prime.
+///
+/// [`Mechanism::DhPkcsDerive`]: super::Mechanism::DhPkcsDerive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DhPkcsDeriveParams<'a> {
+    public_value: &'a [u8],
+}
+
+impl<'a> DhPkcsDeriveParams<'a> {
+    /// Construct PKCS #3 Diffie-Hellman key derivation parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_value` - The public value `g^y mod p` of the other party.
+    pub fn new(public_value: &'a [u8]) -> Self {
+        Self { public_value }
+    }
+
+    /// The public value of the other party.
+    pub fn public_value(&self) -> &'a [u8] {
+        self.public_value
+    }
+}
+
+/// X9.42 Diffie-Hellman key derivation function
+///
+/// The lifetime parameter represents the lifetime of the other info used by
+/// the KDF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct X942Kdf<'a> {
+    kdf_type: CK_X9_42_DH_KDF_TYPE,
+    other_info: Option<&'a [u8]>,
+}
+
+impl<'a> X942Kdf<'a> {
+    /// The null transformation, the derived key value being taken from the
+    /// left of the agreed value.
+    pub fn null() -> Self {
+        Self {
+            kdf_type: CKD_NULL,
+            other_info: None,
+        }
+    }
+
+    /// The ANSI X9.42 KDF based on SHA-1, with the DER-encoded
+    /// `OtherInfo` structure of X9.42.
+    pub fn sha1_asn1(other_info: &'a [u8]) -> Self {
+        Self::with_other_info(CKD_SHA1_KDF_ASN1, other_info)
+    }
+
+    /// The ANSI X9.42 KDF based on SHA-1, with the other info concatenated
+    /// to the agreed value.
+    pub fn sha1_concatenate(other_info: Option<&'a [u8]>) -> Self {
+        Self::with_other_info(CKD_SHA1_KDF_CONCATENATE, other_info.unwrap_or_default())
+    }
+
+    /// The ANSI X9.42 KDF based on SHA-256, with the DER-encoded
+    /// `OtherInfo` structure of X9.42.
+    pub fn sha256(other_info: &'a [u8]) -> Self {
+        Self::with_other_info(CKD_SHA256_KDF, other_info)
+    }
+
+    fn with_other_info(kdf_type: CK_X9_42_DH_KDF_TYPE, other_info: &'a [u8]) -> Self {
+        Self {
+            kdf_type,
+            // An empty slice is passed to the token as a null pointer
+            other_info: Some(other_info).filter(|data| !data.is_empty()),
+        }
+    }
+}
+
+/// X9.42 Diffie-Hellman key derivation parameters
+///
+/// This structure wraps a `CK_X9_42_DH1_DERIVE_PARAMS` structure.
+#[derive(Copy, Debug, Clone)]
+#[repr(C)]
+pub struct X942Dh1DeriveParams<'a> {
+    /// Key derivation function
+    kdf: CK_X9_42_DH_KDF_TYPE,
+    /// Length of the optional other info
+    other_info_len: Ulong,
+    /// Address of the optional other info or `std::ptr::null()`
+    other_info: *const u8,
+    /// Length of the other party's public value
+    public_data_len: Ulong,
+    /// Pointer to the other party's public value
+    public_data: *const u8,
+    /// Marker type to ensure we don't outlive the other info and public data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> X942Dh1DeriveParams<'a> {
+    /// Construct X9.42 Diffie-Hellman derivation parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `kdf` - The key derivation function to use.
+    ///
+    /// * `public_data` - The public value of the other party, passed to the
+    ///   token untouched.
+    pub fn new(kdf: X942Kdf<'a>, public_data: &'a [u8]) -> Self {
+        Self {
+            kdf: kdf.kdf_type,
+            other_info_len: kdf
+                .other_info
+                .map_or(0, <[u8]>::len)
+                .try_into()
+                .expect("usize can not fit in CK_ULONG"),
+            other_info: kdf.other_info.map_or(ptr::null(), <[u8]>::as_ptr),
+            public_data_len: public_data
+                .len()
+                .try_into()
+                .expect("usize can not fit in CK_ULONG"),
+            public_data: public_data.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The other info used by the key derivation function, if any.
+    pub fn other_info(&self) -> Option<&'a [u8]> {
+        if self.other_info.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null other info pointer
+            // always comes from a &'a [u8]
+            Some(unsafe { slice::from_raw_parts(self.other_info, *self.other_info_len as _) })
+        }
+    }
+
+    /// The public value of the other party, as passed to the constructor.
+    pub fn public_data(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the public data always comes from a
+        // &'a [u8]
+        unsafe { slice::from_raw_parts(self.public_data, *self.public_data_len as _) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mechanism::Mechanism;
+    use cryptoki_sys::{CKM_DH_PKCS_DERIVE, CK_MECHANISM};
+
+    #[test]
+    fn derive_parameter_is_public_value() {
+        let public_value = [0x5a; 256];
+        let mechanism = Mechanism::DhPkcsDerive(DhPkcsDeriveParams::new(&public_value));
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_DH_PKCS_DERIVE);
+        assert_eq!(raw.pParameter as *const u8, public_value.as_ptr());
+        assert_eq!(raw.ulParameterLen, 256);
+    }
+
+    #[test]
+    fn x942_dh1_derive_params() {
+        let public_data = [0x11; 128];
+        let params = X942Dh1DeriveParams::new(X942Kdf::null(), &public_data);
+        assert!(params.other_info().is_none());
+        assert_eq!(params.public_data(), public_data);
+
+        let other_info = [0x30, 0x03, 0x02, 0x01, 0x01];
+        let params = X942Dh1DeriveParams::new(X942Kdf::sha1_asn1(&other_info), &public_data);
+        assert_eq!(params.other_info(), Some(&other_info[..]));
+
+        // The layout is the one of CK_X9_42_DH1_DERIVE_PARAMS
+        let raw: CK_X9_42_DH1_DERIVE_PARAMS = unsafe { std::mem::transmute(params) };
+        assert_eq!(raw.kdf, CKD_SHA1_KDF_ASN1);
+        assert_eq!(raw.ulOtherInfoLen, 5);
+        assert_eq!(raw.pOtherInfo as *const u8, other_info.as_ptr());
+        assert_eq!(raw.ulPublicDataLen, 128);
+        assert_eq!(raw.pPublicData as *const u8, public_data.as_ptr());
+
+        let params = X942Dh1DeriveParams::new(X942Kdf::sha1_concatenate(None), &public_data);
+        assert!(params.other_info().is_none());
+    }
+}
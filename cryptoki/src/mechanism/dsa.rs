@@ -0,0 +1,85 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! DSA mechanism types
+
+use crate::object::Attribute;
+
+/// DSA domain parameters
+///
+/// Unlike the parameters of other mechanisms, the domain parameters are not
+/// passed to the DSA mechanisms: they are attributes of the keys.  When
+/// generating a key pair with [`Mechanism::DsaKeyPairGen`], they are part of
+/// the public key template, see [`DsaParams::attributes`].
+///
+/// New domain parameters are generated by
+/// [`Session::generate_dsa_params`](crate::session::Session::generate_dsa_params).
+///
+/// [`Mechanism::DsaKeyPairGen`]: super::Mechanism::DsaKeyPairGen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DsaParams {
+    prime: Vec<u8>,
+    subprime: Vec<u8>,
+    base: Vec<u8>,
+}
+
+impl DsaParams {
+    /// Construct DSA domain parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prime` - The prime `p`, as a big-endian integer.
+    ///
+    /// * `subprime` - The subprime `q`, as a big-endian integer.
+    ///
+    /// * `base` - The base `g`, as a big-endian integer.
+    pub fn new(prime: &[u8], subprime: &[u8], base: &[u8]) -> Self {
+        Self {
+            prime: prime.to_vec(),
+            subprime: subprime.to_vec(),
+            base: base.to_vec(),
+        }
+    }
+
+    /// The prime `p`.
+    pub fn prime(&self) -> &[u8] {
+        &self.prime
+    }
+
+    /// The subprime `q`.
+    pub fn subprime(&self) -> &[u8] {
+        &self.subprime
+    }
+
+    /// The base `g`.
+    pub fn base(&self) -> &[u8] {
+        &self.base
+    }
+
+    /// The attributes holding the domain parameters in a key template.
+    pub fn attributes(&self) -> [Attribute; 3] {
+        [
+            Attribute::Prime(self.prime.clone()),
+            Attribute::Subprime(self.subprime.clone()),
+            Attribute::Base(self.base.clone()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cryptoki_sys::{CKA_BASE, CKA_PRIME, CKA_SUBPRIME, CK_ATTRIBUTE};
+
+    #[test]
+    fn domain_parameter_attributes() {
+        let params = DsaParams::new(&[1; 128], &[2; 20], &[3; 128]);
+        let attributes = params.attributes();
+        let raw: Vec<CK_ATTRIBUTE> = attributes.iter().map(CK_ATTRIBUTE::from).collect();
+        assert_eq!(raw[0].type_, CKA_PRIME);
+        assert_eq!(raw[0].ulValueLen, 128);
+        assert_eq!(raw[1].type_, CKA_SUBPRIME);
+        assert_eq!(raw[1].ulValueLen, 20);
+        assert_eq!(raw[2].type_, CKA_BASE);
+        assert_eq!(raw[2].ulValueLen, 128);
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! EdDSA mechanism types
+
+use super::Mechanism;
+use crate::error::{Error, Result};
+use cryptoki_sys::*;
+use log::error;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::slice;
+
+/// DER encoding of the Ed25519 curve OID (1.3.101.112), as used in the
+/// [`EcParams`](crate::object::Attribute::EcParams) attribute of Edwards keys.
+pub const ED25519_OID: [u8; 5] = [0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// DER encoding of the Ed448 curve OID (1.3.101.113), as used in the
+/// [`EcParams`](crate::object::Attribute::EcParams) attribute of Edwards keys.
+pub const ED448_OID: [u8; 5] = [0x06, 0x03, 0x2b, 0x65, 0x71];
+
+/// EdDSA parameters.
+///
+/// Without parameters, the EdDSA mechanism signs with Ed25519 or Ed448 in
+/// "pure" mode, depending on the curve of the key.  The parameters select the
+/// prehash variants (Ed25519ph, Ed448ph) and may carry a context string, as
+/// used by Ed25519ctx and Ed448.
+///
+/// This structure wraps a `CK_EDDSA_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct EddsaParams<'a> {
+    inner: CK_EDDSA_PARAMS,
+    /// Marker type to ensure we don't outlive the context data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> EddsaParams<'a> {
+    /// Construct EdDSA parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `ph_flag` - Whether to use the prehash variant of the algorithm.
+    ///
+    /// * `context` - The context string, if any.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `context` is longer
+    /// than 255 bytes, the limit of RFC 8032.
+    pub fn new(ph_flag: bool, context: Option<&'a [u8]>) -> Result<Self> {
+        let context_len = context.map_or(0, <[u8]>::len);
+        if context_len > 255 {
+            error!(
+                "The EdDSA context is {} bytes long, more than 255 bytes",
+                context_len
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_EDDSA_PARAMS {
+                phFlag: ph_flag.into(),
+                ulContextDataLen: context_len.try_into()?,
+                pContextData: context.map_or(null_mut(), |context| context.as_ptr() as *mut _),
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// Whether the prehash variant of the algorithm is used.
+    pub fn ph_flag(&self) -> bool {
+        self.inner.phFlag != 0
+    }
+
+    /// The context string, if any.
+    pub fn context(&self) -> Option<&'a [u8]> {
+        if self.inner.pContextData.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null context always comes
+            // from a &'a [u8]
+            Some(unsafe {
+                slice::from_raw_parts(self.inner.pContextData, self.inner.ulContextDataLen as _)
+            })
+        }
+    }
+}
+
+impl<'a> From<EddsaParams<'a>> for Mechanism<'a> {
+    fn from(params: EddsaParams<'a>) -> Self {
+        Mechanism::EddsaWithParams(params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn eddsa_params() {
+        let params = EddsaParams::new(true, None).unwrap();
+        assert!(params.ph_flag());
+        assert!(params.context().is_none());
+        assert!(params.inner.pContextData.is_null());
+        assert_eq!(params.inner.ulContextDataLen, 0);
+
+        let context = [1, 2, 3];
+        let mechanism: Mechanism = EddsaParams::new(false, Some(&context)).unwrap().into();
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_EDDSA);
+        assert_eq!(raw.ulParameterLen as usize, size_of::<CK_EDDSA_PARAMS>());
+        let raw_params = unsafe { &*(raw.pParameter as *const CK_EDDSA_PARAMS) };
+        assert_eq!(raw_params.phFlag, CK_FALSE);
+        assert_eq!(raw_params.ulContextDataLen, 3);
+        assert_eq!(raw_params.pContextData as *const u8, context.as_ptr());
+
+        assert!(EddsaParams::new(false, Some(&[0; 255])).is_ok());
+        assert!(matches!(
+            EddsaParams::new(false, Some(&[0; 256])),
+            Err(Error::InvalidValue)
+        ));
+    }
+}
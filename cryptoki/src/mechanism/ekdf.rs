@@ -3,8 +3,25 @@
 //! Mechanisms of key derivation by data encryption
 //! See: <https://docs.oasis-open.org/pkcs11/pkcs11-spec/v3.1/os/pkcs11-spec-v3.1-os.html#_Toc111203514>
 
+use crate::error::{Error, Result};
+use crate::mechanism::simple_kdf::KeyDerivationStringData;
+use log::error;
 use std::{convert::TryInto, marker::PhantomData, slice};
 
+// As no padding is applied, the data to encrypt must be a non-zero multiple
+// of the AES block size
+fn check_block_multiple(data: &[u8], mode: &str) -> Result<()> {
+    if data.is_empty() || data.len() % 16 != 0 {
+        error!(
+            "AES {} derivation data must be a multiple of 16 bytes long, not {}",
+            mode,
+            data.len()
+        );
+        return Err(Error::InvalidValue);
+    }
+    Ok(())
+}
+
 /// AES CBC derivation parameters.
 ///
 /// The mechanisms will function by performing the encryption over the data provided using the base
@@ -28,19 +45,23 @@ impl<'a> AesCbcDeriveParams<'a> {
     /// * `iv` - The initialization vector
     ///
     /// * `data` - Data that will be encryption with the base key to obtain
-    ///   the new key from the resulted cypher.
-    pub fn new(iv: [u8; 16], data: &'a [u8]) -> Self {
-        Self {
+    ///   the new key from the resulted cypher.  As no padding is applied, its
+    ///   length must be a non-zero multiple of the 16-byte AES block size.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if the length of `data`
+    /// is not a multiple of the block size.
+    pub fn new(iv: [u8; 16], data: &'a [u8]) -> Result<Self> {
+        check_block_multiple(data, "CBC")?;
+        Ok(Self {
             inner: cryptoki_sys::CK_AES_CBC_ENCRYPT_DATA_PARAMS {
                 iv,
                 pData: data.as_ptr() as *mut _,
-                length: data
-                    .len()
-                    .try_into()
-                    .expect("data length does not fit in CK_ULONG"),
+                length: data.len().try_into()?,
             },
             _marker: PhantomData,
-        }
+        })
     }
 
     /// The initialization vector.
@@ -53,3 +74,67 @@ impl<'a> AesCbcDeriveParams<'a> {
         unsafe { slice::from_raw_parts(self.inner.pData, self.inner.length as _) }
     }
 }
+
+/// AES ECB derivation parameters.
+///
+/// The data is encrypted with the base key, and the resulting cipher text is
+/// the value of the derived key.
+///
+/// This structure wraps a [`KeyDerivationStringData`], whose data is a
+/// multiple of the block size.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct AesEcbDeriveParams<'a> {
+    inner: KeyDerivationStringData<'a>,
+}
+
+impl<'a> AesEcbDeriveParams<'a> {
+    /// Construct parameters for key derivation via AES ECB encryption.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Data that will be encrypted with the base key.  As no
+    ///   padding is applied, its length must be a non-zero multiple of the
+    ///   16-byte AES block size.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if the length of `data`
+    /// is not a multiple of the block size.
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        check_block_multiple(data, "ECB")?;
+        Ok(Self {
+            inner: KeyDerivationStringData::new(data)?,
+        })
+    }
+
+    /// The data.
+    pub fn data(&self) -> &'a [u8] {
+        self.inner.data()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aes_ecb_data_len() {
+        for len in [0, 8, 17, 33] {
+            assert!(AesEcbDeriveParams::new(&[0; 33][..len]).is_err());
+        }
+        let data = [1; 32];
+        assert_eq!(AesEcbDeriveParams::new(&data).unwrap().data(), data);
+    }
+
+    #[test]
+    fn aes_cbc_data_len() {
+        for len in [0, 8, 17, 33] {
+            assert!(AesCbcDeriveParams::new([0; 16], &[0; 33][..len]).is_err());
+        }
+        let data = [1; 48];
+        let params = AesCbcDeriveParams::new([2; 16], &data).unwrap();
+        assert_eq!(params.data(), data);
+        assert_eq!(params.iv(), [2; 16]);
+    }
+}
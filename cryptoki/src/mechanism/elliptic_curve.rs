@@ -1,10 +1,31 @@
 //! ECDH mechanism types
 
+use crate::error::{Error, Result};
 use crate::types::Ulong;
 use cryptoki_sys::*;
+use log::error;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::ptr;
+use std::slice;
+
+/// DER encoding of the X25519 curve OID (1.3.101.110), as used in the
+/// [`EcParams`](crate::object::Attribute::EcParams) attribute of Montgomery
+/// keys.
+pub const X25519_OID: [u8; 5] = [0x06, 0x03, 0x2b, 0x65, 0x6e];
+
+/// DER encoding of the X448 curve OID (1.3.101.111), as used in the
+/// [`EcParams`](crate::object::Attribute::EcParams) attribute of Montgomery
+/// keys.
+pub const X448_OID: [u8; 5] = [0x06, 0x03, 0x2b, 0x65, 0x6f];
+
+/// DER encoding of the SM2 curve OID (1.2.156.10197.1.301), as used in the
+/// [`EcParams`](crate::object::Attribute::EcParams) attribute of SM2 keys.
+///
+/// The SM2 mechanisms and key type are not part of PKCS #11, see
+/// [`MechanismType::new_vendor_defined`](super::MechanismType::new_vendor_defined)
+/// and [`KeyType::new_vendor_defined`](crate::object::KeyType::new_vendor_defined).
+pub const SM2_OID: [u8; 10] = [0x06, 0x08, 0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x82, 0x2d];
 
 /// ECDH derivation parameters.
 ///
@@ -48,7 +69,8 @@ impl<'a> Ecdh1DeriveParams<'a> {
     ///   X9.62) i.e. the same as a `CKA_EC_POINT` encoding.  The calling
     ///   application is responsible for converting the offered public key to the
     ///   compressed or uncompressed forms of these encodings if the token does
-    ///   not support the offered form.
+    ///   not support the offered form.  For Montgomery keys, i.e. X25519 and
+    ///   X448, this is the raw public value of RFC 7748, of 32 and 56 bytes.
     pub fn new(kdf: EcKdf<'a>, public_data: &'a [u8]) -> Self {
         Self {
             kdf: kdf.kdf_type,
@@ -66,6 +88,94 @@ impl<'a> Ecdh1DeriveParams<'a> {
             _marker: PhantomData,
         }
     }
+
+    /// The shared data used by the key derivation function, if any.
+    pub fn shared_data(&self) -> Option<&'a [u8]> {
+        if self.shared_data.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null shared data pointer
+            // always comes from a &'a [u8]
+            Some(unsafe { slice::from_raw_parts(self.shared_data, *self.shared_data_len as _) })
+        }
+    }
+
+    /// The other party's public key, as passed to the constructor.
+    pub fn public_data(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the public data always comes from a
+        // &'a [u8]
+        unsafe { slice::from_raw_parts(self.public_data, *self.public_data_len as _) }
+    }
+}
+
+/// ECDH AES key wrap parameters.
+///
+/// The key is wrapped with AES key wrap with padding, using a temporary AES
+/// key derived from an ephemeral EC key pair and the wrapping, or
+/// unwrapping, EC key.
+///
+/// This structure wraps a `CK_ECDH_AES_KEY_WRAP_PARAMS` structure.
+#[derive(Copy, Debug, Clone)]
+#[repr(C)]
+pub struct EcdhAesKeyWrapParams<'a> {
+    /// Length of the temporary AES key in bits
+    aes_key_bits: Ulong,
+    /// Key derivation function
+    kdf: CK_EC_KDF_TYPE,
+    /// Length of the optional shared data used by some of the key
+    /// derivation functions
+    shared_data_len: Ulong,
+    /// Address of the optional data or `std::ptr::null()` of there is
+    /// no shared data
+    shared_data: *const u8,
+    /// Marker type to ensure we don't outlive shared data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> EcdhAesKeyWrapParams<'a> {
+    /// Construct ECDH AES key wrap parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `aes_key_bits` - The length of the temporary AES key in bits, 128,
+    ///   192 or 256.
+    ///
+    /// * `kdf` - The key derivation function used to derive the temporary AES
+    ///   key.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `aes_key_bits` is not
+    /// a valid AES key length.
+    pub fn new(aes_key_bits: u64, kdf: EcKdf<'a>) -> Result<Self> {
+        if !matches!(aes_key_bits, 128 | 192 | 256) {
+            error!("{} is not a valid AES key length in bits", aes_key_bits);
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            aes_key_bits: aes_key_bits.into(),
+            kdf: kdf.kdf_type,
+            shared_data_len: kdf.shared_data.map_or(0, <[u8]>::len).try_into()?,
+            shared_data: kdf.shared_data.map_or(ptr::null(), <[u8]>::as_ptr),
+            _marker: PhantomData,
+        })
+    }
+
+    /// The length of the temporary AES key in bits.
+    pub fn aes_key_bits(&self) -> Ulong {
+        self.aes_key_bits
+    }
+
+    /// The shared data used by the key derivation function, if any.
+    pub fn shared_data(&self) -> Option<&'a [u8]> {
+        if self.shared_data.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null shared data pointer
+            // always comes from a &'a [u8]
+            Some(unsafe { slice::from_raw_parts(self.shared_data, *self.shared_data_len as _) })
+        }
+    }
 }
 
 /// Key Derivation Function applied to derive keying data from a shared secret.
@@ -73,9 +183,8 @@ impl<'a> Ecdh1DeriveParams<'a> {
 /// The key derivation function will be used by the EC key agreement schemes.
 ///
 /// The lifetime parameter represents the lifetime of the shared data used by
-/// the KDF.  In the current version of this crate, only the null KDF is
-/// supported, which takes no shared data.  Therefore `'a` can always be inferred
-/// `'static`.
+/// the KDF.  The null KDF takes no shared data, so for it `'a` can always be
+/// inferred `'static`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EcKdf<'a> {
     kdf_type: CK_EC_KDF_TYPE,
@@ -93,7 +202,61 @@ impl<'a> EcKdf<'a> {
         }
     }
 
-    // The intention here is to be able to support other methods with
-    // shared data, without it being a breaking change, by just adding
-    // additional constructors here.
+    /// The ANSI X9.63 KDF based on SHA-1, with optional shared data.
+    pub fn sha1(shared_data: Option<&'a [u8]>) -> Self {
+        Self::with_shared_data(CKD_SHA1_KDF, shared_data)
+    }
+
+    /// The ANSI X9.63 KDF based on SHA-224, with optional shared data.
+    pub fn sha224(shared_data: Option<&'a [u8]>) -> Self {
+        Self::with_shared_data(CKD_SHA224_KDF, shared_data)
+    }
+
+    /// The ANSI X9.63 KDF based on SHA-256, with optional shared data.
+    pub fn sha256(shared_data: Option<&'a [u8]>) -> Self {
+        Self::with_shared_data(CKD_SHA256_KDF, shared_data)
+    }
+
+    /// The ANSI X9.63 KDF based on SHA-384, with optional shared data.
+    pub fn sha384(shared_data: Option<&'a [u8]>) -> Self {
+        Self::with_shared_data(CKD_SHA384_KDF, shared_data)
+    }
+
+    /// The ANSI X9.63 KDF based on SHA-512, with optional shared data.
+    pub fn sha512(shared_data: Option<&'a [u8]>) -> Self {
+        Self::with_shared_data(CKD_SHA512_KDF, shared_data)
+    }
+
+    fn with_shared_data(kdf_type: CK_EC_KDF_TYPE, shared_data: Option<&'a [u8]>) -> Self {
+        Self {
+            kdf_type,
+            // An empty slice is passed to the token as a null pointer
+            shared_data: shared_data.filter(|data| !data.is_empty()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ecdh_aes_key_wrap_params() {
+        assert!(EcdhAesKeyWrapParams::new(64, EcKdf::null()).is_err());
+
+        let params = EcdhAesKeyWrapParams::new(128, EcKdf::sha256(Some(&[]))).unwrap();
+        assert_eq!(*params.aes_key_bits(), 128);
+        assert!(params.shared_data().is_none());
+
+        let shared_data = [1, 2, 3];
+        let params = EcdhAesKeyWrapParams::new(256, EcKdf::sha256(Some(&shared_data))).unwrap();
+        assert_eq!(params.shared_data(), Some(&shared_data[..]));
+
+        // The layout is the one of CK_ECDH_AES_KEY_WRAP_PARAMS
+        let raw: CK_ECDH_AES_KEY_WRAP_PARAMS = unsafe { std::mem::transmute(params) };
+        assert_eq!(raw.ulAESKeyBits, 256);
+        assert_eq!(raw.kdf, CKD_SHA256_KDF);
+        assert_eq!(raw.ulSharedDataLen, 3);
+        assert_eq!(raw.pSharedData as *const u8, shared_data.as_ptr());
+    }
 }
@@ -0,0 +1,117 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! GOST mechanism types
+
+use crate::object::Attribute;
+
+/// GOST R 34.10 parameter sets
+///
+/// A GOST R 34.10 key pair is generated on the curve given by the
+/// [`Attribute::GostR3410Params`] attribute of the public key template, which
+/// is the DER-encoding of the OID of the parameter set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GostR3410ParamSet {
+    /// id-GostR3410-2001-CryptoPro-A-ParamSet
+    CryptoProA,
+    /// id-GostR3410-2001-CryptoPro-B-ParamSet
+    CryptoProB,
+    /// id-GostR3410-2001-CryptoPro-C-ParamSet
+    CryptoProC,
+    /// id-GostR3410-2001-CryptoPro-XchA-ParamSet
+    CryptoProXchA,
+    /// id-GostR3410-2001-CryptoPro-XchB-ParamSet
+    CryptoProXchB,
+    /// id-tc26-gost-3410-12-256-paramSetA
+    Tc26ParamSet256A,
+    /// id-tc26-gost-3410-12-256-paramSetB
+    Tc26ParamSet256B,
+    /// id-tc26-gost-3410-12-256-paramSetC
+    Tc26ParamSet256C,
+    /// id-tc26-gost-3410-12-256-paramSetD
+    Tc26ParamSet256D,
+    /// id-tc26-gost-3410-12-512-paramSetA
+    Tc26ParamSet512A,
+    /// id-tc26-gost-3410-12-512-paramSetB
+    Tc26ParamSet512B,
+    /// id-tc26-gost-3410-12-512-paramSetC
+    Tc26ParamSet512C,
+}
+
+impl GostR3410ParamSet {
+    /// The DER-encoding of the OID of the parameter set
+    pub fn oid(&self) -> &'static [u8] {
+        match self {
+            // 1.2.643.2.2.35.1
+            GostR3410ParamSet::CryptoProA => {
+                &[0x06, 0x07, 0x2A, 0x85, 0x03, 0x02, 0x02, 0x23, 0x01]
+            }
+            // 1.2.643.2.2.35.2
+            GostR3410ParamSet::CryptoProB => {
+                &[0x06, 0x07, 0x2A, 0x85, 0x03, 0x02, 0x02, 0x23, 0x02]
+            }
+            // 1.2.643.2.2.35.3
+            GostR3410ParamSet::CryptoProC => {
+                &[0x06, 0x07, 0x2A, 0x85, 0x03, 0x02, 0x02, 0x23, 0x03]
+            }
+            // 1.2.643.2.2.36.0
+            GostR3410ParamSet::CryptoProXchA => {
+                &[0x06, 0x07, 0x2A, 0x85, 0x03, 0x02, 0x02, 0x24, 0x00]
+            }
+            // 1.2.643.2.2.36.1
+            GostR3410ParamSet::CryptoProXchB => {
+                &[0x06, 0x07, 0x2A, 0x85, 0x03, 0x02, 0x02, 0x24, 0x01]
+            }
+            // 1.2.643.7.1.2.1.1.1
+            GostR3410ParamSet::Tc26ParamSet256A => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x01, 0x01,
+            ],
+            // 1.2.643.7.1.2.1.1.2
+            GostR3410ParamSet::Tc26ParamSet256B => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x01, 0x02,
+            ],
+            // 1.2.643.7.1.2.1.1.3
+            GostR3410ParamSet::Tc26ParamSet256C => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x01, 0x03,
+            ],
+            // 1.2.643.7.1.2.1.1.4
+            GostR3410ParamSet::Tc26ParamSet256D => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x01, 0x04,
+            ],
+            // 1.2.643.7.1.2.1.2.1
+            GostR3410ParamSet::Tc26ParamSet512A => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x02, 0x01,
+            ],
+            // 1.2.643.7.1.2.1.2.2
+            GostR3410ParamSet::Tc26ParamSet512B => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x02, 0x02,
+            ],
+            // 1.2.643.7.1.2.1.2.3
+            GostR3410ParamSet::Tc26ParamSet512C => &[
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x02, 0x03,
+            ],
+        }
+    }
+}
+
+impl From<GostR3410ParamSet> for Attribute {
+    fn from(param_set: GostR3410ParamSet) -> Self {
+        Attribute::GostR3410Params(param_set.oid().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn param_set_attribute() {
+        let attribute = Attribute::from(GostR3410ParamSet::Tc26ParamSet256A);
+        assert_eq!(
+            attribute,
+            Attribute::GostR3410Params(vec![
+                0x06, 0x09, 0x2A, 0x85, 0x03, 0x07, 0x01, 0x02, 0x01, 0x01, 0x01
+            ])
+        );
+    }
+}
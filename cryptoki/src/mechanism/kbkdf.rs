@@ -0,0 +1,442 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Mechanisms of NIST SP 800-108 key-based key derivation functions (KBKDF)
+//! See: <https://docs.oasis-open.org/pkcs11/pkcs11-curr/v3.0/os/pkcs11-curr-v3.0-os.html#_Toc30061446>
+
+use crate::error::{Error, Result};
+use crate::object::{Attribute, ObjectHandle};
+use cryptoki_sys::*;
+use log::error;
+use std::cell::Cell;
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr::null_mut;
+
+use super::MechanismType;
+
+/// Encoding of a counter in the PRF input.
+///
+/// This structure wraps a `CK_SP800_108_COUNTER_FORMAT` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct KbkdfCounterFormat {
+    inner: CK_SP800_108_COUNTER_FORMAT,
+}
+
+impl KbkdfCounterFormat {
+    /// Construct a counter format.
+    ///
+    /// # Arguments
+    ///
+    /// * `little_endian` - Whether the counter is encoded in little-endian
+    ///   rather than big-endian order.
+    ///
+    /// * `width_in_bits` - The width of the counter, one of 8, 16, 24 or 32.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `width_in_bits` is not
+    /// one of the values listed above.
+    pub fn new(little_endian: bool, width_in_bits: u64) -> Result<Self> {
+        if !matches!(width_in_bits, 8 | 16 | 24 | 32) {
+            error!(
+                "KBKDF counter width must be 8, 16, 24 or 32 bits, not {}",
+                width_in_bits
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_SP800_108_COUNTER_FORMAT {
+                bLittleEndian: little_endian.into(),
+                ulWidthInBits: usize::try_from(width_in_bits)?.try_into()?,
+            },
+        })
+    }
+}
+
+/// Length of the derived keying material (DKM) encoded in the PRF input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DkmLengthMethod {
+    /// CK_SP800_108_DKM_LENGTH_SUM_OF_KEYS the sum of the lengths of all the
+    /// derived keys.
+    SumOfKeys,
+    /// CK_SP800_108_DKM_LENGTH_SUM_OF_SEGMENTS the sum of the lengths of all
+    /// the PRF output segments needed to derive the keys.
+    SumOfSegments,
+}
+
+/// Encoding of the DKM length in the PRF input.
+///
+/// This structure wraps a `CK_SP800_108_DKM_LENGTH_FORMAT` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct KbkdfDkmLengthFormat {
+    inner: CK_SP800_108_DKM_LENGTH_FORMAT,
+}
+
+impl KbkdfDkmLengthFormat {
+    /// Construct a DKM length format.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - How the DKM length is computed.
+    ///
+    /// * `little_endian` - Whether the length is encoded in little-endian
+    ///   rather than big-endian order.
+    ///
+    /// * `width_in_bits` - The width of the encoded length, a multiple of 8
+    ///   between 8 and 64.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `width_in_bits` is out
+    /// of range.
+    pub fn new(method: DkmLengthMethod, little_endian: bool, width_in_bits: u64) -> Result<Self> {
+        if width_in_bits % 8 != 0 || !(8..=64).contains(&width_in_bits) {
+            error!(
+                "KBKDF DKM length width must be a multiple of 8 between 8 and 64 bits, not {}",
+                width_in_bits
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_SP800_108_DKM_LENGTH_FORMAT {
+                dkmLengthMethod: match method {
+                    DkmLengthMethod::SumOfKeys => CK_SP800_108_DKM_LENGTH_SUM_OF_KEYS,
+                    DkmLengthMethod::SumOfSegments => CK_SP800_108_DKM_LENGTH_SUM_OF_SEGMENTS,
+                },
+                bLittleEndian: little_endian.into(),
+                ulWidthInBits: usize::try_from(width_in_bits)?.try_into()?,
+            },
+        })
+    }
+}
+
+/// A segment of the PRF input.
+#[derive(Debug, Clone, Copy)]
+pub enum PrfDataParamType<'a> {
+    /// CK_SP800_108_ITERATION_VARIABLE the iteration variable.  In counter
+    /// mode, this is the counter and its format must be given.  In feedback
+    /// and double-pipeline mode, this is the output of the previous iteration
+    /// and no format must be given.
+    IterationVariable(Option<&'a KbkdfCounterFormat>),
+    /// CK_SP800_108_OPTIONAL_COUNTER an additional counter, only allowed in
+    /// feedback and double-pipeline mode.
+    Counter(&'a KbkdfCounterFormat),
+    /// CK_SP800_108_DKM_LENGTH the length of the derived keying material.
+    DkmLength(&'a KbkdfDkmLengthFormat),
+    /// CK_SP800_108_BYTE_ARRAY fixed data, such as a label or a context.
+    ByteArray(&'a [u8]),
+}
+
+/// A segment of the PRF input.
+///
+/// This structure wraps a `CK_PRF_DATA_PARAM` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct PrfDataParam<'a> {
+    inner: CK_PRF_DATA_PARAM,
+    /// Marker type to ensure we don't outlive the data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> PrfDataParam<'a> {
+    /// Construct a segment of the PRF input.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of a byte array does not fit into a
+    /// `CK_ULONG`.
+    pub fn new(type_: PrfDataParamType<'a>) -> Self {
+        let (type_, value, len) = match type_ {
+            PrfDataParamType::IterationVariable(None) => {
+                (CK_SP800_108_ITERATION_VARIABLE, null_mut(), 0)
+            }
+            PrfDataParamType::IterationVariable(Some(format)) => (
+                CK_SP800_108_ITERATION_VARIABLE,
+                format as *const _ as *mut _,
+                size_of::<CK_SP800_108_COUNTER_FORMAT>(),
+            ),
+            PrfDataParamType::Counter(format) => (
+                CK_SP800_108_OPTIONAL_COUNTER,
+                format as *const _ as *mut _,
+                size_of::<CK_SP800_108_COUNTER_FORMAT>(),
+            ),
+            PrfDataParamType::DkmLength(format) => (
+                CK_SP800_108_DKM_LENGTH,
+                format as *const _ as *mut _,
+                size_of::<CK_SP800_108_DKM_LENGTH_FORMAT>(),
+            ),
+            PrfDataParamType::ByteArray(data) => {
+                (CK_SP800_108_BYTE_ARRAY, data.as_ptr() as *mut _, data.len())
+            }
+        };
+        Self {
+            inner: CK_PRF_DATA_PARAM {
+                type_,
+                pValue: value,
+                ulValueLen: len.try_into().expect("usize can not fit in CK_ULONG"),
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Additional keys derived by a KBKDF mechanism, besides the key returned by
+/// [`Session::derive_key`](crate::session::Session::derive_key).
+///
+/// The keys are derived from the keying material that follows the material of
+/// the main key, in order.  Once the key derivation succeeded, their handles
+/// can be read from [`DerivedKeys::handles`].
+#[derive(Debug)]
+pub struct DerivedKeys<'a> {
+    keys: Vec<CK_DERIVED_KEY>,
+    // The CK_DERIVED_KEY structures point to these buffers, which do not move
+    // when the structure moves.
+    _templates: Vec<Vec<CK_ATTRIBUTE>>,
+    handles: Box<[Cell<CK_OBJECT_HANDLE>]>,
+    /// Marker type to ensure we don't outlive the templates
+    _marker: PhantomData<&'a [Attribute]>,
+}
+
+impl<'a> DerivedKeys<'a> {
+    /// Construct additional derived keys, one for each template.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::TryFromInt`] if the length of a
+    /// template does not fit into a `CK_ULONG`.
+    pub fn new(templates: &[&'a [Attribute]]) -> Result<Self> {
+        let mut templates: Vec<Vec<CK_ATTRIBUTE>> = templates
+            .iter()
+            .map(|template| template.iter().map(|attr| attr.into()).collect())
+            .collect();
+        let handles: Box<[Cell<CK_OBJECT_HANDLE>]> = templates
+            .iter()
+            .map(|_| Cell::new(CK_INVALID_HANDLE))
+            .collect();
+        let keys = templates
+            .iter_mut()
+            .zip(handles.iter())
+            .map(|(template, handle)| {
+                Ok(CK_DERIVED_KEY {
+                    pTemplate: template.as_mut_ptr(),
+                    ulAttributeCount: template.len().try_into()?,
+                    phKey: handle.as_ptr(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            keys,
+            _templates: templates,
+            handles,
+            _marker: PhantomData,
+        })
+    }
+
+    /// The handles of the derived keys, in the order of the templates.
+    ///
+    /// A handle is `None` until the key derivation succeeded.
+    pub fn handles(&self) -> Vec<Option<ObjectHandle>> {
+        self.handles
+            .iter()
+            .map(|handle| match handle.get() {
+                CK_INVALID_HANDLE => None,
+                handle => Some(ObjectHandle::new(handle)),
+            })
+            .collect()
+    }
+
+    fn len(&self) -> Result<CK_ULONG> {
+        Ok(self.keys.len().try_into()?)
+    }
+
+    fn as_ptr(&self) -> *mut CK_DERIVED_KEY {
+        if self.keys.is_empty() {
+            null_mut()
+        } else {
+            // The token only writes through the phKey pointers, which point to
+            // cells.
+            self.keys.as_ptr() as *mut _
+        }
+    }
+}
+
+fn additional_keys(keys: Option<&DerivedKeys>) -> Result<(CK_ULONG, *mut CK_DERIVED_KEY)> {
+    match keys {
+        Some(keys) => Ok((keys.len()?, keys.as_ptr())),
+        None => Ok((0, null_mut())),
+    }
+}
+
+/// Parameters of the KBKDF in counter or double-pipeline mode.
+///
+/// This structure wraps a `CK_SP800_108_KDF_PARAMS` structure, also known as
+/// `CK_SP800_108_DOUBLE_PIPELINE_KDF_PARAMS`.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct KbkdfParams<'a> {
+    inner: CK_SP800_108_KDF_PARAMS,
+    /// Marker type to ensure we don't outlive the data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> KbkdfParams<'a> {
+    /// Construct KBKDF parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf` - The pseudo-random function, an HMAC or CMAC mechanism such as
+    ///   [`MechanismType::SHA256_HMAC`] or [`MechanismType::AES_CMAC`].
+    ///
+    /// * `data_params` - The segments of the PRF input, in order.
+    ///
+    /// * `additional_derived_keys` - The keys to derive besides the main key,
+    ///   if any.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::TryFromInt`] if a length does not fit
+    /// into a `CK_ULONG`.
+    pub fn new(
+        prf: MechanismType,
+        data_params: &'a [PrfDataParam<'a>],
+        additional_derived_keys: Option<&'a DerivedKeys<'a>>,
+    ) -> Result<Self> {
+        let (additional_keys_len, additional_keys) = additional_keys(additional_derived_keys)?;
+        Ok(Self {
+            inner: CK_SP800_108_KDF_PARAMS {
+                prfType: *prf,
+                ulNumberOfDataParams: data_params.len().try_into()?,
+                pDataParams: data_params.as_ptr() as *mut _,
+                ulAdditionalDerivedKeys: additional_keys_len,
+                pAdditionalDerivedKeys: additional_keys,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The pseudo-random function.
+    pub fn prf(&self) -> MechanismType {
+        MechanismType {
+            val: self.inner.prfType,
+        }
+    }
+}
+
+/// Parameters of the KBKDF in feedback mode.
+///
+/// This structure wraps a `CK_SP800_108_FEEDBACK_KDF_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct KbkdfFeedbackParams<'a> {
+    inner: CK_SP800_108_FEEDBACK_KDF_PARAMS,
+    /// Marker type to ensure we don't outlive the data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> KbkdfFeedbackParams<'a> {
+    /// Construct KBKDF parameters for feedback mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf` - The pseudo-random function, an HMAC or CMAC mechanism.
+    ///
+    /// * `data_params` - The segments of the PRF input, in order.
+    ///
+    /// * `iv` - The IV used as feedback in the first iteration, if any.
+    ///
+    /// * `additional_derived_keys` - The keys to derive besides the main key,
+    ///   if any.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::TryFromInt`] if a length does not fit
+    /// into a `CK_ULONG`.
+    pub fn new(
+        prf: MechanismType,
+        data_params: &'a [PrfDataParam<'a>],
+        iv: Option<&'a [u8]>,
+        additional_derived_keys: Option<&'a DerivedKeys<'a>>,
+    ) -> Result<Self> {
+        let (additional_keys_len, additional_keys) = additional_keys(additional_derived_keys)?;
+        Ok(Self {
+            inner: CK_SP800_108_FEEDBACK_KDF_PARAMS {
+                prfType: *prf,
+                ulNumberOfDataParams: data_params.len().try_into()?,
+                pDataParams: data_params.as_ptr() as *mut _,
+                ulIVLen: iv.map_or(0, <[u8]>::len).try_into()?,
+                pIV: iv.map_or(null_mut(), |iv| iv.as_ptr() as *mut _),
+                ulAdditionalDerivedKeys: additional_keys_len,
+                pAdditionalDerivedKeys: additional_keys,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The pseudo-random function.
+    pub fn prf(&self) -> MechanismType {
+        MechanismType {
+            val: self.inner.prfType,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counter_and_dkm_length_widths() {
+        assert!(KbkdfCounterFormat::new(false, 32).is_ok());
+        assert!(KbkdfCounterFormat::new(true, 12).is_err());
+        assert!(KbkdfCounterFormat::new(false, 64).is_err());
+
+        assert!(KbkdfDkmLengthFormat::new(DkmLengthMethod::SumOfKeys, false, 64).is_ok());
+        assert!(KbkdfDkmLengthFormat::new(DkmLengthMethod::SumOfKeys, false, 0).is_err());
+        assert!(KbkdfDkmLengthFormat::new(DkmLengthMethod::SumOfSegments, false, 20).is_err());
+        assert!(KbkdfDkmLengthFormat::new(DkmLengthMethod::SumOfSegments, false, 72).is_err());
+    }
+
+    #[test]
+    fn data_params() {
+        let counter = KbkdfCounterFormat::new(false, 16).unwrap();
+        let param = PrfDataParam::new(PrfDataParamType::IterationVariable(Some(&counter)));
+        assert_eq!(param.inner.type_, CK_SP800_108_ITERATION_VARIABLE);
+        assert_eq!(param.inner.pValue as *const _, &counter as *const _ as _);
+        assert_eq!(
+            param.inner.ulValueLen as usize,
+            size_of::<CK_SP800_108_COUNTER_FORMAT>()
+        );
+
+        let param = PrfDataParam::new(PrfDataParamType::IterationVariable(None));
+        assert!(param.inner.pValue.is_null());
+        assert_eq!(param.inner.ulValueLen, 0);
+
+        let label = b"label";
+        let param = PrfDataParam::new(PrfDataParamType::ByteArray(label));
+        assert_eq!(param.inner.type_, CK_SP800_108_BYTE_ARRAY);
+        assert_eq!(param.inner.ulValueLen, 5);
+    }
+
+    #[test]
+    fn derived_keys() {
+        let template = [Attribute::Token(false)];
+        let keys = DerivedKeys::new(&[&template, &template]).unwrap();
+        assert_eq!(keys.handles(), [None, None]);
+
+        // the token writes the handles through the pointers
+        let ph_key = keys.keys[1].phKey;
+        unsafe { *ph_key = 42 };
+        assert_eq!(keys.handles(), [None, Some(ObjectHandle::new(42))]);
+
+        let params = KbkdfParams::new(MechanismType::SHA256_HMAC, &[], Some(&keys)).unwrap();
+        assert_eq!(params.prf(), MechanismType::SHA256_HMAC);
+        assert_eq!(params.inner.ulAdditionalDerivedKeys, 2);
+
+        let params = KbkdfFeedbackParams::new(MechanismType::AES_CMAC, &[], None, None).unwrap();
+        assert!(params.inner.pAdditionalDerivedKeys.is_null());
+        assert!(params.inner.pIV.is_null());
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Mechanisms of password-based key derivation functions (PBKDF2)
+//! See: <https://docs.oasis-open.org/pkcs11/pkcs11-curr/v3.0/os/pkcs11-curr-v3.0-os.html#_Toc30061378>
+
+use crate::error::{Error, Result};
+use crate::types::Ulong;
+use cryptoki_sys::*;
+use log::error;
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::null_mut;
+use std::slice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+/// Pseudo-random function used to generate the key with PBKDF2.
+pub struct Pkcs5Prf {
+    val: CK_PKCS5_PBKD2_PSEUDO_RANDOM_FUNCTION_TYPE,
+}
+
+impl Pkcs5Prf {
+    /// HMAC-SHA-1
+    pub const HMAC_SHA1: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA1,
+    };
+    /// HMAC-SHA-224
+    pub const HMAC_SHA224: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA224,
+    };
+    /// HMAC-SHA-256
+    pub const HMAC_SHA256: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA256,
+    };
+    /// HMAC-SHA-384
+    pub const HMAC_SHA384: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA384,
+    };
+    /// HMAC-SHA-512
+    pub const HMAC_SHA512: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA512,
+    };
+    /// HMAC-SHA-512/224
+    pub const HMAC_SHA512_224: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA512_224,
+    };
+    /// HMAC-SHA-512/256
+    pub const HMAC_SHA512_256: Pkcs5Prf = Pkcs5Prf {
+        val: CKP_PKCS5_PBKD2_HMAC_SHA512_256,
+    };
+}
+
+impl Deref for Pkcs5Prf {
+    type Target = CK_PKCS5_PBKD2_PSEUDO_RANDOM_FUNCTION_TYPE;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl From<Pkcs5Prf> for CK_PKCS5_PBKD2_PSEUDO_RANDOM_FUNCTION_TYPE {
+    fn from(prf: Pkcs5Prf) -> Self {
+        *prf
+    }
+}
+
+impl TryFrom<CK_PKCS5_PBKD2_PSEUDO_RANDOM_FUNCTION_TYPE> for Pkcs5Prf {
+    type Error = Error;
+
+    fn try_from(prf: CK_PKCS5_PBKD2_PSEUDO_RANDOM_FUNCTION_TYPE) -> Result<Self> {
+        match prf {
+            CKP_PKCS5_PBKD2_HMAC_SHA1 => Ok(Pkcs5Prf::HMAC_SHA1),
+            CKP_PKCS5_PBKD2_HMAC_SHA224 => Ok(Pkcs5Prf::HMAC_SHA224),
+            CKP_PKCS5_PBKD2_HMAC_SHA256 => Ok(Pkcs5Prf::HMAC_SHA256),
+            CKP_PKCS5_PBKD2_HMAC_SHA384 => Ok(Pkcs5Prf::HMAC_SHA384),
+            CKP_PKCS5_PBKD2_HMAC_SHA512 => Ok(Pkcs5Prf::HMAC_SHA512),
+            CKP_PKCS5_PBKD2_HMAC_SHA512_224 => Ok(Pkcs5Prf::HMAC_SHA512_224),
+            CKP_PKCS5_PBKD2_HMAC_SHA512_256 => Ok(Pkcs5Prf::HMAC_SHA512_256),
+            other => {
+                error!(
+                    "Pseudo-random function type {} is not one of the valid values.",
+                    other
+                );
+                Err(Error::InvalidValue)
+            }
+        }
+    }
+}
+
+/// The source of the PBKDF2 salt.
+#[derive(Debug, Clone, Copy)]
+pub enum Pbkdf2Salt<'a> {
+    /// CKZ_SALT_SPECIFIED the salt is supplied as data.
+    Specified(&'a [u8]),
+}
+
+/// PBKDF2 parameters.
+///
+/// This structure wraps a `CK_PKCS5_PBKD2_PARAMS2` structure, which differs
+/// from the older `CK_PKCS5_PBKD2_PARAMS` in that the password length is
+/// given by value rather than through a pointer.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct Pbkdf2Params<'a> {
+    inner: CK_PKCS5_PBKD2_PARAMS2,
+    /// Marker type to ensure we don't outlive the salt and password
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Pbkdf2Params<'a> {
+    /// Construct parameters for the PBKDF2 key generation mechanism.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf` - The pseudo-random function used to generate the key.
+    ///
+    /// * `salt` - The salt, as specified by the source.
+    ///
+    /// * `iterations` - The number of iterations to perform, at least 1.
+    ///
+    /// * `password` - The password from which the key is generated, if any.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `iterations` is
+    /// zero, and [`Error::TryFromInt`] if one of the values does not fit into
+    /// a `CK_ULONG`.
+    pub fn new(
+        prf: Pkcs5Prf,
+        salt: Pbkdf2Salt<'a>,
+        iterations: u64,
+        password: Option<&'a [u8]>,
+    ) -> Result<Self> {
+        if iterations == 0 {
+            error!("PBKDF2 requires at least one iteration");
+            return Err(Error::InvalidValue);
+        }
+        let Pbkdf2Salt::Specified(salt_data) = salt;
+        Ok(Self {
+            inner: CK_PKCS5_PBKD2_PARAMS2 {
+                saltSource: CKZ_SALT_SPECIFIED,
+                pSaltSourceData: salt_data.as_ptr() as *mut _,
+                ulSaltSourceDataLen: salt_data.len().try_into()?,
+                iterations: usize::try_from(iterations)?.try_into()?,
+                prf: *prf,
+                pPrfData: null_mut(),
+                ulPrfDataLen: 0,
+                pPassword: password.map_or(null_mut(), |password| password.as_ptr() as *mut _),
+                ulPasswordLen: password.map_or(0, <[u8]>::len).try_into()?,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The pseudo-random function used to generate the key.
+    pub fn prf(&self) -> Pkcs5Prf {
+        Pkcs5Prf {
+            val: self.inner.prf,
+        }
+    }
+
+    /// The salt.
+    pub fn salt(&self) -> Pbkdf2Salt<'a> {
+        // SAFETY: In the constructor, the salt always comes from a &'a [u8]
+        Pbkdf2Salt::Specified(unsafe {
+            slice::from_raw_parts(
+                self.inner.pSaltSourceData as *const u8,
+                self.inner.ulSaltSourceDataLen as _,
+            )
+        })
+    }
+
+    /// The number of iterations.
+    pub fn iterations(&self) -> Ulong {
+        self.inner.iterations.into()
+    }
+
+    /// The password, if any.
+    pub fn password(&self) -> Option<&'a [u8]> {
+        if self.inner.pPassword.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null password always comes
+            // from a &'a [u8]
+            Some(unsafe {
+                slice::from_raw_parts(self.inner.pPassword, self.inner.ulPasswordLen as _)
+            })
+        }
+    }
+}
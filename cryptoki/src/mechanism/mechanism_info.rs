@@ -9,6 +9,11 @@ use std::fmt::{Debug, Formatter};
 bitflags! {
     struct MechanismInfoFlags: CK_FLAGS {
         const HW = CKF_HW;
+        const MESSAGE_ENCRYPT = CKF_MESSAGE_ENCRYPT;
+        const MESSAGE_DECRYPT = CKF_MESSAGE_DECRYPT;
+        const MESSAGE_SIGN = CKF_MESSAGE_SIGN;
+        const MESSAGE_VERIFY = CKF_MESSAGE_VERIFY;
+        const MULTI_MESSAGE = CKF_MULTI_MESSAGE;
         const ENCRYPT = CKF_ENCRYPT;
         const DECRYPT = CKF_DECRYPT;
         const DIGEST = CKF_DIGEST;
@@ -150,6 +155,36 @@ impl MechanismInfo {
         self.flags.contains(MechanismInfoFlags::DERIVE)
     }
 
+    /// True if the mechanism can be used with
+    /// [`Session::message_encrypt_init`](crate::session::Session::message_encrypt_init)
+    pub fn message_encrypt(&self) -> bool {
+        self.flags.contains(MechanismInfoFlags::MESSAGE_ENCRYPT)
+    }
+
+    /// True if the mechanism can be used with
+    /// [`Session::message_decrypt_init`](crate::session::Session::message_decrypt_init)
+    pub fn message_decrypt(&self) -> bool {
+        self.flags.contains(MechanismInfoFlags::MESSAGE_DECRYPT)
+    }
+
+    /// True if the mechanism can be used with
+    /// [`Session::message_sign_init`](crate::session::Session::message_sign_init)
+    pub fn message_sign(&self) -> bool {
+        self.flags.contains(MechanismInfoFlags::MESSAGE_SIGN)
+    }
+
+    /// True if the mechanism can be used with
+    /// [`Session::message_verify_init`](crate::session::Session::message_verify_init)
+    pub fn message_verify(&self) -> bool {
+        self.flags.contains(MechanismInfoFlags::MESSAGE_VERIFY)
+    }
+
+    /// True if the mechanism can be used to process several messages at the
+    /// same time
+    pub fn multi_message(&self) -> bool {
+        self.flags.contains(MechanismInfoFlags::MULTI_MESSAGE)
+    }
+
     /// True if there is an extension to the flags; false if no extensions
     ///
     /// **[Conformance](crate#conformance-notes):**
@@ -262,11 +297,13 @@ impl From<CK_MECHANISM_INFO> for MechanismInfo {
 #[cfg(test)]
 mod test {
     use super::{MechanismInfo, MechanismInfoFlags};
+    use cryptoki_sys::*;
 
     #[test]
     fn debug_flags_all() {
         let expected = "\
-HW | ENCRYPT | DECRYPT | DIGEST | SIGN | SIGN_RECOVER | VERIFY | \
+HW | MESSAGE_ENCRYPT | MESSAGE_DECRYPT | MESSAGE_SIGN | MESSAGE_VERIFY | \
+MULTI_MESSAGE | ENCRYPT | DECRYPT | DIGEST | SIGN | SIGN_RECOVER | VERIFY | \
 VERIFY_RECOVER | GENERATE | GENERATE_KEY_PAIR | WRAP | UNWRAP | DERIVE | \
 EXTENSION | EC_F_P | EC_F_2M | EC_ECPARAMETERS | EC_NAMEDCURVE | \
 EC_OID | EC_UNCOMPRESS | EC_COMPRESS";
@@ -291,4 +328,20 @@ EC_OID | EC_UNCOMPRESS | EC_COMPRESS";
         let observed = format!("{info:#?}");
         assert_eq!(observed, expected);
     }
+
+    #[test]
+    fn message_flags() {
+        let info = MechanismInfo::from(CK_MECHANISM_INFO {
+            ulMinKeySize: 16,
+            ulMaxKeySize: 32,
+            flags: CKF_ENCRYPT | CKF_MESSAGE_ENCRYPT | CKF_MESSAGE_DECRYPT | CKF_MULTI_MESSAGE,
+        });
+        assert!(info.encrypt());
+        assert!(info.message_encrypt());
+        assert!(info.message_decrypt());
+        assert!(info.multi_message());
+        assert!(!info.message_sign());
+        assert!(!info.message_verify());
+        assert!(!info.decrypt());
+    }
 }
@@ -0,0 +1,12 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! ML-DSA mechanism types
+
+use cryptoki_sys::*;
+
+parameter_set! {
+    MlDsaParameterSet: CK_ML_DSA_PARAMETER_SET_TYPE, "ML-DSA", "FIPS 204";
+    MlDsa44 = CKP_ML_DSA_44, "ML-DSA-44";
+    MlDsa65 = CKP_ML_DSA_65, "ML-DSA-65";
+    MlDsa87 = CKP_ML_DSA_87, "ML-DSA-87";
+}
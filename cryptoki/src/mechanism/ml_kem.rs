@@ -0,0 +1,12 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! ML-KEM mechanism types
+
+use cryptoki_sys::*;
+
+parameter_set! {
+    MlKemParameterSet: CK_ML_KEM_PARAMETER_SET_TYPE, "ML-KEM", "FIPS 203";
+    MlKem512 = CKP_ML_KEM_512, "ML-KEM-512";
+    MlKem768 = CKP_ML_KEM_768, "ML-KEM-768";
+    MlKem1024 = CKP_ML_KEM_1024, "ML-KEM-1024";
+}
@@ -2,14 +2,120 @@
 // SPDX-License-Identifier: Apache-2.0
 //! Data types for mechanisms
 
+// Parameter sets of the post-quantum mechanisms, which are given to the key
+// pair generation with the CKA_PARAMETER_SET attribute
+macro_rules! parameter_set {
+    (
+        $name:ident: $raw:ident, $algorithm:literal, $standard:literal;
+        $($variant:ident = $value:ident, $variant_name:literal;)*
+    ) => {
+        #[doc = concat!($algorithm, " parameter sets, as defined in ", $standard)]
+        ///
+        #[doc = concat!("An ", $algorithm, " key pair is generated with the parameter set given by the")]
+        /// [`Attribute::ParameterSet`](crate::object::Attribute::ParameterSet)
+        /// attribute of the public key template.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[non_exhaustive]
+        pub enum $name {
+            $(
+                #[doc = $variant_name]
+                $variant,
+            )*
+        }
+
+        impl From<$name> for $raw {
+            fn from(parameter_set: $name) -> Self {
+                match parameter_set {
+                    $($name::$variant => $value,)*
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<$raw> for $name {
+            type Error = $crate::error::Error;
+
+            fn try_from(parameter_set: $raw) -> $crate::error::Result<Self> {
+                match parameter_set {
+                    $($value => Ok($name::$variant),)*
+                    other => {
+                        log::error!(
+                            concat!($algorithm, " parameter set {} is not supported."),
+                            other
+                        );
+                        Err($crate::error::Error::NotSupported)
+                    }
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<$crate::types::Ulong> for $name {
+            type Error = $crate::error::Error;
+
+            fn try_from(parameter_set: $crate::types::Ulong) -> $crate::error::Result<Self> {
+                $name::try_from(*parameter_set)
+            }
+        }
+
+        impl From<$name> for $crate::object::Attribute {
+            fn from(parameter_set: $name) -> Self {
+                $crate::object::Attribute::ParameterSet($raw::from(parameter_set).into())
+            }
+        }
+
+        #[cfg(test)]
+        mod test {
+            use super::*;
+            use $crate::object::Attribute;
+            use std::convert::TryFrom;
+
+            #[test]
+            fn parameter_sets() {
+                for (parameter_set, value) in [$(($name::$variant, $value),)*] {
+                    assert_eq!($raw::from(parameter_set), value);
+                    assert_eq!($name::try_from(value).unwrap(), parameter_set);
+
+                    let attribute = Attribute::from(parameter_set);
+                    assert_eq!(attribute, Attribute::ParameterSet(value.into()));
+                    let raw = CK_ATTRIBUTE::from(&attribute);
+                    assert_eq!(raw.type_, CKA_PARAMETER_SET);
+                    assert_eq!(Attribute::try_from(raw).unwrap(), attribute);
+                    match attribute {
+                        Attribute::ParameterSet(value) => {
+                            assert_eq!($name::try_from(value).unwrap(), parameter_set)
+                        }
+                        _ => panic!("Expected parameter set attribute."),
+                    }
+                }
+                assert!($name::try_from(0).is_err());
+            }
+        }
+    };
+}
+
 pub mod aead;
+pub mod aes;
+pub mod camellia;
+pub mod chacha;
+pub mod dh;
+pub mod dsa;
+pub mod eddsa;
 pub mod ekdf;
 pub mod elliptic_curve;
+pub mod gost;
 pub mod hkdf;
+pub mod kbkdf;
+pub mod kdf;
 mod mechanism_info;
+pub mod ml_dsa;
+pub mod ml_kem;
 pub mod rsa;
+pub mod simple_kdf;
+pub mod tls;
+pub mod vendor_defined;
 
 use crate::error::Error;
+use crate::object::ObjectHandle;
+use crate::types::Ulong;
 use cryptoki_sys::*;
 use log::error;
 use std::convert::{TryFrom, TryInto};
@@ -65,24 +171,46 @@ impl MechanismType {
     pub const AES_KEY_WRAP_PAD: MechanismType = MechanismType {
         val: CKM_AES_KEY_WRAP_PAD,
     };
+    /// AES key wrap with padding mechanism (RFC 5649).  This mechanism can wrap a key or encrypt
+    /// a block of data of any length.
+    pub const AES_KEY_WRAP_KWP: MechanismType = MechanismType {
+        val: CKM_AES_KEY_WRAP_KWP,
+    };
     /// AES-CTR mechanism
     pub const AES_CTR: MechanismType = MechanismType { val: CKM_AES_CTR };
     /// AES-GCM mechanism
     pub const AES_GCM: MechanismType = MechanismType { val: CKM_AES_GCM };
+    /// AES-CCM mechanism
+    pub const AES_CCM: MechanismType = MechanismType { val: CKM_AES_CCM };
 
     /// Derivation via encryption
     pub const AES_CBC_ENCRYPT_DATA: MechanismType = MechanismType {
         val: CKM_AES_CBC_ENCRYPT_DATA,
     };
+    /// Derivation via AES ECB encryption
+    pub const AES_ECB_ENCRYPT_DATA: MechanismType = MechanismType {
+        val: CKM_AES_ECB_ENCRYPT_DATA,
+    };
 
     /// AES-CMAC mechanism (See RFC 4493)
     pub const AES_CMAC: MechanismType = MechanismType { val: CKM_AES_CMAC };
+    /// AES-CMAC-GENERAL mechanism, with a truncated MAC
+    pub const AES_CMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_AES_CMAC_GENERAL,
+    };
 
     /// AES-CFB128 mechanism
     pub const AES_CFB128: MechanismType = MechanismType {
         val: CKM_AES_CFB128,
     };
 
+    /// AES-XTS mechanism
+    pub const AES_XTS: MechanismType = MechanismType { val: CKM_AES_XTS };
+    /// AES-XTS key generation mechanism
+    pub const AES_XTS_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_AES_XTS_KEY_GEN,
+    };
+
     // RSA
     /// PKCS #1 RSA key pair generation mechanism
     pub const RSA_PKCS_KEY_PAIR_GEN: MechanismType = MechanismType {
@@ -105,6 +233,66 @@ impl MechanismType {
     /// Multi-purpose mechanism based on the RSA public-key cryptosystem.  This is so-called "raw"
     /// RSA, as assumed in X.509.
     pub const RSA_X_509: MechanismType = MechanismType { val: CKM_RSA_X_509 };
+    /// RSA AES key wrap mechanism
+    pub const RSA_AES_KEY_WRAP: MechanismType = MechanismType {
+        val: CKM_RSA_AES_KEY_WRAP,
+    };
+
+    // DH
+    /// PKCS #3 Diffie-Hellman key pair generation mechanism
+    pub const DH_PKCS_KEY_PAIR_GEN: MechanismType = MechanismType {
+        val: CKM_DH_PKCS_KEY_PAIR_GEN,
+    };
+    /// PKCS #3 Diffie-Hellman key derivation mechanism
+    pub const DH_PKCS_DERIVE: MechanismType = MechanismType {
+        val: CKM_DH_PKCS_DERIVE,
+    };
+    /// PKCS #3 Diffie-Hellman domain parameter generation mechanism
+    pub const DH_PKCS_PARAMETER_GEN: MechanismType = MechanismType {
+        val: CKM_DH_PKCS_PARAMETER_GEN,
+    };
+    /// X9.42 Diffie-Hellman key pair generation mechanism
+    pub const X9_42_DH_KEY_PAIR_GEN: MechanismType = MechanismType {
+        val: CKM_X9_42_DH_KEY_PAIR_GEN,
+    };
+    /// X9.42 Diffie-Hellman key derivation mechanism
+    pub const X9_42_DH_DERIVE: MechanismType = MechanismType {
+        val: CKM_X9_42_DH_DERIVE,
+    };
+    /// X9.42 Diffie-Hellman domain parameter generation mechanism
+    pub const X9_42_DH_PARAMETER_GEN: MechanismType = MechanismType {
+        val: CKM_X9_42_DH_PARAMETER_GEN,
+    };
+
+    // DSA
+    /// DSA key pair generation mechanism
+    pub const DSA_KEY_PAIR_GEN: MechanismType = MechanismType {
+        val: CKM_DSA_KEY_PAIR_GEN,
+    };
+    /// DSA domain parameter generation mechanism
+    pub const DSA_PARAMETER_GEN: MechanismType = MechanismType {
+        val: CKM_DSA_PARAMETER_GEN,
+    };
+    /// DSA signature mechanism
+    pub const DSA: MechanismType = MechanismType { val: CKM_DSA };
+    /// DSA with SHA-1 mechanism
+    pub const DSA_SHA1: MechanismType = MechanismType { val: CKM_DSA_SHA1 };
+    /// DSA with SHA-224 mechanism
+    pub const DSA_SHA224: MechanismType = MechanismType {
+        val: CKM_DSA_SHA224,
+    };
+    /// DSA with SHA-256 mechanism
+    pub const DSA_SHA256: MechanismType = MechanismType {
+        val: CKM_DSA_SHA256,
+    };
+    /// DSA with SHA-384 mechanism
+    pub const DSA_SHA384: MechanismType = MechanismType {
+        val: CKM_DSA_SHA384,
+    };
+    /// DSA with SHA-512 mechanism
+    pub const DSA_SHA512: MechanismType = MechanismType {
+        val: CKM_DSA_SHA512,
+    };
 
     // DES
     /// DES
@@ -161,6 +349,80 @@ impl MechanismType {
     /// Note that DES3 is deprecated. See <https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-131Ar2.pdf> section 2, p. 6.
     pub const DES3_ECB: MechanismType = MechanismType { val: CKM_DES3_ECB };
 
+    // Camellia
+    /// Camellia key generation mechanism
+    pub const CAMELLIA_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_KEY_GEN,
+    };
+    /// Camellia-ECB mechanism
+    pub const CAMELLIA_ECB: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_ECB,
+    };
+    /// Camellia-CBC mechanism
+    pub const CAMELLIA_CBC: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_CBC,
+    };
+    /// Camellia-CBC with PKCS#7 padding mechanism
+    pub const CAMELLIA_CBC_PAD: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_CBC_PAD,
+    };
+    /// Camellia-CTR mechanism
+    pub const CAMELLIA_CTR: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_CTR,
+    };
+    /// Camellia-MAC mechanism
+    pub const CAMELLIA_MAC: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_MAC,
+    };
+    /// Camellia-MAC-GENERAL mechanism
+    pub const CAMELLIA_MAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_CAMELLIA_MAC_GENERAL,
+    };
+
+    // GOST
+    /// GOST R 34.10 key pair generation mechanism
+    pub const GOSTR3410_KEY_PAIR_GEN: MechanismType = MechanismType {
+        val: CKM_GOSTR3410_KEY_PAIR_GEN,
+    };
+    /// GOST R 34.10 signature mechanism
+    pub const GOSTR3410: MechanismType = MechanismType { val: CKM_GOSTR3410 };
+    /// GOST R 34.10 signature with GOST R 34.11 mechanism
+    pub const GOSTR3410_WITH_GOSTR3411: MechanismType = MechanismType {
+        val: CKM_GOSTR3410_WITH_GOSTR3411,
+    };
+    /// GOST R 34.10 key wrap mechanism
+    pub const GOSTR3410_KEY_WRAP: MechanismType = MechanismType {
+        val: CKM_GOSTR3410_KEY_WRAP,
+    };
+    /// GOST R 34.10 key derivation mechanism
+    pub const GOSTR3410_DERIVE: MechanismType = MechanismType {
+        val: CKM_GOSTR3410_DERIVE,
+    };
+    /// GOST R 34.11 digest mechanism
+    pub const GOSTR3411: MechanismType = MechanismType { val: CKM_GOSTR3411 };
+    /// GOST R 34.11 HMAC mechanism
+    pub const GOSTR3411_HMAC: MechanismType = MechanismType {
+        val: CKM_GOSTR3411_HMAC,
+    };
+    /// GOST 28147-89 key generation mechanism
+    pub const GOST28147_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_GOST28147_KEY_GEN,
+    };
+    /// GOST 28147-89 in ECB mode
+    pub const GOST28147_ECB: MechanismType = MechanismType {
+        val: CKM_GOST28147_ECB,
+    };
+    /// GOST 28147-89 encryption mechanism
+    pub const GOST28147: MechanismType = MechanismType { val: CKM_GOST28147 };
+    /// GOST 28147-89 MAC mechanism
+    pub const GOST28147_MAC: MechanismType = MechanismType {
+        val: CKM_GOST28147_MAC,
+    };
+    /// GOST 28147-89 key wrap mechanism
+    pub const GOST28147_KEY_WRAP: MechanismType = MechanismType {
+        val: CKM_GOST28147_KEY_WRAP,
+    };
+
     // ECC
     /// EC key pair generation mechanism
     pub const ECC_KEY_PAIR_GEN: MechanismType = MechanismType {
@@ -186,6 +448,10 @@ impl MechanismType {
     pub const ECDH1_DERIVE: MechanismType = MechanismType {
         val: CKM_ECDH1_DERIVE,
     };
+    /// ECDH AES key wrap mechanism
+    pub const ECDH_AES_KEY_WRAP: MechanismType = MechanismType {
+        val: CKM_ECDH_AES_KEY_WRAP,
+    };
 
     /// ECDSA mechanism
     pub const ECDSA: MechanismType = MechanismType { val: CKM_ECDSA };
@@ -281,6 +547,46 @@ impl MechanismType {
     pub const SHA512_HMAC: MechanismType = MechanismType {
         val: CKM_SHA512_HMAC,
     };
+    /// SHA1-HMAC-GENERAL mechanism
+    pub const SHA1_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA_1_HMAC_GENERAL,
+    };
+    /// SHA224-HMAC-GENERAL mechanism
+    pub const SHA224_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA224_HMAC_GENERAL,
+    };
+    /// SHA256-HMAC-GENERAL mechanism
+    pub const SHA256_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA256_HMAC_GENERAL,
+    };
+    /// SHA384-HMAC-GENERAL mechanism
+    pub const SHA384_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA384_HMAC_GENERAL,
+    };
+    /// SHA512-HMAC-GENERAL mechanism
+    pub const SHA512_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA512_HMAC_GENERAL,
+    };
+    /// SHA1 key generation mechanism, for SHA1-HMAC keys
+    pub const SHA1_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA_1_KEY_GEN,
+    };
+    /// SHA224 key generation mechanism, for SHA224-HMAC keys
+    pub const SHA224_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA224_KEY_GEN,
+    };
+    /// SHA256 key generation mechanism, for SHA256-HMAC keys
+    pub const SHA256_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA256_KEY_GEN,
+    };
+    /// SHA384 key generation mechanism, for SHA384-HMAC keys
+    pub const SHA384_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA384_KEY_GEN,
+    };
+    /// SHA512 key generation mechanism, for SHA512-HMAC keys
+    pub const SHA512_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA512_KEY_GEN,
+    };
     /// GENERIC-SECRET-KEY-GEN mechanism
     pub const GENERIC_SECRET_KEY_GEN: MechanismType = MechanismType {
         val: CKM_GENERIC_SECRET_KEY_GEN,
@@ -298,6 +604,238 @@ impl MechanismType {
     /// HKDF-DATA mechanism
     pub const HKDF_DATA: MechanismType = MechanismType { val: CKM_HKDF_DATA };
 
+    // PKCS #5
+    /// PKCS #5 PBKDF2 key generation mechanism
+    pub const PKCS5_PBKD2: MechanismType = MechanismType {
+        val: CKM_PKCS5_PBKD2,
+    };
+
+    // ChaCha20 and Salsa20
+    /// ChaCha20 key generation mechanism
+    pub const CHACHA20_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_CHACHA20_KEY_GEN,
+    };
+    /// ChaCha20 stream cipher mechanism
+    pub const CHACHA20: MechanismType = MechanismType { val: CKM_CHACHA20 };
+    /// ChaCha20-Poly1305 AEAD mechanism
+    pub const CHACHA20_POLY1305: MechanismType = MechanismType {
+        val: CKM_CHACHA20_POLY1305,
+    };
+    /// Salsa20-Poly1305 AEAD mechanism
+    pub const SALSA20_POLY1305: MechanismType = MechanismType {
+        val: CKM_SALSA20_POLY1305,
+    };
+
+    // NIST SP 800-108 KBKDF
+    /// NIST SP 800-108 KDF in counter mode
+    pub const SP800_108_COUNTER_KDF: MechanismType = MechanismType {
+        val: CKM_SP800_108_COUNTER_KDF,
+    };
+    /// NIST SP 800-108 KDF in feedback mode
+    pub const SP800_108_FEEDBACK_KDF: MechanismType = MechanismType {
+        val: CKM_SP800_108_FEEDBACK_KDF,
+    };
+    /// NIST SP 800-108 KDF in double-pipeline mode
+    pub const SP800_108_DOUBLE_PIPELINE_KDF: MechanismType = MechanismType {
+        val: CKM_SP800_108_DOUBLE_PIPELINE_KDF,
+    };
+
+    // TLS 1.2
+    /// TLS 1.2 master key derivation mechanism
+    pub const TLS12_MASTER_KEY_DERIVE: MechanismType = MechanismType {
+        val: CKM_TLS12_MASTER_KEY_DERIVE,
+    };
+    /// TLS 1.2 master key derivation mechanism for DH and ECDH key exchanges
+    pub const TLS12_MASTER_KEY_DERIVE_DH: MechanismType = MechanismType {
+        val: CKM_TLS12_MASTER_KEY_DERIVE_DH,
+    };
+    /// TLS 1.2 key and MAC derivation mechanism
+    pub const TLS12_KEY_AND_MAC_DERIVE: MechanismType = MechanismType {
+        val: CKM_TLS12_KEY_AND_MAC_DERIVE,
+    };
+    /// TLS 1.2 key derivation mechanism that does not return the IVs
+    pub const TLS12_KEY_SAFE_DERIVE: MechanismType = MechanismType {
+        val: CKM_TLS12_KEY_SAFE_DERIVE,
+    };
+    /// TLS 1.2 `Finished` MAC mechanism
+    pub const TLS12_MAC: MechanismType = MechanismType { val: CKM_TLS12_MAC };
+    /// TLS 1.2 PRF key derivation mechanism
+    pub const TLS12_KDF: MechanismType = MechanismType { val: CKM_TLS12_KDF };
+
+    // SHA-3
+    /// SHA3-224 mechanism
+    pub const SHA3_224: MechanismType = MechanismType { val: CKM_SHA3_224 };
+    /// SHA3-256 mechanism
+    pub const SHA3_256: MechanismType = MechanismType { val: CKM_SHA3_256 };
+    /// SHA3-384 mechanism
+    pub const SHA3_384: MechanismType = MechanismType { val: CKM_SHA3_384 };
+    /// SHA3-512 mechanism
+    pub const SHA3_512: MechanismType = MechanismType { val: CKM_SHA3_512 };
+    /// SHA3-224-RSA-PKCS mechanism
+    pub const SHA3_224_RSA_PKCS: MechanismType = MechanismType {
+        val: CKM_SHA3_224_RSA_PKCS,
+    };
+    /// SHA3-256-RSA-PKCS mechanism
+    pub const SHA3_256_RSA_PKCS: MechanismType = MechanismType {
+        val: CKM_SHA3_256_RSA_PKCS,
+    };
+    /// SHA3-384-RSA-PKCS mechanism
+    pub const SHA3_384_RSA_PKCS: MechanismType = MechanismType {
+        val: CKM_SHA3_384_RSA_PKCS,
+    };
+    /// SHA3-512-RSA-PKCS mechanism
+    pub const SHA3_512_RSA_PKCS: MechanismType = MechanismType {
+        val: CKM_SHA3_512_RSA_PKCS,
+    };
+    /// SHA3-224-RSA-PKCS-PSS mechanism
+    pub const SHA3_224_RSA_PKCS_PSS: MechanismType = MechanismType {
+        val: CKM_SHA3_224_RSA_PKCS_PSS,
+    };
+    /// SHA3-256-RSA-PKCS-PSS mechanism
+    pub const SHA3_256_RSA_PKCS_PSS: MechanismType = MechanismType {
+        val: CKM_SHA3_256_RSA_PKCS_PSS,
+    };
+    /// SHA3-384-RSA-PKCS-PSS mechanism
+    pub const SHA3_384_RSA_PKCS_PSS: MechanismType = MechanismType {
+        val: CKM_SHA3_384_RSA_PKCS_PSS,
+    };
+    /// SHA3-512-RSA-PKCS-PSS mechanism
+    pub const SHA3_512_RSA_PKCS_PSS: MechanismType = MechanismType {
+        val: CKM_SHA3_512_RSA_PKCS_PSS,
+    };
+    /// ECDSA with SHA3-224 mechanism
+    pub const ECDSA_SHA3_224: MechanismType = MechanismType {
+        val: CKM_ECDSA_SHA3_224,
+    };
+    /// ECDSA with SHA3-256 mechanism
+    pub const ECDSA_SHA3_256: MechanismType = MechanismType {
+        val: CKM_ECDSA_SHA3_256,
+    };
+    /// ECDSA with SHA3-384 mechanism
+    pub const ECDSA_SHA3_384: MechanismType = MechanismType {
+        val: CKM_ECDSA_SHA3_384,
+    };
+    /// ECDSA with SHA3-512 mechanism
+    pub const ECDSA_SHA3_512: MechanismType = MechanismType {
+        val: CKM_ECDSA_SHA3_512,
+    };
+    /// SHA3-224-HMAC mechanism
+    pub const SHA3_224_HMAC: MechanismType = MechanismType {
+        val: CKM_SHA3_224_HMAC,
+    };
+    /// SHA3-256-HMAC mechanism
+    pub const SHA3_256_HMAC: MechanismType = MechanismType {
+        val: CKM_SHA3_256_HMAC,
+    };
+    /// SHA3-384-HMAC mechanism
+    pub const SHA3_384_HMAC: MechanismType = MechanismType {
+        val: CKM_SHA3_384_HMAC,
+    };
+    /// SHA3-512-HMAC mechanism
+    pub const SHA3_512_HMAC: MechanismType = MechanismType {
+        val: CKM_SHA3_512_HMAC,
+    };
+    /// SHA3-224-HMAC-GENERAL mechanism
+    pub const SHA3_224_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA3_224_HMAC_GENERAL,
+    };
+    /// SHA3-256-HMAC-GENERAL mechanism
+    pub const SHA3_256_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA3_256_HMAC_GENERAL,
+    };
+    /// SHA3-384-HMAC-GENERAL mechanism
+    pub const SHA3_384_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA3_384_HMAC_GENERAL,
+    };
+    /// SHA3-512-HMAC-GENERAL mechanism
+    pub const SHA3_512_HMAC_GENERAL: MechanismType = MechanismType {
+        val: CKM_SHA3_512_HMAC_GENERAL,
+    };
+    /// SHA3-224 key generation mechanism, for SHA3-224-HMAC keys
+    pub const SHA3_224_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA3_224_KEY_GEN,
+    };
+    /// SHA3-256 key generation mechanism, for SHA3-256-HMAC keys
+    pub const SHA3_256_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA3_256_KEY_GEN,
+    };
+    /// SHA3-384 key generation mechanism, for SHA3-384-HMAC keys
+    pub const SHA3_384_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA3_384_KEY_GEN,
+    };
+    /// SHA3-512 key generation mechanism, for SHA3-512-HMAC keys
+    pub const SHA3_512_KEY_GEN: MechanismType = MechanismType {
+        val: CKM_SHA3_512_KEY_GEN,
+    };
+
+    // Simple key derivation
+    /// Derivation by concatenation of the base key and another key
+    pub const CONCATENATE_BASE_AND_KEY: MechanismType = MechanismType {
+        val: CKM_CONCATENATE_BASE_AND_KEY,
+    };
+    /// Derivation by concatenation of the base key and data
+    pub const CONCATENATE_BASE_AND_DATA: MechanismType = MechanismType {
+        val: CKM_CONCATENATE_BASE_AND_DATA,
+    };
+    /// Derivation by concatenation of data and the base key
+    pub const CONCATENATE_DATA_AND_BASE: MechanismType = MechanismType {
+        val: CKM_CONCATENATE_DATA_AND_BASE,
+    };
+    /// Derivation by XOR of the base key and data
+    pub const XOR_BASE_AND_DATA: MechanismType = MechanismType {
+        val: CKM_XOR_BASE_AND_DATA,
+    };
+    /// Derivation by extraction of bits of the base key
+    pub const EXTRACT_KEY_FROM_KEY: MechanismType = MechanismType {
+        val: CKM_EXTRACT_KEY_FROM_KEY,
+    };
+
+    /// Create a vendor defined mechanism type, `adding` being its offset
+    /// from `CKM_VENDOR_DEFINED`
+    ///
+    /// Mechanisms which are not part of the standard, such as the SM2, SM3
+    /// and SM4 algorithms, are only supported through the values defined by
+    /// each vendor, e.g. `CKM_VENDOR_DEFINED | 0x0001`:
+    ///
+    /// ```rust
+    /// use cryptoki::mechanism::MechanismType;
+    ///
+    /// const CKM_VENDOR_SM3: MechanismType = MechanismType::new_vendor_defined(0x0001);
+    /// ```
+    pub const fn new_vendor_defined(adding: CK_MECHANISM_TYPE) -> MechanismType {
+        MechanismType {
+            val: CKM_VENDOR_DEFINED | adding,
+        }
+    }
+
+    /// Whether the mechanism type is vendor defined, see
+    /// [`MechanismType::new_vendor_defined`]
+    pub const fn is_vendor_defined(&self) -> bool {
+        self.val & CKM_VENDOR_DEFINED == CKM_VENDOR_DEFINED
+    }
+
+    // Mechanism types returned by the library are kept as they are, even
+    // the ones this crate does not know about yet
+    pub(crate) const fn new_unchecked(val: CK_MECHANISM_TYPE) -> MechanismType {
+        MechanismType { val }
+    }
+
+    // ML-KEM
+    /// ML-KEM key pair generation mechanism
+    pub const ML_KEM_KEY_PAIR_GEN: MechanismType = MechanismType {
+        val: CKM_ML_KEM_KEY_PAIR_GEN,
+    };
+    /// ML-KEM key encapsulation mechanism
+    pub const ML_KEM: MechanismType = MechanismType { val: CKM_ML_KEM };
+
+    // ML-DSA
+    /// ML-DSA key pair generation mechanism
+    pub const ML_DSA_KEY_PAIR_GEN: MechanismType = MechanismType {
+        val: CKM_ML_DSA_KEY_PAIR_GEN,
+    };
+    /// ML-DSA signature mechanism
+    pub const ML_DSA: MechanismType = MechanismType { val: CKM_ML_DSA };
+
     pub(crate) fn stringify(mech: CK_MECHANISM_TYPE) -> String {
         match mech {
             CKM_RSA_PKCS_KEY_PAIR_GEN => String::from(stringify!(CKM_RSA_PKCS_KEY_PAIR_GEN)),
@@ -626,8 +1164,13 @@ impl MechanismType {
             CKM_AES_CFB64 => String::from(stringify!(CKM_AES_CFB64)),
             CKM_AES_CFB8 => String::from(stringify!(CKM_AES_CFB8)),
             CKM_AES_CFB128 => String::from(stringify!(CKM_AES_CFB128)),
+            CKM_AES_XTS => String::from(stringify!(CKM_AES_XTS)),
+            CKM_AES_XTS_KEY_GEN => String::from(stringify!(CKM_AES_XTS_KEY_GEN)),
             CKM_AES_CFB1 => String::from(stringify!(CKM_AES_CFB1)),
             CKM_VENDOR_DEFINED => String::from(stringify!(CKM_VENDOR_DEFINED)),
+            vendor if vendor > CKM_VENDOR_DEFINED => {
+                format!("CKM_VENDOR_DEFINED | {:#x}", vendor - CKM_VENDOR_DEFINED)
+            }
             CKM_SHA224 => String::from(stringify!(CKM_SHA224)),
             CKM_SHA224_HMAC => String::from(stringify!(CKM_SHA224_HMAC)),
             CKM_SHA224_HMAC_GENERAL => String::from(stringify!(CKM_SHA224_HMAC_GENERAL)),
@@ -647,6 +1190,7 @@ impl MechanismType {
             }
             CKM_AES_KEY_WRAP => String::from(stringify!(CKM_AES_KEY_WRAP)),
             CKM_AES_KEY_WRAP_PAD => String::from(stringify!(CKM_AES_KEY_WRAP_PAD)),
+            CKM_AES_KEY_WRAP_KWP => String::from(stringify!(CKM_AES_KEY_WRAP_KWP)),
             CKM_RSA_PKCS_TPM_1_1 => String::from(stringify!(CKM_RSA_PKCS_TPM_1_1)),
             CKM_RSA_PKCS_OAEP_TPM_1_1 => String::from(stringify!(CKM_RSA_PKCS_OAEP_TPM_1_1)),
             CKM_EC_EDWARDS_KEY_PAIR_GEN => String::from(stringify!(CKM_EC_EDWARDS_KEY_PAIR_GEN)),
@@ -657,6 +1201,56 @@ impl MechanismType {
             CKM_HKDF_KEY_GEN => String::from(stringify!(CKM_HKDF_KEY_GEN)),
             CKM_HKDF_DERIVE => String::from(stringify!(CKM_HKDF_DERIVE)),
             CKM_HKDF_DATA => String::from(stringify!(CKM_HKDF_DATA)),
+            CKM_CHACHA20_KEY_GEN => String::from(stringify!(CKM_CHACHA20_KEY_GEN)),
+            CKM_CHACHA20_POLY1305 => String::from(stringify!(CKM_CHACHA20_POLY1305)),
+            CKM_SALSA20_POLY1305 => String::from(stringify!(CKM_SALSA20_POLY1305)),
+            CKM_SP800_108_COUNTER_KDF => String::from(stringify!(CKM_SP800_108_COUNTER_KDF)),
+            CKM_SP800_108_FEEDBACK_KDF => String::from(stringify!(CKM_SP800_108_FEEDBACK_KDF)),
+            CKM_SP800_108_DOUBLE_PIPELINE_KDF => {
+                String::from(stringify!(CKM_SP800_108_DOUBLE_PIPELINE_KDF))
+            }
+            CKM_SHA3_224 => String::from(stringify!(CKM_SHA3_224)),
+            CKM_SHA3_256 => String::from(stringify!(CKM_SHA3_256)),
+            CKM_SHA3_384 => String::from(stringify!(CKM_SHA3_384)),
+            CKM_SHA3_512 => String::from(stringify!(CKM_SHA3_512)),
+            CKM_SHA3_224_RSA_PKCS => String::from(stringify!(CKM_SHA3_224_RSA_PKCS)),
+            CKM_SHA3_256_RSA_PKCS => String::from(stringify!(CKM_SHA3_256_RSA_PKCS)),
+            CKM_SHA3_384_RSA_PKCS => String::from(stringify!(CKM_SHA3_384_RSA_PKCS)),
+            CKM_SHA3_512_RSA_PKCS => String::from(stringify!(CKM_SHA3_512_RSA_PKCS)),
+            CKM_SHA3_224_RSA_PKCS_PSS => String::from(stringify!(CKM_SHA3_224_RSA_PKCS_PSS)),
+            CKM_SHA3_256_RSA_PKCS_PSS => String::from(stringify!(CKM_SHA3_256_RSA_PKCS_PSS)),
+            CKM_SHA3_384_RSA_PKCS_PSS => String::from(stringify!(CKM_SHA3_384_RSA_PKCS_PSS)),
+            CKM_SHA3_512_RSA_PKCS_PSS => String::from(stringify!(CKM_SHA3_512_RSA_PKCS_PSS)),
+            CKM_ECDSA_SHA3_224 => String::from(stringify!(CKM_ECDSA_SHA3_224)),
+            CKM_ECDSA_SHA3_256 => String::from(stringify!(CKM_ECDSA_SHA3_256)),
+            CKM_ECDSA_SHA3_384 => String::from(stringify!(CKM_ECDSA_SHA3_384)),
+            CKM_ECDSA_SHA3_512 => String::from(stringify!(CKM_ECDSA_SHA3_512)),
+            CKM_SHA3_224_HMAC => String::from(stringify!(CKM_SHA3_224_HMAC)),
+            CKM_SHA3_224_HMAC_GENERAL => String::from(stringify!(CKM_SHA3_224_HMAC_GENERAL)),
+            CKM_SHA3_224_KEY_GEN => String::from(stringify!(CKM_SHA3_224_KEY_GEN)),
+            CKM_SHA3_224_KEY_DERIVATION => String::from(stringify!(CKM_SHA3_224_KEY_DERIVATION)),
+            CKM_SHA3_256_HMAC => String::from(stringify!(CKM_SHA3_256_HMAC)),
+            CKM_SHA3_256_HMAC_GENERAL => String::from(stringify!(CKM_SHA3_256_HMAC_GENERAL)),
+            CKM_SHA3_256_KEY_GEN => String::from(stringify!(CKM_SHA3_256_KEY_GEN)),
+            CKM_SHA3_256_KEY_DERIVATION => String::from(stringify!(CKM_SHA3_256_KEY_DERIVATION)),
+            CKM_SHA3_384_HMAC => String::from(stringify!(CKM_SHA3_384_HMAC)),
+            CKM_SHA3_384_HMAC_GENERAL => String::from(stringify!(CKM_SHA3_384_HMAC_GENERAL)),
+            CKM_SHA3_384_KEY_GEN => String::from(stringify!(CKM_SHA3_384_KEY_GEN)),
+            CKM_SHA3_384_KEY_DERIVATION => String::from(stringify!(CKM_SHA3_384_KEY_DERIVATION)),
+            CKM_SHA3_512_HMAC => String::from(stringify!(CKM_SHA3_512_HMAC)),
+            CKM_SHA3_512_HMAC_GENERAL => String::from(stringify!(CKM_SHA3_512_HMAC_GENERAL)),
+            CKM_SHA3_512_KEY_GEN => String::from(stringify!(CKM_SHA3_512_KEY_GEN)),
+            CKM_SHA3_512_KEY_DERIVATION => String::from(stringify!(CKM_SHA3_512_KEY_DERIVATION)),
+            CKM_CHACHA20 => String::from(stringify!(CKM_CHACHA20)),
+            CKM_SHA_1_KEY_GEN => String::from(stringify!(CKM_SHA_1_KEY_GEN)),
+            CKM_SHA224_KEY_GEN => String::from(stringify!(CKM_SHA224_KEY_GEN)),
+            CKM_SHA256_KEY_GEN => String::from(stringify!(CKM_SHA256_KEY_GEN)),
+            CKM_SHA384_KEY_GEN => String::from(stringify!(CKM_SHA384_KEY_GEN)),
+            CKM_SHA512_KEY_GEN => String::from(stringify!(CKM_SHA512_KEY_GEN)),
+            CKM_ML_KEM_KEY_PAIR_GEN => String::from(stringify!(CKM_ML_KEM_KEY_PAIR_GEN)),
+            CKM_ML_KEM => String::from(stringify!(CKM_ML_KEM)),
+            CKM_ML_DSA_KEY_PAIR_GEN => String::from(stringify!(CKM_ML_DSA_KEY_PAIR_GEN)),
+            CKM_ML_DSA => String::from(stringify!(CKM_ML_DSA)),
             _ => format!("unknown {mech:08x}"),
         }
     }
@@ -693,8 +1287,10 @@ impl TryFrom<CK_MECHANISM_TYPE> for MechanismType {
             CKM_AES_ECB => Ok(MechanismType::AES_ECB),
             CKM_AES_KEY_WRAP => Ok(MechanismType::AES_KEY_WRAP),
             CKM_AES_KEY_WRAP_PAD => Ok(MechanismType::AES_KEY_WRAP_PAD),
+            CKM_AES_KEY_WRAP_KWP => Ok(MechanismType::AES_KEY_WRAP_KWP),
             CKM_AES_CTR => Ok(MechanismType::AES_CTR),
             CKM_AES_GCM => Ok(MechanismType::AES_GCM),
+            CKM_AES_CCM => Ok(MechanismType::AES_CCM),
             CKM_AES_CBC_ENCRYPT_DATA => Ok(MechanismType::AES_CBC_ENCRYPT_DATA),
             CKM_AES_CMAC => Ok(MechanismType::AES_CMAC),
             CKM_AES_CFB128 => Ok(MechanismType::AES_CFB128),
@@ -736,6 +1332,107 @@ impl TryFrom<CK_MECHANISM_TYPE> for MechanismType {
             CKM_HKDF_KEY_GEN => Ok(MechanismType::HKDF_KEY_GEN),
             CKM_HKDF_DERIVE => Ok(MechanismType::HKDF_DERIVE),
             CKM_HKDF_DATA => Ok(MechanismType::HKDF_DATA),
+            CKM_PKCS5_PBKD2 => Ok(MechanismType::PKCS5_PBKD2),
+            CKM_CHACHA20_KEY_GEN => Ok(MechanismType::CHACHA20_KEY_GEN),
+            CKM_CHACHA20_POLY1305 => Ok(MechanismType::CHACHA20_POLY1305),
+            CKM_SALSA20_POLY1305 => Ok(MechanismType::SALSA20_POLY1305),
+            CKM_SP800_108_COUNTER_KDF => Ok(MechanismType::SP800_108_COUNTER_KDF),
+            CKM_SP800_108_FEEDBACK_KDF => Ok(MechanismType::SP800_108_FEEDBACK_KDF),
+            CKM_SP800_108_DOUBLE_PIPELINE_KDF => Ok(MechanismType::SP800_108_DOUBLE_PIPELINE_KDF),
+            CKM_TLS12_MASTER_KEY_DERIVE => Ok(MechanismType::TLS12_MASTER_KEY_DERIVE),
+            CKM_TLS12_MASTER_KEY_DERIVE_DH => Ok(MechanismType::TLS12_MASTER_KEY_DERIVE_DH),
+            CKM_TLS12_KEY_AND_MAC_DERIVE => Ok(MechanismType::TLS12_KEY_AND_MAC_DERIVE),
+            CKM_TLS12_KEY_SAFE_DERIVE => Ok(MechanismType::TLS12_KEY_SAFE_DERIVE),
+            CKM_TLS12_MAC => Ok(MechanismType::TLS12_MAC),
+            CKM_TLS12_KDF => Ok(MechanismType::TLS12_KDF),
+            CKM_SHA3_224 => Ok(MechanismType::SHA3_224),
+            CKM_SHA3_256 => Ok(MechanismType::SHA3_256),
+            CKM_SHA3_384 => Ok(MechanismType::SHA3_384),
+            CKM_SHA3_512 => Ok(MechanismType::SHA3_512),
+            CKM_SHA3_224_RSA_PKCS => Ok(MechanismType::SHA3_224_RSA_PKCS),
+            CKM_SHA3_256_RSA_PKCS => Ok(MechanismType::SHA3_256_RSA_PKCS),
+            CKM_SHA3_384_RSA_PKCS => Ok(MechanismType::SHA3_384_RSA_PKCS),
+            CKM_SHA3_512_RSA_PKCS => Ok(MechanismType::SHA3_512_RSA_PKCS),
+            CKM_SHA3_224_RSA_PKCS_PSS => Ok(MechanismType::SHA3_224_RSA_PKCS_PSS),
+            CKM_SHA3_256_RSA_PKCS_PSS => Ok(MechanismType::SHA3_256_RSA_PKCS_PSS),
+            CKM_SHA3_384_RSA_PKCS_PSS => Ok(MechanismType::SHA3_384_RSA_PKCS_PSS),
+            CKM_SHA3_512_RSA_PKCS_PSS => Ok(MechanismType::SHA3_512_RSA_PKCS_PSS),
+            CKM_ECDSA_SHA3_224 => Ok(MechanismType::ECDSA_SHA3_224),
+            CKM_ECDSA_SHA3_256 => Ok(MechanismType::ECDSA_SHA3_256),
+            CKM_ECDSA_SHA3_384 => Ok(MechanismType::ECDSA_SHA3_384),
+            CKM_ECDSA_SHA3_512 => Ok(MechanismType::ECDSA_SHA3_512),
+            CKM_SHA3_224_HMAC => Ok(MechanismType::SHA3_224_HMAC),
+            CKM_SHA3_256_HMAC => Ok(MechanismType::SHA3_256_HMAC),
+            CKM_SHA3_384_HMAC => Ok(MechanismType::SHA3_384_HMAC),
+            CKM_SHA3_512_HMAC => Ok(MechanismType::SHA3_512_HMAC),
+            CKM_CHACHA20 => Ok(MechanismType::CHACHA20),
+            CKM_AES_XTS => Ok(MechanismType::AES_XTS),
+            CKM_AES_XTS_KEY_GEN => Ok(MechanismType::AES_XTS_KEY_GEN),
+            CKM_CONCATENATE_BASE_AND_KEY => Ok(MechanismType::CONCATENATE_BASE_AND_KEY),
+            CKM_CONCATENATE_BASE_AND_DATA => Ok(MechanismType::CONCATENATE_BASE_AND_DATA),
+            CKM_CONCATENATE_DATA_AND_BASE => Ok(MechanismType::CONCATENATE_DATA_AND_BASE),
+            CKM_XOR_BASE_AND_DATA => Ok(MechanismType::XOR_BASE_AND_DATA),
+            CKM_SHA3_224_KEY_GEN => Ok(MechanismType::SHA3_224_KEY_GEN),
+            CKM_SHA3_256_KEY_GEN => Ok(MechanismType::SHA3_256_KEY_GEN),
+            CKM_SHA3_384_KEY_GEN => Ok(MechanismType::SHA3_384_KEY_GEN),
+            CKM_SHA3_512_KEY_GEN => Ok(MechanismType::SHA3_512_KEY_GEN),
+            CKM_EXTRACT_KEY_FROM_KEY => Ok(MechanismType::EXTRACT_KEY_FROM_KEY),
+            CKM_AES_ECB_ENCRYPT_DATA => Ok(MechanismType::AES_ECB_ENCRYPT_DATA),
+            CKM_SHA_1_HMAC_GENERAL => Ok(MechanismType::SHA1_HMAC_GENERAL),
+            CKM_SHA224_HMAC_GENERAL => Ok(MechanismType::SHA224_HMAC_GENERAL),
+            CKM_SHA256_HMAC_GENERAL => Ok(MechanismType::SHA256_HMAC_GENERAL),
+            CKM_SHA384_HMAC_GENERAL => Ok(MechanismType::SHA384_HMAC_GENERAL),
+            CKM_SHA512_HMAC_GENERAL => Ok(MechanismType::SHA512_HMAC_GENERAL),
+            CKM_SHA_1_KEY_GEN => Ok(MechanismType::SHA1_KEY_GEN),
+            CKM_SHA224_KEY_GEN => Ok(MechanismType::SHA224_KEY_GEN),
+            CKM_SHA256_KEY_GEN => Ok(MechanismType::SHA256_KEY_GEN),
+            CKM_SHA384_KEY_GEN => Ok(MechanismType::SHA384_KEY_GEN),
+            CKM_SHA512_KEY_GEN => Ok(MechanismType::SHA512_KEY_GEN),
+            CKM_CAMELLIA_KEY_GEN => Ok(MechanismType::CAMELLIA_KEY_GEN),
+            CKM_CAMELLIA_ECB => Ok(MechanismType::CAMELLIA_ECB),
+            CKM_CAMELLIA_CBC => Ok(MechanismType::CAMELLIA_CBC),
+            CKM_CAMELLIA_CBC_PAD => Ok(MechanismType::CAMELLIA_CBC_PAD),
+            CKM_RSA_AES_KEY_WRAP => Ok(MechanismType::RSA_AES_KEY_WRAP),
+            vendor if vendor >= CKM_VENDOR_DEFINED => Ok(MechanismType { val: vendor }),
+            CKM_ECDH_AES_KEY_WRAP => Ok(MechanismType::ECDH_AES_KEY_WRAP),
+            CKM_GOSTR3410_KEY_PAIR_GEN => Ok(MechanismType::GOSTR3410_KEY_PAIR_GEN),
+            CKM_GOSTR3410 => Ok(MechanismType::GOSTR3410),
+            CKM_GOSTR3410_WITH_GOSTR3411 => Ok(MechanismType::GOSTR3410_WITH_GOSTR3411),
+            CKM_GOSTR3411 => Ok(MechanismType::GOSTR3411),
+            CKM_GOST28147 => Ok(MechanismType::GOST28147),
+            CKM_DSA_KEY_PAIR_GEN => Ok(MechanismType::DSA_KEY_PAIR_GEN),
+            CKM_DSA_PARAMETER_GEN => Ok(MechanismType::DSA_PARAMETER_GEN),
+            CKM_DSA => Ok(MechanismType::DSA),
+            CKM_DSA_SHA1 => Ok(MechanismType::DSA_SHA1),
+            CKM_DSA_SHA224 => Ok(MechanismType::DSA_SHA224),
+            CKM_DSA_SHA256 => Ok(MechanismType::DSA_SHA256),
+            CKM_DSA_SHA384 => Ok(MechanismType::DSA_SHA384),
+            CKM_DSA_SHA512 => Ok(MechanismType::DSA_SHA512),
+            CKM_GOSTR3410_KEY_WRAP => Ok(MechanismType::GOSTR3410_KEY_WRAP),
+            CKM_GOSTR3410_DERIVE => Ok(MechanismType::GOSTR3410_DERIVE),
+            CKM_GOSTR3411_HMAC => Ok(MechanismType::GOSTR3411_HMAC),
+            CKM_GOST28147_KEY_GEN => Ok(MechanismType::GOST28147_KEY_GEN),
+            CKM_GOST28147_ECB => Ok(MechanismType::GOST28147_ECB),
+            CKM_GOST28147_MAC => Ok(MechanismType::GOST28147_MAC),
+            CKM_GOST28147_KEY_WRAP => Ok(MechanismType::GOST28147_KEY_WRAP),
+            CKM_DH_PKCS_KEY_PAIR_GEN => Ok(MechanismType::DH_PKCS_KEY_PAIR_GEN),
+            CKM_DH_PKCS_DERIVE => Ok(MechanismType::DH_PKCS_DERIVE),
+            CKM_CAMELLIA_CTR => Ok(MechanismType::CAMELLIA_CTR),
+            CKM_CAMELLIA_MAC => Ok(MechanismType::CAMELLIA_MAC),
+            CKM_CAMELLIA_MAC_GENERAL => Ok(MechanismType::CAMELLIA_MAC_GENERAL),
+            CKM_AES_CMAC_GENERAL => Ok(MechanismType::AES_CMAC_GENERAL),
+            CKM_DH_PKCS_PARAMETER_GEN => Ok(MechanismType::DH_PKCS_PARAMETER_GEN),
+            CKM_SHA3_224_HMAC_GENERAL => Ok(MechanismType::SHA3_224_HMAC_GENERAL),
+            CKM_SHA3_256_HMAC_GENERAL => Ok(MechanismType::SHA3_256_HMAC_GENERAL),
+            CKM_SHA3_384_HMAC_GENERAL => Ok(MechanismType::SHA3_384_HMAC_GENERAL),
+            CKM_SHA3_512_HMAC_GENERAL => Ok(MechanismType::SHA3_512_HMAC_GENERAL),
+            CKM_X9_42_DH_KEY_PAIR_GEN => Ok(MechanismType::X9_42_DH_KEY_PAIR_GEN),
+            CKM_X9_42_DH_DERIVE => Ok(MechanismType::X9_42_DH_DERIVE),
+            CKM_X9_42_DH_PARAMETER_GEN => Ok(MechanismType::X9_42_DH_PARAMETER_GEN),
+            CKM_ML_KEM_KEY_PAIR_GEN => Ok(MechanismType::ML_KEM_KEY_PAIR_GEN),
+            CKM_ML_KEM => Ok(MechanismType::ML_KEM),
+            CKM_ML_DSA_KEY_PAIR_GEN => Ok(MechanismType::ML_DSA_KEY_PAIR_GEN),
+            CKM_ML_DSA => Ok(MechanismType::ML_DSA),
             other => {
                 error!("Mechanism type {} is not supported.", other);
                 Err(Error::NotSupported)
@@ -771,12 +1468,67 @@ pub enum Mechanism<'a> {
     AesCbcPad([u8; 16]),
     /// AES in ECB mode
     AesEcb,
-    /// AES key wrap
+    /// AES in counter mode
+    AesCtr(aes::AesCtrParams),
+    /// AES in XTS mode
+    ///
+    /// The key is a double-length key of type [`KeyType::AES_XTS`], holding
+    /// the two concatenated AES keys of XTS: 256 bits for AES-128-XTS, and 512
+    /// bits for AES-256-XTS.  Some tokens also accept, or only accept, keys of
+    /// type [`KeyType::AES`] of the same length.
+    ///
+    /// Each single-part operation processes one data unit, of at least one
+    /// block: the token rejects shorter inputs with
+    /// [`RvError::DataLenRange`](crate::error::RvError::DataLenRange).
+    ///
+    /// [`KeyType::AES_XTS`]: crate::object::KeyType::AES_XTS
+    /// [`KeyType::AES`]: crate::object::KeyType::AES
+    AesXts(aes::AesXtsParams),
+    /// AES-XTS key generation mechanism
+    ///
+    /// The length of the key, set with
+    /// [`Attribute::ValueLen`](crate::object::Attribute::ValueLen), is 32 bytes
+    /// for AES-128-XTS and 64 bytes for AES-256-XTS.
+    AesXtsKeyGen,
+    /// AES key wrap (RFC 3394), with the default initial value
+    ///
+    /// The key to wrap must be a multiple of 8 bytes long, and at least 16
+    /// bytes.  Use [`Mechanism::AesKeyWrapKwp`] for keys of any length.
     AesKeyWrap,
-    /// AES key wrap with padding block
+    /// AES key wrap (RFC 3394), with the given 8-byte initial value
+    AesKeyWrapWithIv([u8; 8]),
+    /// AES key wrap with padding block, with the default initial value of
+    /// the token
+    ///
+    /// This mechanism is deprecated in PKCS #11 3.0: the specification pads
+    /// the input as in PKCS #7 before wrapping it as in RFC 3394, but some
+    /// tokens implement RFC 5649 instead.  Prefer
+    /// [`Mechanism::AesKeyWrapKwp`] when the token supports it.
     AesKeyWrapPad,
+    /// AES key wrap with padding block, with the given 8-byte initial value
+    AesKeyWrapPadWithIv([u8; 8]),
+    /// AES key wrap with padding (RFC 5649)
+    ///
+    /// The optional parameter is the 4-byte initial value.  Without it, the
+    /// token uses the default initial value of RFC 5649.
+    ///
+    /// The key to wrap can be of any length: it is padded to a multiple of 8
+    /// bytes.
+    AesKeyWrapKwp(Option<[u8; 4]>),
     /// AES-GCM mechanism
+    ///
+    /// The authentication tag is appended to the ciphertext.
     AesGcm(aead::GcmParams<'a>),
+    /// AES-GCM mechanism for message-based operations
+    ///
+    /// It has no parameters: the IV and tag of every message are given with
+    /// [`MessageParam::AesGcmMessage`] instead.
+    AesGcmMessage,
+    /// AES-CCM mechanism
+    ///
+    /// The MAC is appended to the ciphertext.  The parameters carry the length
+    /// of the message, so they must be constructed anew for every message.
+    AesCcm(aead::CcmParams<'a>),
     /// AES-CBC-ENCRYPT-DATA mechanism
     ///
     /// The parameter to this mechanism is the initialization vector and the message to encrypt. These mechanisms allow
@@ -785,8 +1537,18 @@ pub enum Mechanism<'a> {
     /// For derivation, the message length must be a multiple of the block
     /// size. See <https://www.cryptsoft.com/pkcs11doc/v220/>.
     AesCbcEncryptData(ekdf::AesCbcDeriveParams<'a>),
+    /// AES-ECB-ENCRYPT-DATA mechanism
+    ///
+    /// The parameter to this mechanism is the data to encrypt with the base
+    /// key, whose cipher text is the value of the derived key.
+    AesEcbEncryptData(ekdf::AesEcbDeriveParams<'a>),
     /// AES CMAC (RFC 4493)
     AesCMac,
+    /// AES CMAC (RFC 4493) with a truncated MAC
+    ///
+    /// The parameter is the length of the MAC, in bytes, of at most 16 bytes,
+    /// see [`Mechanism::aes_cmac_general`].
+    AesCMacGeneral(MacLength<16>),
 
     // RSA
     /// PKCS #1 RSA key pair generation mechanism
@@ -803,6 +1565,63 @@ pub enum Mechanism<'a> {
     /// Multi-purpose mechanism based on the RSA public-key cryptosystem.  This is so-called "raw"
     /// RSA, as assumed in X.509.
     RsaX509,
+    /// RSA AES key wrap mechanism, wrapping a key with a temporary AES key
+    /// itself wrapped with RSA OAEP
+    RsaAesKeyWrap(rsa::RsaAesKeyWrapParams<'a>),
+
+    // DH
+    /// PKCS #3 Diffie-Hellman key pair generation mechanism
+    ///
+    /// The domain parameters are given by the attributes of the public key
+    /// template, see [`dh::DhPkcsParams`].
+    DhPkcsKeyPairGen,
+    /// PKCS #3 Diffie-Hellman key derivation mechanism
+    ///
+    /// The length of the derived secret can be set with
+    /// [`Attribute::ValueLen`](crate::object::Attribute::ValueLen) in the
+    /// template; it defaults to the length of the prime.
+    DhPkcsDerive(dh::DhPkcsDeriveParams<'a>),
+    /// PKCS #3 Diffie-Hellman domain parameter generation mechanism
+    ///
+    /// [`Session::generate_key`](crate::session::Session::generate_key)
+    /// creates a domain parameters object, whose prime length is set with
+    /// [`Attribute::PrimeBits`](crate::object::Attribute::PrimeBits) in the
+    /// template.
+    DhPkcsParameterGen,
+    /// X9.42 Diffie-Hellman key pair generation mechanism
+    ///
+    /// The domain parameters are given by the
+    /// [`Attribute::Prime`](crate::object::Attribute::Prime),
+    /// [`Attribute::Subprime`](crate::object::Attribute::Subprime) and
+    /// [`Attribute::Base`](crate::object::Attribute::Base) attributes of the
+    /// public key template.
+    X942DhKeyPairGen,
+    /// X9.42 Diffie-Hellman key derivation mechanism
+    X942DhDerive(dh::X942Dh1DeriveParams<'a>),
+    /// X9.42 Diffie-Hellman domain parameter generation mechanism
+    X942DhParameterGen,
+
+    // DSA
+    /// DSA key pair generation mechanism
+    ///
+    /// The domain parameters are given by the attributes of the public key
+    /// template, see [`dsa::DsaParams`].
+    DsaKeyPairGen,
+    /// DSA domain parameter generation mechanism, see
+    /// [`Session::generate_dsa_params`](crate::session::Session::generate_dsa_params)
+    DsaParameterGen,
+    /// DSA signature mechanism, signing a digest
+    Dsa,
+    /// DSA with SHA-1 mechanism
+    DsaSha1,
+    /// DSA with SHA-224 mechanism
+    DsaSha224,
+    /// DSA with SHA-256 mechanism
+    DsaSha256,
+    /// DSA with SHA-384 mechanism
+    DsaSha384,
+    /// DSA with SHA-512 mechanism
+    DsaSha512,
 
     // DES
     /// DES
@@ -852,6 +1671,70 @@ pub enum Mechanism<'a> {
     /// DES3 ECB
     Des3Ecb,
 
+    // Camellia
+    /// Camellia key generation mechanism
+    CamelliaKeyGen,
+    /// Camellia in ECB mode
+    CamelliaEcb,
+    /// Camellia-CBC mechanism
+    ///
+    /// The parameter to this mechanism is the initialization vector.  As with
+    /// [`Mechanism::AesCbc`], the message length must be a multiple of the
+    /// block size.
+    CamelliaCbc([u8; 16]),
+    /// Camellia-CBC with PKCS#7 padding mechanism
+    ///
+    /// The parameter to this mechanism is the initialization vector.
+    CamelliaCbcPad([u8; 16]),
+    /// Camellia in counter mode
+    CamelliaCtr(camellia::CamelliaCtrParams),
+    /// Camellia-MAC mechanism, a CBC-MAC of half the block size
+    CamelliaMac,
+    /// Camellia-MAC-GENERAL mechanism
+    ///
+    /// The parameter to this mechanism is the length of the MAC in bytes, of
+    /// at most 16 bytes, see [`Mechanism::camellia_mac_general`].
+    CamelliaMacGeneral(MacLength<16>),
+
+    // GOST
+    /// GOST R 34.10 key pair generation mechanism
+    ///
+    /// The curve is given by the
+    /// [`Attribute::GostR3410Params`](crate::object::Attribute::GostR3410Params)
+    /// attribute of the public key template, see [`gost::GostR3410ParamSet`].
+    GostR3410KeyPairGen,
+    /// GOST R 34.10 signature mechanism, signing a 32-byte digest
+    GostR3410,
+    /// GOST R 34.10 signature with GOST R 34.11 mechanism
+    ///
+    /// The digest parameter set is the one of the key.
+    GostR3410WithGostR3411,
+    /// GOST R 34.11 digest mechanism
+    GostR3411,
+    /// GOST R 34.11 HMAC mechanism
+    GostR3411Hmac,
+    /// GOST 28147-89 key generation mechanism
+    ///
+    /// The parameter set is given by the
+    /// [`Attribute::Gost28147Params`](crate::object::Attribute::Gost28147Params)
+    /// attribute of the template.
+    Gost28147KeyGen,
+    /// GOST 28147-89 in ECB mode
+    Gost28147Ecb,
+    /// GOST 28147-89 encryption mechanism
+    ///
+    /// The parameter to this mechanism is the 8-byte initialization vector.
+    Gost28147([u8; 8]),
+    /// GOST 28147-89 MAC mechanism
+    ///
+    /// The optional parameter is the 8-byte initialization vector.
+    Gost28147Mac(Option<[u8; 8]>),
+    /// GOST 28147-89 key wrap mechanism
+    ///
+    /// The optional parameter is the 8-byte user keying material.  Without
+    /// it, the token generates a random one.
+    Gost28147KeyWrap(Option<[u8; 8]>),
+
     // ECC
     /// EC key pair generation
     EccKeyPairGen,
@@ -861,6 +1744,9 @@ pub enum Mechanism<'a> {
     EccMontgomeryKeyPairGen,
     /// ECDH
     Ecdh1Derive(elliptic_curve::Ecdh1DeriveParams<'a>),
+    /// ECDH AES key wrap mechanism, wrapping a key with a temporary AES key
+    /// derived through ECDH
+    EcdhAesKeyWrap(elliptic_curve::EcdhAesKeyWrapParams<'a>),
     /// ECDSA mechanism
     Ecdsa,
     /// ECDSA with SHA-1 mechanism
@@ -875,11 +1761,17 @@ pub enum Mechanism<'a> {
     EcdsaSha512,
     /// EDDSA mechanism
     ///
+    /// The "pure" variant of EdDSA is used for the curve of the key, with no
+    /// context string.
+    ///
     /// Note: EdDSA is not part of the PKCS#11 v2.40 standard and as
     /// such may not be understood by the backend. It is included here
     /// because some vendor implementations support it through the
     /// v2.40 interface.
     Eddsa,
+    /// EDDSA mechanism with parameters, selecting the prehash variant or a
+    /// context string
+    EddsaWithParams(eddsa::EddsaParams<'a>),
 
     // SHA-n
     /// SHA-1 mechanism
@@ -926,6 +1818,41 @@ pub enum Mechanism<'a> {
     Sha384Hmac,
     /// SHA512-HMAC mechanism
     Sha512Hmac,
+    /// SHA1-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha1_hmac_general`].
+    Sha1HmacGeneral(MacLength<20>),
+    /// SHA224-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha224_hmac_general`].
+    Sha224HmacGeneral(MacLength<28>),
+    /// SHA256-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha256_hmac_general`].
+    Sha256HmacGeneral(MacLength<32>),
+    /// SHA384-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha384_hmac_general`].
+    Sha384HmacGeneral(MacLength<48>),
+    /// SHA512-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha512_hmac_general`].
+    Sha512HmacGeneral(MacLength<64>),
+    /// SHA1 key generation mechanism
+    Sha1KeyGen,
+    /// SHA224 key generation mechanism
+    Sha224KeyGen,
+    /// SHA256 key generation mechanism
+    Sha256KeyGen,
+    /// SHA384 key generation mechanism
+    Sha384KeyGen,
+    /// SHA512 key generation mechanism
+    Sha512KeyGen,
 
     /// GENERIC-SECRET-KEY-GEN mechanism
     GenericSecretKeyGen,
@@ -937,26 +1864,303 @@ pub enum Mechanism<'a> {
     HkdfDerive(hkdf::HkdfParams<'a>),
     /// HKDF-DATA mechanism
     HkdfData(hkdf::HkdfParams<'a>),
+
+    // PKCS #5
+    /// PKCS #5 PBKDF2 key generation mechanism
+    Pkcs5Pbkd2(kdf::Pbkdf2Params<'a>),
+
+    // ChaCha20 and Salsa20
+    /// ChaCha20 key generation mechanism
+    ChaCha20KeyGen,
+    /// ChaCha20 stream cipher mechanism
+    ///
+    /// The stream cipher does not authenticate the data: use
+    /// [`Mechanism::ChaCha20Poly1305`] unless the data is authenticated by
+    /// other means.
+    ChaCha20(chacha::ChaCha20Params<'a>),
+    /// ChaCha20-Poly1305 AEAD mechanism
+    ///
+    /// The Poly1305 tag is appended to the ciphertext.
+    ChaCha20Poly1305(chacha::ChaCha20Poly1305Params<'a>),
+    /// Salsa20-Poly1305 AEAD mechanism
+    ///
+    /// The Poly1305 tag is appended to the ciphertext.
+    Salsa20Poly1305(chacha::ChaCha20Poly1305Params<'a>),
+
+    // NIST SP 800-108 KBKDF
+    /// NIST SP 800-108 KDF in counter mode
+    Sp800108CounterKdf(kbkdf::KbkdfParams<'a>),
+    /// NIST SP 800-108 KDF in feedback mode
+    Sp800108FeedbackKdf(kbkdf::KbkdfFeedbackParams<'a>),
+    /// NIST SP 800-108 KDF in double-pipeline mode
+    Sp800108DoublePipelineKdf(kbkdf::KbkdfParams<'a>),
+
+    // TLS 1.2
+    /// TLS 1.2 master key derivation mechanism
+    Tls12MasterKeyDerive(tls::Tls12MasterKeyDeriveParams<'a>),
+    /// TLS 1.2 master key derivation mechanism for DH and ECDH key exchanges
+    Tls12MasterKeyDeriveDh(tls::Tls12MasterKeyDeriveParams<'a>),
+    /// TLS 1.2 key and MAC derivation mechanism
+    Tls12KeyAndMacDerive(tls::Tls12KeyMaterialParams<'a>),
+    /// TLS 1.2 key derivation mechanism that does not return the IVs
+    Tls12KeySafeDerive(tls::Tls12KeyMaterialParams<'a>),
+    /// TLS 1.2 `Finished` MAC mechanism
+    Tls12Mac(tls::TlsMacParams),
+    /// TLS 1.2 PRF key derivation mechanism
+    Tls12Kdf(tls::TlsKdfParams<'a>),
+
+    // SHA-3
+    /// SHA3-224 mechanism
+    Sha3_224,
+    /// SHA3-256 mechanism
+    Sha3_256,
+    /// SHA3-384 mechanism
+    Sha3_384,
+    /// SHA3-512 mechanism
+    Sha3_512,
+    /// SHA3-224-RSA-PKCS mechanism
+    Sha3_224RsaPkcs,
+    /// SHA3-256-RSA-PKCS mechanism
+    Sha3_256RsaPkcs,
+    /// SHA3-384-RSA-PKCS mechanism
+    Sha3_384RsaPkcs,
+    /// SHA3-512-RSA-PKCS mechanism
+    Sha3_512RsaPkcs,
+    /// SHA3-224-RSA-PKCS-PSS mechanism
+    Sha3_224RsaPkcsPss(rsa::PkcsPssParams),
+    /// SHA3-256-RSA-PKCS-PSS mechanism
+    Sha3_256RsaPkcsPss(rsa::PkcsPssParams),
+    /// SHA3-384-RSA-PKCS-PSS mechanism
+    Sha3_384RsaPkcsPss(rsa::PkcsPssParams),
+    /// SHA3-512-RSA-PKCS-PSS mechanism
+    Sha3_512RsaPkcsPss(rsa::PkcsPssParams),
+    /// ECDSA with SHA3-224 mechanism
+    EcdsaSha3_224,
+    /// ECDSA with SHA3-256 mechanism
+    EcdsaSha3_256,
+    /// ECDSA with SHA3-384 mechanism
+    EcdsaSha3_384,
+    /// ECDSA with SHA3-512 mechanism
+    EcdsaSha3_512,
+    /// SHA3-224-HMAC mechanism
+    Sha3_224Hmac,
+    /// SHA3-256-HMAC mechanism
+    Sha3_256Hmac,
+    /// SHA3-384-HMAC mechanism
+    Sha3_384Hmac,
+    /// SHA3-512-HMAC mechanism
+    Sha3_512Hmac,
+    /// SHA3-224-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha3_224_hmac_general`].
+    Sha3_224HmacGeneral(MacLength<28>),
+    /// SHA3-256-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha3_256_hmac_general`].
+    Sha3_256HmacGeneral(MacLength<32>),
+    /// SHA3-384-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha3_384_hmac_general`].
+    Sha3_384HmacGeneral(MacLength<48>),
+    /// SHA3-512-HMAC-GENERAL mechanism
+    ///
+    /// The parameter is the length of the MAC, in bytes, see
+    /// [`Mechanism::sha3_512_hmac_general`].
+    Sha3_512HmacGeneral(MacLength<64>),
+    /// SHA3-224 key generation mechanism
+    Sha3_224KeyGen,
+    /// SHA3-256 key generation mechanism
+    Sha3_256KeyGen,
+    /// SHA3-384 key generation mechanism
+    Sha3_384KeyGen,
+    /// SHA3-512 key generation mechanism
+    Sha3_512KeyGen,
+
+    // Simple key derivation
+    /// Derivation by concatenation of the base key and another key
+    ///
+    /// The parameter is the key appended to the base key.
+    ConcatenateBaseAndKey(ObjectHandle),
+    /// Derivation by concatenation of the base key and data
+    ConcatenateBaseAndData(simple_kdf::KeyDerivationStringData<'a>),
+    /// Derivation by concatenation of data and the base key
+    ConcatenateDataAndBase(simple_kdf::KeyDerivationStringData<'a>),
+    /// Derivation by XOR of the base key and data
+    ///
+    /// The derived key is as long as the shorter of the base key and the data,
+    /// unless the template sets a shorter length.
+    XorBaseAndData(simple_kdf::KeyDerivationStringData<'a>),
+    /// Derivation by extraction of bits of the base key
+    ExtractKeyFromKey(simple_kdf::ExtractParams),
+
+    /// A vendor defined mechanism
+    VendorDefined(vendor_defined::VendorDefinedMechanism<'a>),
+
+    // ML-KEM
+    /// ML-KEM key pair generation mechanism
+    ///
+    /// The parameter set is given by the [`Attribute::ParameterSet`](crate::object::Attribute::ParameterSet)
+    /// attribute of the public key template, see [`ml_kem::MlKemParameterSet`].
+    MlKemKeyPairGen,
+    /// ML-KEM key encapsulation mechanism, see [`Session::encapsulate_key`](crate::session::Session::encapsulate_key)
+    MlKem,
+
+    // ML-DSA
+    /// ML-DSA key pair generation mechanism
+    ///
+    /// The parameter set is given by the [`Attribute::ParameterSet`](crate::object::Attribute::ParameterSet)
+    /// attribute of the public key template, see [`ml_dsa::MlDsaParameterSet`].
+    MlDsaKeyPairGen,
+    /// ML-DSA signature mechanism, without context
+    MlDsa,
+}
+
+/// Length in bytes of a truncated MAC, the parameter of the general-length
+/// MAC mechanisms
+///
+/// `MAX` is the full length of the MAC of the mechanism, which the length is
+/// checked against when the mechanism is created, e.g. with
+/// [`Mechanism::aes_cmac_general`]. A length checked for one mechanism can
+/// thus only be used with the mechanisms of the same full MAC length.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct MacLength<const MAX: u64> {
+    val: Ulong,
+}
+
+impl<const MAX: u64> MacLength<MAX> {
+    fn new(mac_len: u64, mac_name: &str) -> Result<Self, Error> {
+        if !(1..=MAX).contains(&mac_len) {
+            error!(
+                "{} length must be between 1 and {} bytes, not {}",
+                mac_name, MAX, mac_len
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(MacLength {
+            val: CK_ULONG::try_from(mac_len)?.into(),
+        })
+    }
+}
+
+impl<const MAX: u64> Deref for MacLength<MAX> {
+    type Target = Ulong;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+// Checked constructors of the HMAC general-length mechanisms, whose MAC is
+// at most as long as the digest
+macro_rules! hmac_general {
+    ($($fn_name:ident, $variant:ident, $name:literal, $digest_len:literal;)*) => {
+        impl Mechanism<'_> {
+            $(
+                #[doc = concat!($name, "-HMAC-GENERAL mechanism with a MAC truncated to `mac_len` bytes")]
+                ///
+                /// # Errors
+                ///
+                #[doc = concat!(
+                    "This function returns [`Error::InvalidValue`] if `mac_len` is not between 1 and ",
+                    stringify!($digest_len),
+                    "."
+                )]
+                pub fn $fn_name(mac_len: u64) -> Result<Self, Error> {
+                    Ok(Mechanism::$variant(MacLength::new(
+                        mac_len,
+                        concat!($name, " HMAC"),
+                    )?))
+                }
+            )*
+        }
+    };
+}
+
+hmac_general! {
+    sha1_hmac_general, Sha1HmacGeneral, "SHA1", 20;
+    sha224_hmac_general, Sha224HmacGeneral, "SHA224", 28;
+    sha256_hmac_general, Sha256HmacGeneral, "SHA256", 32;
+    sha384_hmac_general, Sha384HmacGeneral, "SHA384", 48;
+    sha512_hmac_general, Sha512HmacGeneral, "SHA512", 64;
+    sha3_224_hmac_general, Sha3_224HmacGeneral, "SHA3-224", 28;
+    sha3_256_hmac_general, Sha3_256HmacGeneral, "SHA3-256", 32;
+    sha3_384_hmac_general, Sha3_384HmacGeneral, "SHA3-384", 48;
+    sha3_512_hmac_general, Sha3_512HmacGeneral, "SHA3-512", 64;
 }
 
 impl Mechanism<'_> {
+    /// AES CMAC mechanism with a MAC truncated to `mac_len` bytes, see
+    /// [`Mechanism::AesCMacGeneral`]
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `mac_len` is not
+    /// between 1 and 16.
+    pub fn aes_cmac_general(mac_len: u64) -> Result<Self, Error> {
+        Ok(Mechanism::AesCMacGeneral(MacLength::new(
+            mac_len, "AES CMAC",
+        )?))
+    }
+
+    /// Camellia-MAC-GENERAL mechanism with a MAC truncated to `mac_len` bytes
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `mac_len` is not
+    /// between 1 and 16.
+    pub fn camellia_mac_general(mac_len: u64) -> Result<Self, Error> {
+        Ok(Mechanism::CamelliaMacGeneral(MacLength::new(
+            mac_len,
+            "Camellia MAC",
+        )?))
+    }
+
     /// Get the type of a mechanism
     pub fn mechanism_type(&self) -> MechanismType {
         match self {
             Mechanism::AesKeyGen => MechanismType::AES_KEY_GEN,
             Mechanism::AesEcb => MechanismType::AES_ECB,
             Mechanism::AesCbc(_) => MechanismType::AES_CBC,
+            Mechanism::AesCtr(_) => MechanismType::AES_CTR,
+            Mechanism::AesXts(_) => MechanismType::AES_XTS,
+            Mechanism::AesXtsKeyGen => MechanismType::AES_XTS_KEY_GEN,
             Mechanism::AesCbcPad(_) => MechanismType::AES_CBC_PAD,
-            Mechanism::AesKeyWrap => MechanismType::AES_KEY_WRAP,
-            Mechanism::AesKeyWrapPad => MechanismType::AES_KEY_WRAP_PAD,
-            Mechanism::AesGcm(_) => MechanismType::AES_GCM,
+            Mechanism::AesKeyWrap | Mechanism::AesKeyWrapWithIv(_) => MechanismType::AES_KEY_WRAP,
+            Mechanism::AesKeyWrapPad | Mechanism::AesKeyWrapPadWithIv(_) => {
+                MechanismType::AES_KEY_WRAP_PAD
+            }
+            Mechanism::AesKeyWrapKwp(_) => MechanismType::AES_KEY_WRAP_KWP,
+            Mechanism::AesGcm(_) | Mechanism::AesGcmMessage => MechanismType::AES_GCM,
+            Mechanism::AesCcm(_) => MechanismType::AES_CCM,
             Mechanism::AesCbcEncryptData(_) => MechanismType::AES_CBC_ENCRYPT_DATA,
+            Mechanism::AesEcbEncryptData(_) => MechanismType::AES_ECB_ENCRYPT_DATA,
             Mechanism::AesCMac => MechanismType::AES_CMAC,
+            Mechanism::AesCMacGeneral(_) => MechanismType::AES_CMAC_GENERAL,
             Mechanism::RsaPkcsKeyPairGen => MechanismType::RSA_PKCS_KEY_PAIR_GEN,
             Mechanism::RsaPkcs => MechanismType::RSA_PKCS,
             Mechanism::RsaPkcsPss(_) => MechanismType::RSA_PKCS_PSS,
             Mechanism::RsaPkcsOaep(_) => MechanismType::RSA_PKCS_OAEP,
             Mechanism::RsaX509 => MechanismType::RSA_X_509,
+            Mechanism::RsaAesKeyWrap(_) => MechanismType::RSA_AES_KEY_WRAP,
+
+            Mechanism::DhPkcsKeyPairGen => MechanismType::DH_PKCS_KEY_PAIR_GEN,
+            Mechanism::DhPkcsDerive(_) => MechanismType::DH_PKCS_DERIVE,
+            Mechanism::DhPkcsParameterGen => MechanismType::DH_PKCS_PARAMETER_GEN,
+            Mechanism::X942DhKeyPairGen => MechanismType::X9_42_DH_KEY_PAIR_GEN,
+            Mechanism::X942DhDerive(_) => MechanismType::X9_42_DH_DERIVE,
+            Mechanism::X942DhParameterGen => MechanismType::X9_42_DH_PARAMETER_GEN,
+            Mechanism::DsaKeyPairGen => MechanismType::DSA_KEY_PAIR_GEN,
+            Mechanism::DsaParameterGen => MechanismType::DSA_PARAMETER_GEN,
+            Mechanism::Dsa => MechanismType::DSA,
+            Mechanism::DsaSha1 => MechanismType::DSA_SHA1,
+            Mechanism::DsaSha224 => MechanismType::DSA_SHA224,
+            Mechanism::DsaSha256 => MechanismType::DSA_SHA256,
+            Mechanism::DsaSha384 => MechanismType::DSA_SHA384,
+            Mechanism::DsaSha512 => MechanismType::DSA_SHA512,
 
             Mechanism::DesKeyGen => MechanismType::DES_KEY_GEN,
             Mechanism::Des2KeyGen => MechanismType::DES2_KEY_GEN,
@@ -967,12 +2171,31 @@ impl Mechanism<'_> {
             Mechanism::Des3CbcPad(_) => MechanismType::DES3_CBC_PAD,
             Mechanism::DesEcb => MechanismType::DES_ECB,
             Mechanism::Des3Ecb => MechanismType::DES3_ECB,
+            Mechanism::CamelliaKeyGen => MechanismType::CAMELLIA_KEY_GEN,
+            Mechanism::CamelliaEcb => MechanismType::CAMELLIA_ECB,
+            Mechanism::CamelliaCbc(_) => MechanismType::CAMELLIA_CBC,
+            Mechanism::CamelliaCbcPad(_) => MechanismType::CAMELLIA_CBC_PAD,
+            Mechanism::CamelliaCtr(_) => MechanismType::CAMELLIA_CTR,
+            Mechanism::CamelliaMac => MechanismType::CAMELLIA_MAC,
+            Mechanism::CamelliaMacGeneral(_) => MechanismType::CAMELLIA_MAC_GENERAL,
+
+            Mechanism::GostR3410KeyPairGen => MechanismType::GOSTR3410_KEY_PAIR_GEN,
+            Mechanism::GostR3410 => MechanismType::GOSTR3410,
+            Mechanism::GostR3410WithGostR3411 => MechanismType::GOSTR3410_WITH_GOSTR3411,
+            Mechanism::GostR3411 => MechanismType::GOSTR3411,
+            Mechanism::GostR3411Hmac => MechanismType::GOSTR3411_HMAC,
+            Mechanism::Gost28147KeyGen => MechanismType::GOST28147_KEY_GEN,
+            Mechanism::Gost28147Ecb => MechanismType::GOST28147_ECB,
+            Mechanism::Gost28147(_) => MechanismType::GOST28147,
+            Mechanism::Gost28147Mac(_) => MechanismType::GOST28147_MAC,
+            Mechanism::Gost28147KeyWrap(_) => MechanismType::GOST28147_KEY_WRAP,
 
             Mechanism::EccKeyPairGen => MechanismType::ECC_KEY_PAIR_GEN,
             Mechanism::EccEdwardsKeyPairGen => MechanismType::ECC_EDWARDS_KEY_PAIR_GEN,
             Mechanism::EccMontgomeryKeyPairGen => MechanismType::ECC_MONTGOMERY_KEY_PAIR_GEN,
-            Mechanism::Eddsa => MechanismType::EDDSA,
+            Mechanism::Eddsa | Mechanism::EddsaWithParams(_) => MechanismType::EDDSA,
             Mechanism::Ecdh1Derive(_) => MechanismType::ECDH1_DERIVE,
+            Mechanism::EcdhAesKeyWrap(_) => MechanismType::ECDH_AES_KEY_WRAP,
             Mechanism::Ecdsa => MechanismType::ECDSA,
             Mechanism::EcdsaSha1 => MechanismType::ECDSA_SHA1,
             Mechanism::EcdsaSha224 => MechanismType::ECDSA_SHA224,
@@ -1002,12 +2225,74 @@ impl Mechanism<'_> {
             Mechanism::Sha256Hmac => MechanismType::SHA256_HMAC,
             Mechanism::Sha384Hmac => MechanismType::SHA384_HMAC,
             Mechanism::Sha512Hmac => MechanismType::SHA512_HMAC,
+            Mechanism::Sha1HmacGeneral(_) => MechanismType::SHA1_HMAC_GENERAL,
+            Mechanism::Sha224HmacGeneral(_) => MechanismType::SHA224_HMAC_GENERAL,
+            Mechanism::Sha256HmacGeneral(_) => MechanismType::SHA256_HMAC_GENERAL,
+            Mechanism::Sha384HmacGeneral(_) => MechanismType::SHA384_HMAC_GENERAL,
+            Mechanism::Sha512HmacGeneral(_) => MechanismType::SHA512_HMAC_GENERAL,
+            Mechanism::Sha1KeyGen => MechanismType::SHA1_KEY_GEN,
+            Mechanism::Sha224KeyGen => MechanismType::SHA224_KEY_GEN,
+            Mechanism::Sha256KeyGen => MechanismType::SHA256_KEY_GEN,
+            Mechanism::Sha384KeyGen => MechanismType::SHA384_KEY_GEN,
+            Mechanism::Sha512KeyGen => MechanismType::SHA512_KEY_GEN,
 
             Mechanism::GenericSecretKeyGen => MechanismType::GENERIC_SECRET_KEY_GEN,
 
             Mechanism::HkdfKeyGen => MechanismType::HKDF_KEY_GEN,
             Mechanism::HkdfDerive(_) => MechanismType::HKDF_DERIVE,
             Mechanism::HkdfData(_) => MechanismType::HKDF_DATA,
+            Mechanism::Pkcs5Pbkd2(_) => MechanismType::PKCS5_PBKD2,
+            Mechanism::ChaCha20KeyGen => MechanismType::CHACHA20_KEY_GEN,
+            Mechanism::ChaCha20(_) => MechanismType::CHACHA20,
+            Mechanism::ChaCha20Poly1305(_) => MechanismType::CHACHA20_POLY1305,
+            Mechanism::Salsa20Poly1305(_) => MechanismType::SALSA20_POLY1305,
+            Mechanism::Sp800108CounterKdf(_) => MechanismType::SP800_108_COUNTER_KDF,
+            Mechanism::Sp800108FeedbackKdf(_) => MechanismType::SP800_108_FEEDBACK_KDF,
+            Mechanism::Sp800108DoublePipelineKdf(_) => MechanismType::SP800_108_DOUBLE_PIPELINE_KDF,
+            Mechanism::Tls12MasterKeyDerive(_) => MechanismType::TLS12_MASTER_KEY_DERIVE,
+            Mechanism::Tls12MasterKeyDeriveDh(_) => MechanismType::TLS12_MASTER_KEY_DERIVE_DH,
+            Mechanism::Tls12KeyAndMacDerive(_) => MechanismType::TLS12_KEY_AND_MAC_DERIVE,
+            Mechanism::Tls12KeySafeDerive(_) => MechanismType::TLS12_KEY_SAFE_DERIVE,
+            Mechanism::Tls12Mac(_) => MechanismType::TLS12_MAC,
+            Mechanism::Tls12Kdf(_) => MechanismType::TLS12_KDF,
+            Mechanism::Sha3_224 => MechanismType::SHA3_224,
+            Mechanism::Sha3_224RsaPkcs => MechanismType::SHA3_224_RSA_PKCS,
+            Mechanism::Sha3_224RsaPkcsPss(_) => MechanismType::SHA3_224_RSA_PKCS_PSS,
+            Mechanism::EcdsaSha3_224 => MechanismType::ECDSA_SHA3_224,
+            Mechanism::Sha3_224Hmac => MechanismType::SHA3_224_HMAC,
+            Mechanism::Sha3_256 => MechanismType::SHA3_256,
+            Mechanism::Sha3_256RsaPkcs => MechanismType::SHA3_256_RSA_PKCS,
+            Mechanism::Sha3_256RsaPkcsPss(_) => MechanismType::SHA3_256_RSA_PKCS_PSS,
+            Mechanism::EcdsaSha3_256 => MechanismType::ECDSA_SHA3_256,
+            Mechanism::Sha3_256Hmac => MechanismType::SHA3_256_HMAC,
+            Mechanism::Sha3_384 => MechanismType::SHA3_384,
+            Mechanism::Sha3_384RsaPkcs => MechanismType::SHA3_384_RSA_PKCS,
+            Mechanism::Sha3_384RsaPkcsPss(_) => MechanismType::SHA3_384_RSA_PKCS_PSS,
+            Mechanism::EcdsaSha3_384 => MechanismType::ECDSA_SHA3_384,
+            Mechanism::Sha3_384Hmac => MechanismType::SHA3_384_HMAC,
+            Mechanism::Sha3_512 => MechanismType::SHA3_512,
+            Mechanism::Sha3_512RsaPkcs => MechanismType::SHA3_512_RSA_PKCS,
+            Mechanism::Sha3_512RsaPkcsPss(_) => MechanismType::SHA3_512_RSA_PKCS_PSS,
+            Mechanism::EcdsaSha3_512 => MechanismType::ECDSA_SHA3_512,
+            Mechanism::Sha3_512Hmac => MechanismType::SHA3_512_HMAC,
+            Mechanism::Sha3_224HmacGeneral(_) => MechanismType::SHA3_224_HMAC_GENERAL,
+            Mechanism::Sha3_256HmacGeneral(_) => MechanismType::SHA3_256_HMAC_GENERAL,
+            Mechanism::Sha3_384HmacGeneral(_) => MechanismType::SHA3_384_HMAC_GENERAL,
+            Mechanism::Sha3_512HmacGeneral(_) => MechanismType::SHA3_512_HMAC_GENERAL,
+            Mechanism::ConcatenateBaseAndKey(_) => MechanismType::CONCATENATE_BASE_AND_KEY,
+            Mechanism::ConcatenateBaseAndData(_) => MechanismType::CONCATENATE_BASE_AND_DATA,
+            Mechanism::ConcatenateDataAndBase(_) => MechanismType::CONCATENATE_DATA_AND_BASE,
+            Mechanism::XorBaseAndData(_) => MechanismType::XOR_BASE_AND_DATA,
+            Mechanism::Sha3_224KeyGen => MechanismType::SHA3_224_KEY_GEN,
+            Mechanism::Sha3_256KeyGen => MechanismType::SHA3_256_KEY_GEN,
+            Mechanism::Sha3_384KeyGen => MechanismType::SHA3_384_KEY_GEN,
+            Mechanism::Sha3_512KeyGen => MechanismType::SHA3_512_KEY_GEN,
+            Mechanism::ExtractKeyFromKey(_) => MechanismType::EXTRACT_KEY_FROM_KEY,
+            Mechanism::VendorDefined(mechanism) => mechanism.mechanism_type(),
+            Mechanism::MlKemKeyPairGen => MechanismType::ML_KEM_KEY_PAIR_GEN,
+            Mechanism::MlKem => MechanismType::ML_KEM,
+            Mechanism::MlDsaKeyPairGen => MechanismType::ML_DSA_KEY_PAIR_GEN,
+            Mechanism::MlDsa => MechanismType::ML_DSA,
         }
     }
 }
@@ -1021,6 +2306,13 @@ impl From<&Mechanism<'_>> for CK_MECHANISM {
                 make_mechanism(mechanism, params)
             }
             Mechanism::AesCbcEncryptData(params) => make_mechanism(mechanism, params),
+            Mechanism::AesEcbEncryptData(params) => make_mechanism(mechanism, params),
+            Mechanism::AesCtr(params) => make_mechanism(mechanism, params),
+            Mechanism::AesXts(params) => make_mechanism(mechanism, params),
+            Mechanism::AesKeyWrapWithIv(iv) | Mechanism::AesKeyWrapPadWithIv(iv) => {
+                make_mechanism(mechanism, iv)
+            }
+            Mechanism::AesKeyWrapKwp(Some(iv)) => make_mechanism(mechanism, iv),
             Mechanism::DesCbc(params)
             | Mechanism::Des3Cbc(params)
             | Mechanism::DesCbcPad(params)
@@ -1032,21 +2324,85 @@ impl From<&Mechanism<'_>> for CK_MECHANISM {
                     .try_into()
                     .expect("usize can not fit in CK_ULONG"),
             },
+            Mechanism::AesCcm(params) => make_mechanism(mechanism, params),
             Mechanism::RsaPkcsPss(params)
             | Mechanism::Sha1RsaPkcsPss(params)
             | Mechanism::Sha256RsaPkcsPss(params)
             | Mechanism::Sha384RsaPkcsPss(params)
             | Mechanism::Sha512RsaPkcsPss(params) => make_mechanism(mechanism, params),
             Mechanism::RsaPkcsOaep(params) => make_mechanism(mechanism, params),
+            Mechanism::RsaAesKeyWrap(params) => make_mechanism(mechanism, params),
+            Mechanism::DhPkcsDerive(params) => CK_MECHANISM {
+                mechanism,
+                pParameter: params.public_value().as_ptr() as *mut c_void,
+                ulParameterLen: params
+                    .public_value()
+                    .len()
+                    .try_into()
+                    .expect("usize can not fit in CK_ULONG"),
+            },
+            Mechanism::X942DhDerive(params) => make_mechanism(mechanism, params),
             Mechanism::Ecdh1Derive(params) => make_mechanism(mechanism, params),
+            Mechanism::EcdhAesKeyWrap(params) => make_mechanism(mechanism, params),
+            Mechanism::EddsaWithParams(params) => make_mechanism(mechanism, params),
             Mechanism::HkdfDerive(params) | Mechanism::HkdfData(params) => {
                 make_mechanism(mechanism, params)
             }
+            Mechanism::Pkcs5Pbkd2(params) => make_mechanism(mechanism, params),
+            Mechanism::ChaCha20(params) => make_mechanism(mechanism, params),
+            Mechanism::ChaCha20Poly1305(params) | Mechanism::Salsa20Poly1305(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Sp800108CounterKdf(params)
+            | Mechanism::Sp800108DoublePipelineKdf(params) => make_mechanism(mechanism, params),
+            Mechanism::Sp800108FeedbackKdf(params) => make_mechanism(mechanism, params),
+            Mechanism::Tls12MasterKeyDerive(params) | Mechanism::Tls12MasterKeyDeriveDh(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Tls12KeyAndMacDerive(params) | Mechanism::Tls12KeySafeDerive(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Tls12Mac(params) => make_mechanism(mechanism, params),
+            Mechanism::Tls12Kdf(params) => make_mechanism(mechanism, params),
+            Mechanism::Sha3_224RsaPkcsPss(params)
+            | Mechanism::Sha3_256RsaPkcsPss(params)
+            | Mechanism::Sha3_384RsaPkcsPss(params)
+            | Mechanism::Sha3_512RsaPkcsPss(params) => make_mechanism(mechanism, params),
+            Mechanism::ConcatenateBaseAndKey(key) => make_mechanism(mechanism, key),
+            Mechanism::ConcatenateBaseAndData(params)
+            | Mechanism::ConcatenateDataAndBase(params)
+            | Mechanism::XorBaseAndData(params) => make_mechanism(mechanism, params),
+            Mechanism::ExtractKeyFromKey(params) => make_mechanism(mechanism, params),
+            Mechanism::VendorDefined(mechanism) => mechanism.into(),
+            Mechanism::AesCMacGeneral(params) => make_mechanism(mechanism, params),
+            Mechanism::Sha1HmacGeneral(params) => make_mechanism(mechanism, params),
+            Mechanism::Sha224HmacGeneral(params) | Mechanism::Sha3_224HmacGeneral(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Sha256HmacGeneral(params) | Mechanism::Sha3_256HmacGeneral(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Sha384HmacGeneral(params) | Mechanism::Sha3_384HmacGeneral(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Sha512HmacGeneral(params) | Mechanism::Sha3_512HmacGeneral(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::CamelliaCtr(params) => make_mechanism(mechanism, params),
+            Mechanism::CamelliaMacGeneral(params) => make_mechanism(mechanism, params),
+            Mechanism::CamelliaCbc(params) | Mechanism::CamelliaCbcPad(params) => {
+                make_mechanism(mechanism, params)
+            }
+            Mechanism::Gost28147(params)
+            | Mechanism::Gost28147Mac(Some(params))
+            | Mechanism::Gost28147KeyWrap(Some(params)) => make_mechanism(mechanism, params),
             // Mechanisms without parameters
             Mechanism::AesKeyGen
             | Mechanism::AesEcb
+            | Mechanism::AesGcmMessage
             | Mechanism::AesKeyWrap
             | Mechanism::AesKeyWrapPad
+            | Mechanism::AesKeyWrapKwp(None)
             | Mechanism::AesCMac
             | Mechanism::RsaPkcsKeyPairGen
             | Mechanism::RsaPkcs
@@ -1064,7 +2420,6 @@ impl From<&Mechanism<'_>> for CK_MECHANISM {
             | Mechanism::EccKeyPairGen
             | Mechanism::EccEdwardsKeyPairGen
             | Mechanism::EccMontgomeryKeyPairGen
-            | Mechanism::Eddsa
             | Mechanism::Ecdsa
             | Mechanism::EcdsaSha1
             | Mechanism::EcdsaSha224
@@ -1082,7 +2437,63 @@ impl From<&Mechanism<'_>> for CK_MECHANISM {
             | Mechanism::Sha384Hmac
             | Mechanism::Sha512Hmac
             | Mechanism::GenericSecretKeyGen
-            | Mechanism::HkdfKeyGen => CK_MECHANISM {
+            | Mechanism::HkdfKeyGen
+            | Mechanism::Eddsa
+            | Mechanism::ChaCha20KeyGen
+            | Mechanism::Sha3_224
+            | Mechanism::Sha3_256
+            | Mechanism::Sha3_384
+            | Mechanism::Sha3_512
+            | Mechanism::Sha3_224RsaPkcs
+            | Mechanism::Sha3_256RsaPkcs
+            | Mechanism::Sha3_384RsaPkcs
+            | Mechanism::Sha3_512RsaPkcs
+            | Mechanism::EcdsaSha3_224
+            | Mechanism::EcdsaSha3_256
+            | Mechanism::EcdsaSha3_384
+            | Mechanism::EcdsaSha3_512
+            | Mechanism::Sha3_224Hmac
+            | Mechanism::Sha3_256Hmac
+            | Mechanism::Sha3_384Hmac
+            | Mechanism::Sha3_512Hmac
+            | Mechanism::AesXtsKeyGen
+            | Mechanism::Sha3_224KeyGen
+            | Mechanism::Sha3_256KeyGen
+            | Mechanism::Sha3_384KeyGen
+            | Mechanism::Sha3_512KeyGen
+            | Mechanism::Sha1KeyGen
+            | Mechanism::Sha224KeyGen
+            | Mechanism::Sha256KeyGen
+            | Mechanism::Sha384KeyGen
+            | Mechanism::Sha512KeyGen
+            | Mechanism::CamelliaKeyGen
+            | Mechanism::CamelliaEcb
+            | Mechanism::CamelliaMac
+            | Mechanism::GostR3410KeyPairGen
+            | Mechanism::GostR3410
+            | Mechanism::GostR3410WithGostR3411
+            | Mechanism::GostR3411
+            | Mechanism::GostR3411Hmac
+            | Mechanism::Gost28147KeyGen
+            | Mechanism::Gost28147Ecb
+            | Mechanism::Gost28147Mac(None)
+            | Mechanism::Gost28147KeyWrap(None)
+            | Mechanism::DhPkcsKeyPairGen
+            | Mechanism::DhPkcsParameterGen
+            | Mechanism::X942DhKeyPairGen
+            | Mechanism::X942DhParameterGen
+            | Mechanism::DsaKeyPairGen
+            | Mechanism::DsaParameterGen
+            | Mechanism::Dsa
+            | Mechanism::DsaSha1
+            | Mechanism::DsaSha224
+            | Mechanism::DsaSha256
+            | Mechanism::DsaSha384
+            | Mechanism::DsaSha512
+            | Mechanism::MlKemKeyPairGen
+            | Mechanism::MlKem
+            | Mechanism::MlDsaKeyPairGen
+            | Mechanism::MlDsa => CK_MECHANISM {
                 mechanism,
                 pParameter: null_mut(),
                 ulParameterLen: 0,
@@ -1091,6 +2502,36 @@ impl From<&Mechanism<'_>> for CK_MECHANISM {
     }
 }
 
+/// Per-message parameters of the message-based functions of PKCS #11 3.0
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MessageParam<'a> {
+    /// AES-GCM message parameters
+    AesGcmMessage(aead::GcmMessageParams<'a>),
+    /// EdDSA message parameters
+    Eddsa(eddsa::EddsaParams<'a>),
+}
+
+impl MessageParam<'_> {
+    pub(crate) fn as_ptr(&self) -> *mut c_void {
+        match self {
+            // SAFETY: the token does not modify the parameters themselves,
+            // only the buffers they point to.
+            MessageParam::AesGcmMessage(params) => params as *const _ as *mut c_void,
+            MessageParam::Eddsa(params) => params as *const _ as *mut c_void,
+        }
+    }
+
+    pub(crate) fn len(&self) -> CK_ULONG {
+        match self {
+            MessageParam::AesGcmMessage(_) => size_of::<CK_GCM_MESSAGE_PARAMS>(),
+            MessageParam::Eddsa(_) => size_of::<CK_EDDSA_PARAMS>(),
+        }
+        .try_into()
+        .expect("usize can not fit in CK_ULONG")
+    }
+}
+
 // Make a CK_MECHANISM from mechanism type and parameter
 fn make_mechanism<T>(mechanism: CK_MECHANISM_TYPE, param: &T) -> CK_MECHANISM {
     CK_MECHANISM {
@@ -1148,3 +2589,157 @@ impl TryFrom<psa_crypto::types::algorithm::Algorithm> for Mechanism<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aes_key_wrap_iv() {
+        let mechanism = Mechanism::AesKeyWrap;
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_AES_KEY_WRAP);
+        assert!(raw.pParameter.is_null());
+        assert_eq!(raw.ulParameterLen, 0);
+
+        let mechanism = Mechanism::AesKeyWrapPadWithIv([0xa6; 8]);
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_AES_KEY_WRAP_PAD);
+        assert!(!raw.pParameter.is_null());
+        assert_eq!(raw.ulParameterLen, 8);
+
+        let mechanism = Mechanism::AesKeyWrapKwp(Some([0xa6, 0x59, 0x59, 0xa6]));
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_AES_KEY_WRAP_KWP);
+        assert!(!raw.pParameter.is_null());
+        assert_eq!(raw.ulParameterLen, 4);
+    }
+
+    #[test]
+    fn aes_gcm_message_no_parameter() {
+        let raw = CK_MECHANISM::from(&Mechanism::AesGcmMessage);
+        assert_eq!(raw.mechanism, CKM_AES_GCM);
+        assert!(raw.pParameter.is_null());
+        assert_eq!(raw.ulParameterLen, 0);
+    }
+
+    #[test]
+    fn aes_cmac_general_length() {
+        assert!(matches!(
+            Mechanism::aes_cmac_general(4),
+            Ok(Mechanism::AesCMacGeneral(len)) if **len == 4
+        ));
+        assert!(Mechanism::aes_cmac_general(16).is_ok());
+        assert!(matches!(
+            Mechanism::aes_cmac_general(0),
+            Err(Error::InvalidValue)
+        ));
+        assert!(matches!(
+            Mechanism::aes_cmac_general(17),
+            Err(Error::InvalidValue)
+        ));
+    }
+
+    #[test]
+    fn mac_length_parameter() {
+        let mechanism = Mechanism::aes_cmac_general(8).unwrap();
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_AES_CMAC_GENERAL);
+        assert_eq!(raw.ulParameterLen as usize, size_of::<CK_ULONG>());
+        assert_eq!(unsafe { *(raw.pParameter as *const CK_ULONG) }, 8);
+    }
+
+    #[test]
+    fn vendor_defined_mechanism_type() {
+        let vendor = MechanismType::new_vendor_defined(0x17);
+        assert_eq!(*vendor, CKM_VENDOR_DEFINED + 0x17);
+        assert_eq!(MechanismType::try_from(*vendor).unwrap(), vendor);
+        assert_eq!(vendor.to_string(), "CKM_VENDOR_DEFINED | 0x17");
+        assert!(MechanismType::try_from(CKM_VENDOR_DEFINED - 1).is_err());
+        assert!(vendor.is_vendor_defined());
+        assert!(!MechanismType::AES_CBC.is_vendor_defined());
+    }
+
+    #[test]
+    fn unknown_mechanism_type() {
+        let unknown = MechanismType::new_unchecked(0x7fff);
+        assert_eq!(*unknown, 0x7fff);
+        assert_eq!(unknown.to_string(), "unknown 00007fff");
+        assert!(MechanismType::try_from(*unknown).is_err());
+    }
+
+    #[test]
+    fn sha3_mechanism_types() {
+        let mechanism = Mechanism::Sha3_256RsaPkcsPss(rsa::PkcsPssParams {
+            hash_alg: MechanismType::SHA3_256,
+            mgf: rsa::PkcsMgfType::MGF1_SHA3_256,
+            s_len: 32.into(),
+        });
+        assert_eq!(
+            mechanism.mechanism_type(),
+            MechanismType::SHA3_256_RSA_PKCS_PSS
+        );
+        assert_eq!(
+            MechanismType::try_from(CKM_SHA3_512_HMAC).unwrap(),
+            MechanismType::SHA3_512_HMAC
+        );
+        assert_eq!(
+            MechanismType::ECDSA_SHA3_384.to_string(),
+            "CKM_ECDSA_SHA3_384"
+        );
+        assert_eq!(
+            Mechanism::Sha3_256KeyGen.mechanism_type(),
+            MechanismType::SHA3_256_KEY_GEN
+        );
+        let mechanism = Mechanism::sha3_384_hmac_general(24).unwrap();
+        assert_eq!(
+            MechanismType::try_from(*mechanism.mechanism_type()).unwrap(),
+            MechanismType::SHA3_384_HMAC_GENERAL
+        );
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(unsafe { *(raw.pParameter as *const CK_ULONG) }, 24);
+    }
+
+    #[test]
+    fn concatenate_base_and_key_handle() {
+        let mechanism = Mechanism::ConcatenateBaseAndKey(ObjectHandle::new(7));
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_CONCATENATE_BASE_AND_KEY);
+        assert_eq!(raw.ulParameterLen as usize, size_of::<CK_OBJECT_HANDLE>());
+        assert_eq!(unsafe { *(raw.pParameter as *const CK_OBJECT_HANDLE) }, 7);
+    }
+
+    #[test]
+    fn hmac_general_mac_length() {
+        let mechanism = Mechanism::sha256_hmac_general(16).unwrap();
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_SHA256_HMAC_GENERAL);
+        assert_eq!(
+            raw.ulParameterLen as usize,
+            size_of::<CK_MAC_GENERAL_PARAMS>()
+        );
+        assert_eq!(
+            unsafe { *(raw.pParameter as *const CK_MAC_GENERAL_PARAMS) },
+            16
+        );
+        assert_eq!(
+            MechanismType::SHA256_KEY_GEN.to_string(),
+            "CKM_SHA256_KEY_GEN"
+        );
+    }
+
+    #[test]
+    fn hmac_general_length() {
+        assert!(Mechanism::sha1_hmac_general(20).is_ok());
+        assert!(Mechanism::sha512_hmac_general(64).is_ok());
+        assert!(Mechanism::sha3_224_hmac_general(28).is_ok());
+        for mechanism in [
+            Mechanism::sha1_hmac_general(21),
+            Mechanism::sha256_hmac_general(33),
+            Mechanism::sha3_384_hmac_general(49),
+            Mechanism::sha512_hmac_general(0),
+        ] {
+            assert!(matches!(mechanism, Err(Error::InvalidValue)));
+        }
+    }
+}
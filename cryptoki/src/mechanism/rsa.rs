@@ -39,6 +39,22 @@ impl PkcsMgfType {
     pub const MGF1_SHA512: PkcsMgfType = PkcsMgfType {
         val: CKG_MGF1_SHA512,
     };
+    /// MGF1 SHA3-224
+    pub const MGF1_SHA3_224: PkcsMgfType = PkcsMgfType {
+        val: CKG_MGF1_SHA3_224,
+    };
+    /// MGF1 SHA3-256
+    pub const MGF1_SHA3_256: PkcsMgfType = PkcsMgfType {
+        val: CKG_MGF1_SHA3_256,
+    };
+    /// MGF1 SHA3-384
+    pub const MGF1_SHA3_384: PkcsMgfType = PkcsMgfType {
+        val: CKG_MGF1_SHA3_384,
+    };
+    /// MGF1 SHA3-512
+    pub const MGF1_SHA3_512: PkcsMgfType = PkcsMgfType {
+        val: CKG_MGF1_SHA3_512,
+    };
 }
 
 impl Deref for PkcsMgfType {
@@ -65,6 +81,10 @@ impl TryFrom<CK_RSA_PKCS_MGF_TYPE> for PkcsMgfType {
             CKG_MGF1_SHA256 => Ok(PkcsMgfType::MGF1_SHA256),
             CKG_MGF1_SHA384 => Ok(PkcsMgfType::MGF1_SHA384),
             CKG_MGF1_SHA512 => Ok(PkcsMgfType::MGF1_SHA512),
+            CKG_MGF1_SHA3_224 => Ok(PkcsMgfType::MGF1_SHA3_224),
+            CKG_MGF1_SHA3_256 => Ok(PkcsMgfType::MGF1_SHA3_256),
+            CKG_MGF1_SHA3_384 => Ok(PkcsMgfType::MGF1_SHA3_384),
+            CKG_MGF1_SHA3_512 => Ok(PkcsMgfType::MGF1_SHA3_512),
             other => {
                 error!(
                     "Mask Generation Function type {} is not one of the valid values.",
@@ -95,10 +115,9 @@ impl<'a> PkcsOaepSource<'a> {
     }
 
     pub(crate) fn source_ptr(&self) -> *const c_void {
-        if let Some(source_data) = self.0 {
-            source_data.as_ptr() as _
-        } else {
-            std::ptr::null()
+        match self.0 {
+            Some(source_data) if !source_data.is_empty() => source_data.as_ptr() as _,
+            _ => std::ptr::null(),
         }
     }
 
@@ -131,6 +150,52 @@ pub struct PkcsPssParams {
     pub s_len: Ulong,
 }
 
+impl PkcsPssParams {
+    /// Construct new `PkcsPssParams`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_alg` - The hash algorithm used in the PSS encoding.
+    /// * `mgf` - The mask generation function to use on the encoded block.
+    /// * `s_len` - The length, in bytes, of the salt.  Note that this is a
+    ///   byte count, unlike the bit lengths used by some other mechanisms.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::TryFromInt`] if `s_len` does not fit
+    /// into a `CK_ULONG`.
+    pub fn new(hash_alg: MechanismType, mgf: PkcsMgfType, s_len: u64) -> Result<Self> {
+        Ok(PkcsPssParams {
+            hash_alg,
+            mgf,
+            s_len: usize::try_from(s_len)?.try_into()?,
+        })
+    }
+}
+
+impl Mechanism<'_> {
+    /// `Sha256RsaPkcsPss` with SHA-256 as hash and MGF1 function, and a salt
+    /// of `s_len` bytes.
+    pub fn sha256_rsa_pkcs_pss(s_len: u64) -> Result<Self> {
+        PkcsPssParams::new(MechanismType::SHA256, PkcsMgfType::MGF1_SHA256, s_len)
+            .map(Mechanism::Sha256RsaPkcsPss)
+    }
+
+    /// `Sha384RsaPkcsPss` with SHA-384 as hash and MGF1 function, and a salt
+    /// of `s_len` bytes.
+    pub fn sha384_rsa_pkcs_pss(s_len: u64) -> Result<Self> {
+        PkcsPssParams::new(MechanismType::SHA384, PkcsMgfType::MGF1_SHA384, s_len)
+            .map(Mechanism::Sha384RsaPkcsPss)
+    }
+
+    /// `Sha512RsaPkcsPss` with SHA-512 as hash and MGF1 function, and a salt
+    /// of `s_len` bytes.
+    pub fn sha512_rsa_pkcs_pss(s_len: u64) -> Result<Self> {
+        PkcsPssParams::new(MechanismType::SHA512, PkcsMgfType::MGF1_SHA512, s_len)
+            .map(Mechanism::Sha512RsaPkcsPss)
+    }
+}
+
 /// Parameters of the RsaPkcsOaep mechanism
 #[derive(Copy, Debug, Clone)]
 #[repr(C)]
@@ -156,7 +221,7 @@ impl<'a> PkcsOaepParams<'a> {
     /// # Arguments
     ///
     /// * `hash_alg` - The message digest algorithm used to calculate
-    ///    a digest of the encoding parameter.
+    ///   a digest of the encoding parameter.
     /// * `mgf` - The mask generation function to use on the encoded block.
     /// * `encoding_parameter` - The encoding parameter, also known as the label.
     pub fn new(
@@ -178,6 +243,29 @@ impl<'a> PkcsOaepParams<'a> {
     pub fn hash_alg(&self) -> MechanismType {
         self.hash_alg
     }
+
+    /// Get the mask generation function for the `PkcsOaepParams`.
+    pub fn mgf(&self) -> PkcsMgfType {
+        self.mgf
+    }
+
+    /// Get the encoding parameter for the `PkcsOaepParams`.
+    ///
+    /// An empty encoding parameter is returned as [`PkcsOaepSource::empty`].
+    pub fn encoding_parameter(&self) -> PkcsOaepSource<'a> {
+        if self.source_data.is_null() {
+            PkcsOaepSource::empty()
+        } else {
+            // SAFETY: In the constructor, a non-null source data pointer
+            // always comes from a &'a [u8]
+            PkcsOaepSource::data_specified(unsafe {
+                std::slice::from_raw_parts(
+                    self.source_data as *const u8,
+                    *self.source_data_len as _,
+                )
+            })
+        }
+    }
 }
 
 impl<'a> From<PkcsOaepParams<'a>> for Mechanism<'a> {
@@ -186,6 +274,84 @@ impl<'a> From<PkcsOaepParams<'a>> for Mechanism<'a> {
     }
 }
 
+/// Parameters of the RsaAesKeyWrap mechanism
+///
+/// The key is wrapped with a temporary AES key of `aes_key_bits` bits, which
+/// is itself wrapped with RSA OAEP.  The wrapped key is the RSA OAEP
+/// encryption of the temporary key followed by the AES key wrap with padding
+/// (RFC 5649) of the key, both done in a single
+/// [`Session::wrap_key`](crate::session::Session::wrap_key) call.
+///
+/// # Example
+///
+/// ```no_run
+/// use cryptoki::mechanism::rsa::{PkcsMgfType, PkcsOaepParams, PkcsOaepSource, RsaAesKeyWrapParams};
+/// use cryptoki::mechanism::{Mechanism, MechanismType};
+/// # use cryptoki::object::ObjectHandle;
+/// # use cryptoki::session::Session;
+///
+/// # fn wrap(session: &Session, rsa_public_key: ObjectHandle, key: ObjectHandle) -> testresult::TestResult {
+/// let oaep = PkcsOaepParams::new(
+///     MechanismType::SHA256,
+///     PkcsMgfType::MGF1_SHA256,
+///     PkcsOaepSource::empty(),
+/// );
+/// let params = RsaAesKeyWrapParams::new(256, &oaep)?;
+/// let wrapped_key = session.wrap_key(&Mechanism::RsaAesKeyWrap(params), rsa_public_key, key)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Debug, Clone)]
+#[repr(C)]
+pub struct RsaAesKeyWrapParams<'a> {
+    /// length of the temporary AES key in bits
+    aes_key_bits: Ulong,
+    /// parameters of the OAEP wrapping of the temporary AES key
+    oaep_params: &'a PkcsOaepParams<'a>,
+}
+
+impl<'a> RsaAesKeyWrapParams<'a> {
+    /// Construct a new `RsaAesKeyWrapParams`.
+    ///
+    /// # Arguments
+    ///
+    /// * `aes_key_bits` - The length of the temporary AES key in bits, 128,
+    ///   192 or 256.
+    /// * `oaep_params` - The parameters of the OAEP wrapping of the temporary
+    ///   AES key.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `aes_key_bits` is not
+    /// a valid AES key length.
+    pub fn new(aes_key_bits: u64, oaep_params: &'a PkcsOaepParams<'a>) -> Result<Self> {
+        if !matches!(aes_key_bits, 128 | 192 | 256) {
+            error!("{} is not a valid AES key length in bits", aes_key_bits);
+            return Err(Error::InvalidValue);
+        }
+        Ok(RsaAesKeyWrapParams {
+            aes_key_bits: aes_key_bits.into(),
+            oaep_params,
+        })
+    }
+
+    /// Get the length of the temporary AES key in bits.
+    pub fn aes_key_bits(&self) -> Ulong {
+        self.aes_key_bits
+    }
+
+    /// Get the parameters of the OAEP wrapping of the temporary AES key.
+    pub fn oaep_params(&self) -> &'a PkcsOaepParams<'a> {
+        self.oaep_params
+    }
+}
+
+impl<'a> From<RsaAesKeyWrapParams<'a>> for Mechanism<'a> {
+    fn from(params: RsaAesKeyWrapParams<'a>) -> Self {
+        Mechanism::RsaAesKeyWrap(params)
+    }
+}
+
 #[cfg(feature = "psa-crypto-conversions")]
 #[allow(deprecated)]
 impl PkcsMgfType {
@@ -206,3 +372,63 @@ impl PkcsMgfType {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pss_getters() {
+        let params =
+            PkcsPssParams::new(MechanismType::SHA384, PkcsMgfType::MGF1_SHA384, 48).unwrap();
+        assert_eq!(params.hash_alg, MechanismType::SHA384);
+        assert_eq!(params.mgf, PkcsMgfType::MGF1_SHA384);
+        assert_eq!(*params.s_len, 48);
+    }
+
+    #[test]
+    fn oaep_encoding_parameter() {
+        let params = PkcsOaepParams::new(
+            MechanismType::SHA256,
+            PkcsMgfType::MGF1_SHA256,
+            PkcsOaepSource::data_specified(&[]),
+        );
+        assert!(params.source_data.is_null());
+        assert_eq!(*params.source_data_len, 0);
+        assert!(params.encoding_parameter().0.is_none());
+
+        let label = [1, 2, 3, 4];
+        let params = PkcsOaepParams::new(
+            MechanismType::SHA256,
+            PkcsMgfType::MGF1_SHA256,
+            PkcsOaepSource::data_specified(&label),
+        );
+        assert_eq!(params.hash_alg, MechanismType::SHA256);
+        assert_eq!(params.mgf, PkcsMgfType::MGF1_SHA256);
+        assert_eq!(params.encoding_parameter().0, Some(&label[..]));
+    }
+
+    #[test]
+    fn rsa_aes_key_wrap_params() {
+        let oaep = PkcsOaepParams::new(
+            MechanismType::SHA256,
+            PkcsMgfType::MGF1_SHA256,
+            PkcsOaepSource::empty(),
+        );
+        assert!(matches!(
+            RsaAesKeyWrapParams::new(100, &oaep),
+            Err(Error::InvalidValue)
+        ));
+        let params = RsaAesKeyWrapParams::new(256, &oaep).unwrap();
+        assert_eq!(*params.aes_key_bits(), 256);
+        assert_eq!(params.oaep_params().hash_alg(), MechanismType::SHA256);
+
+        // The layout is the one of CK_RSA_AES_KEY_WRAP_PARAMS
+        let raw: CK_RSA_AES_KEY_WRAP_PARAMS = unsafe { std::mem::transmute(params) };
+        assert_eq!(raw.ulAESKeyBits, 256);
+        assert!(std::ptr::eq(
+            raw.pOAEPParams as *const PkcsOaepParams,
+            &oaep
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Mechanisms of miscellaneous simple key derivation, by concatenation, XOR or
+//! extraction
+//! See: <https://docs.oasis-open.org/pkcs11/pkcs11-curr/v3.0/os/pkcs11-curr-v3.0-os.html#_Toc30061451>
+
+use crate::error::Result;
+use crate::types::Ulong;
+use cryptoki_sys::{CK_EXTRACT_PARAMS, CK_KEY_DERIVATION_STRING_DATA};
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+use std::slice;
+
+/// Data to combine with the base key.
+///
+/// The length of the derived key defaults to the length of the combined
+/// data; a shorter [`Attribute::ValueLen`](crate::object::Attribute::ValueLen)
+/// in the template truncates it.
+///
+/// This structure wraps a `CK_KEY_DERIVATION_STRING_DATA` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct KeyDerivationStringData<'a> {
+    inner: CK_KEY_DERIVATION_STRING_DATA,
+    /// Marker type to ensure we don't outlive the data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> KeyDerivationStringData<'a> {
+    /// Construct the parameter from the data to combine with the base key.
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        Ok(Self {
+            inner: CK_KEY_DERIVATION_STRING_DATA {
+                pData: data.as_ptr() as *mut _,
+                ulLen: data.len().try_into()?,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The data to combine with the base key.
+    pub fn data(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the data always comes from a &'a [u8]
+        unsafe { slice::from_raw_parts(self.inner.pData, self.inner.ulLen as _) }
+    }
+}
+
+/// Position of the first bit of the base key extracted to the derived key.
+///
+/// The bits are numbered from the most significant bit of the first byte of
+/// the base key, and wrap around to its start.  The index does not need to be
+/// a multiple of 8.  The length of the derived key is set with
+/// [`Attribute::ValueLen`](crate::object::Attribute::ValueLen) in the
+/// template.
+///
+/// This structure wraps a `CK_EXTRACT_PARAMS` value.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct ExtractParams {
+    inner: CK_EXTRACT_PARAMS,
+}
+
+impl ExtractParams {
+    /// Construct the parameter from the index of the first extracted bit.
+    pub fn new(bit_index: u64) -> Result<Self> {
+        Ok(Self {
+            inner: usize::try_from(bit_index)?.try_into()?,
+        })
+    }
+
+    /// The index of the first extracted bit.
+    pub fn bit_index(&self) -> Ulong {
+        self.inner.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn string_data() {
+        let data = [1, 2, 3];
+        let params = KeyDerivationStringData::new(&data).unwrap();
+        assert_eq!(params.data(), data);
+        assert_eq!(params.inner.ulLen, 3);
+    }
+
+    #[test]
+    fn extract_bit_index() {
+        let params = ExtractParams::new(13).unwrap();
+        assert_eq!(*params.bit_index(), 13);
+    }
+}
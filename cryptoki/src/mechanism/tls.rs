@@ -0,0 +1,552 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! TLS 1.2 mechanism types
+
+use crate::error::{Error, Result};
+use crate::object::ObjectHandle;
+use crate::types::{Ulong, Version};
+use cryptoki_sys::*;
+use log::error;
+use std::convert::{TryFrom, TryInto};
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::slice;
+
+use super::MechanismType;
+
+/// The client and server random values of a TLS handshake.
+///
+/// This structure wraps a `CK_SSL3_RANDOM_DATA` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct TlsRandomData<'a> {
+    inner: CK_SSL3_RANDOM_DATA,
+    /// Marker type to ensure we don't outlive the random values
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> TlsRandomData<'a> {
+    /// Construct the random data from the `ClientHello.random` and
+    /// `ServerHello.random` values.
+    pub fn new(client_random: &'a [u8], server_random: &'a [u8]) -> Result<Self> {
+        Ok(Self {
+            inner: CK_SSL3_RANDOM_DATA {
+                pClientRandom: client_random.as_ptr() as *mut _,
+                ulClientRandomLen: client_random.len().try_into()?,
+                pServerRandom: server_random.as_ptr() as *mut _,
+                ulServerRandomLen: server_random.len().try_into()?,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The client random value.
+    pub fn client_random(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the client random always comes from a
+        // &'a [u8]
+        unsafe {
+            slice::from_raw_parts(self.inner.pClientRandom, self.inner.ulClientRandomLen as _)
+        }
+    }
+
+    /// The server random value.
+    pub fn server_random(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the server random always comes from a
+        // &'a [u8]
+        unsafe {
+            slice::from_raw_parts(self.inner.pServerRandom, self.inner.ulServerRandomLen as _)
+        }
+    }
+}
+
+/// Parameters for the TLS 1.2 master key derivation mechanisms.
+///
+/// This structure wraps a `CK_TLS12_MASTER_KEY_DERIVE_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct Tls12MasterKeyDeriveParams<'a> {
+    inner: CK_TLS12_MASTER_KEY_DERIVE_PARAMS,
+    /// Marker type to ensure we don't outlive the random data and the version
+    _marker: PhantomData<(&'a [u8], &'a mut Version)>,
+}
+
+impl<'a> Tls12MasterKeyDeriveParams<'a> {
+    /// Construct TLS 1.2 master key derivation parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf_hash_mechanism` - The hash mechanism used by the TLS 1.2 PRF,
+    ///   e.g. [`MechanismType::SHA256`].
+    ///
+    /// * `random_info` - The client and server random values.
+    ///
+    /// * `version` - Where the token writes the protocol version found in the
+    ///   pre-master secret.  It must be `None` for
+    ///   [`Mechanism::Tls12MasterKeyDeriveDh`](super::Mechanism::Tls12MasterKeyDeriveDh),
+    ///   whose pre-master secret does not hold a version.
+    pub fn new(
+        prf_hash_mechanism: MechanismType,
+        random_info: TlsRandomData<'a>,
+        version: Option<&'a mut Version>,
+    ) -> Self {
+        Self {
+            inner: CK_TLS12_MASTER_KEY_DERIVE_PARAMS {
+                RandomInfo: random_info.inner,
+                pVersion: match version {
+                    // Version has the same layout as CK_VERSION
+                    Some(version) => version as *mut Version as *mut CK_VERSION,
+                    None => null_mut(),
+                },
+                prfHashMechanism: *prf_hash_mechanism,
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// The hash mechanism used by the PRF.
+    pub fn prf_hash_mechanism(&self) -> MechanismType {
+        MechanismType {
+            val: self.inner.prfHashMechanism,
+        }
+    }
+
+    /// The client and server random values.
+    pub fn random_info(&self) -> TlsRandomData<'a> {
+        TlsRandomData {
+            inner: self.inner.RandomInfo,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The protocol version written by the token, if a version was requested.
+    pub fn version(&self) -> Option<Version> {
+        if self.inner.pVersion.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null version always comes
+            // from a &'a mut Version
+            Some(unsafe { *self.inner.pVersion }.into())
+        }
+    }
+}
+
+/// The keys and IVs returned by the TLS 1.2 key and MAC derivation mechanisms.
+///
+/// This structure wraps a `CK_SSL3_KEY_MAT_OUT` structure, along with the
+/// buffers receiving the IVs.
+#[derive(Debug)]
+pub struct Tls12KeyMaterialOutput {
+    inner: CK_SSL3_KEY_MAT_OUT,
+    iv_client: Vec<u8>,
+    iv_server: Vec<u8>,
+}
+
+impl Tls12KeyMaterialOutput {
+    /// Construct an output for keys with IVs of the size given in `params`.
+    pub(crate) fn new(params: &Tls12KeyMaterialParams) -> Result<Self> {
+        let iv_len = usize::try_from(params.inner.ulIVSizeInBits / 8)?;
+        let mut iv_client = vec![0; iv_len];
+        let mut iv_server = vec![0; iv_len];
+        let (p_iv_client, p_iv_server) = if iv_len == 0 {
+            (null_mut(), null_mut())
+        } else {
+            // The vectors own their heap buffers, which do not move with them
+            (iv_client.as_mut_ptr(), iv_server.as_mut_ptr())
+        };
+        Ok(Self {
+            inner: CK_SSL3_KEY_MAT_OUT {
+                hClientMacSecret: CK_INVALID_HANDLE,
+                hServerMacSecret: CK_INVALID_HANDLE,
+                hClientKey: CK_INVALID_HANDLE,
+                hServerKey: CK_INVALID_HANDLE,
+                pIVClient: p_iv_client,
+                pIVServer: p_iv_server,
+            },
+            iv_client,
+            iv_server,
+        })
+    }
+
+    fn handle(handle: CK_OBJECT_HANDLE) -> Option<ObjectHandle> {
+        if handle == CK_INVALID_HANDLE {
+            None
+        } else {
+            Some(ObjectHandle::new(handle))
+        }
+    }
+
+    /// The client MAC secret, if one was derived.
+    pub fn client_mac_secret(&self) -> Option<ObjectHandle> {
+        Self::handle(self.inner.hClientMacSecret)
+    }
+
+    /// The server MAC secret, if one was derived.
+    pub fn server_mac_secret(&self) -> Option<ObjectHandle> {
+        Self::handle(self.inner.hServerMacSecret)
+    }
+
+    /// The client key, if one was derived.
+    pub fn client_key(&self) -> Option<ObjectHandle> {
+        Self::handle(self.inner.hClientKey)
+    }
+
+    /// The server key, if one was derived.
+    pub fn server_key(&self) -> Option<ObjectHandle> {
+        Self::handle(self.inner.hServerKey)
+    }
+
+    /// The client IV.
+    pub fn client_iv(&self) -> &[u8] {
+        &self.iv_client
+    }
+
+    /// The server IV.
+    pub fn server_iv(&self) -> &[u8] {
+        &self.iv_server
+    }
+}
+
+/// Parameters for the TLS 1.2 key and MAC derivation mechanisms.
+///
+/// This structure wraps a `CK_TLS12_KEY_MAT_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct Tls12KeyMaterialParams<'a> {
+    inner: CK_TLS12_KEY_MAT_PARAMS,
+    /// Marker type to ensure we don't outlive the random data
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Tls12KeyMaterialParams<'a> {
+    /// Construct TLS 1.2 key and MAC derivation parameters.
+    ///
+    /// Export cipher suites are not allowed in TLS 1.2, so the keys are never
+    /// derived as export keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf_hash_mechanism` - The hash mechanism used by the TLS 1.2 PRF.
+    ///
+    /// * `random_info` - The client and server random values.
+    ///
+    /// * `mac_size_bits` - The size of the MAC secrets, in bits.  No MAC
+    ///   secrets are derived if it is 0, e.g. for AEAD cipher suites.
+    ///
+    /// * `key_size_bits` - The size of the keys, in bits.
+    ///
+    /// * `iv_size_bits` - The size of the IVs, in bits.  No IVs are derived
+    ///   if it is 0.
+    ///
+    /// The keys and IVs are returned by
+    /// [`Session::derive_tls_key_material`](crate::session::Session::derive_tls_key_material).
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if a size is not a
+    /// multiple of 8.
+    pub fn new(
+        prf_hash_mechanism: MechanismType,
+        random_info: TlsRandomData<'a>,
+        mac_size_bits: u64,
+        key_size_bits: u64,
+        iv_size_bits: u64,
+    ) -> Result<Self> {
+        if mac_size_bits % 8 != 0 || key_size_bits % 8 != 0 || iv_size_bits % 8 != 0 {
+            error!(
+                "TLS MAC, key and IV sizes must be multiples of 8 bits, not {}, {} and {}",
+                mac_size_bits, key_size_bits, iv_size_bits
+            );
+            return Err(Error::InvalidValue);
+        }
+        Ok(Self {
+            inner: CK_TLS12_KEY_MAT_PARAMS {
+                ulMacSizeInBits: usize::try_from(mac_size_bits)?.try_into()?,
+                ulKeySizeInBits: usize::try_from(key_size_bits)?.try_into()?,
+                ulIVSizeInBits: usize::try_from(iv_size_bits)?.try_into()?,
+                bIsExport: CK_FALSE,
+                RandomInfo: random_info.inner,
+                pReturnedKeyMaterial: null_mut(),
+                prfHashMechanism: *prf_hash_mechanism,
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The raw parameters, with the token returning the key material to
+    /// `output`.
+    pub(crate) fn with_output(
+        &self,
+        output: &mut Tls12KeyMaterialOutput,
+    ) -> CK_TLS12_KEY_MAT_PARAMS {
+        CK_TLS12_KEY_MAT_PARAMS {
+            pReturnedKeyMaterial: &mut output.inner,
+            ..self.inner
+        }
+    }
+
+    /// The hash mechanism used by the PRF.
+    pub fn prf_hash_mechanism(&self) -> MechanismType {
+        MechanismType {
+            val: self.inner.prfHashMechanism,
+        }
+    }
+
+    /// The client and server random values.
+    pub fn random_info(&self) -> TlsRandomData<'a> {
+        TlsRandomData {
+            inner: self.inner.RandomInfo,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The size of the MAC secrets, in bits.
+    pub fn mac_size_bits(&self) -> Ulong {
+        self.inner.ulMacSizeInBits.into()
+    }
+
+    /// The size of the keys, in bits.
+    pub fn key_size_bits(&self) -> Ulong {
+        self.inner.ulKeySizeInBits.into()
+    }
+
+    /// The size of the IVs, in bits.
+    pub fn iv_size_bits(&self) -> Ulong {
+        self.inner.ulIVSizeInBits.into()
+    }
+}
+
+/// The party whose `Finished` message a TLS MAC is computed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsKdfIdentifier {
+    /// The server, with the "server finished" label
+    Server,
+    /// The client, with the "client finished" label
+    Client,
+}
+
+impl From<TlsKdfIdentifier> for CK_ULONG {
+    fn from(identifier: TlsKdfIdentifier) -> Self {
+        match identifier {
+            TlsKdfIdentifier::Server => 1,
+            TlsKdfIdentifier::Client => 2,
+        }
+    }
+}
+
+/// Parameters for the TLS 1.2 MAC mechanism, which computes the
+/// `verify_data` of a `Finished` message.
+///
+/// This structure wraps a `CK_TLS_MAC_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct TlsMacParams {
+    inner: CK_TLS_MAC_PARAMS,
+}
+
+impl TlsMacParams {
+    /// Construct TLS MAC parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf_hash_mechanism` - The hash mechanism used by the TLS 1.2 PRF.
+    ///
+    /// * `mac_length` - The length of the MAC, in bytes; 12 for TLS 1.2.
+    ///
+    /// * `identifier` - The party the MAC is computed for.
+    pub fn new(
+        prf_hash_mechanism: MechanismType,
+        mac_length: u64,
+        identifier: TlsKdfIdentifier,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner: CK_TLS_MAC_PARAMS {
+                prfHashMechanism: *prf_hash_mechanism,
+                ulMacLength: usize::try_from(mac_length)?.try_into()?,
+                ulServerOrClient: identifier.into(),
+            },
+        })
+    }
+
+    /// The hash mechanism used by the PRF.
+    pub fn prf_hash_mechanism(&self) -> MechanismType {
+        MechanismType {
+            val: self.inner.prfHashMechanism,
+        }
+    }
+
+    /// The length of the MAC, in bytes.
+    pub fn mac_length(&self) -> Ulong {
+        self.inner.ulMacLength.into()
+    }
+
+    /// The party the MAC is computed for.
+    pub fn identifier(&self) -> TlsKdfIdentifier {
+        if self.inner.ulServerOrClient == 1 {
+            TlsKdfIdentifier::Server
+        } else {
+            TlsKdfIdentifier::Client
+        }
+    }
+}
+
+/// Parameters for the TLS 1.2 KDF mechanism, which derives a key with the
+/// TLS 1.2 PRF, e.g. for keying material exporters.
+///
+/// This structure wraps a `CK_TLS_KDF_PARAMS` structure.
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct TlsKdfParams<'a> {
+    inner: CK_TLS_KDF_PARAMS,
+    /// Marker type to ensure we don't outlive the label, random data and context
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> TlsKdfParams<'a> {
+    /// Construct TLS KDF parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prf_hash_mechanism` - The hash mechanism used by the TLS 1.2 PRF.
+    ///
+    /// * `label` - The label of the PRF.
+    ///
+    /// * `random_info` - The client and server random values.
+    ///
+    /// * `context` - The context value, if any.
+    pub fn new(
+        prf_hash_mechanism: MechanismType,
+        label: &'a [u8],
+        random_info: TlsRandomData<'a>,
+        context: Option<&'a [u8]>,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner: CK_TLS_KDF_PARAMS {
+                prfMechanism: *prf_hash_mechanism,
+                pLabel: label.as_ptr() as *mut _,
+                ulLabelLength: label.len().try_into()?,
+                RandomInfo: random_info.inner,
+                pContextData: match context {
+                    Some(context) => context.as_ptr() as *mut _,
+                    None => null_mut(),
+                },
+                ulContextDataLength: match context {
+                    Some(context) => context.len().try_into()?,
+                    None => 0,
+                },
+            },
+            _marker: PhantomData,
+        })
+    }
+
+    /// The hash mechanism used by the PRF.
+    pub fn prf_hash_mechanism(&self) -> MechanismType {
+        MechanismType {
+            val: self.inner.prfMechanism,
+        }
+    }
+
+    /// The label of the PRF.
+    pub fn label(&self) -> &'a [u8] {
+        // SAFETY: In the constructor, the label always comes from a &'a [u8]
+        unsafe { slice::from_raw_parts(self.inner.pLabel, self.inner.ulLabelLength as _) }
+    }
+
+    /// The context value, if any.
+    pub fn context(&self) -> Option<&'a [u8]> {
+        if self.inner.pContextData.is_null() {
+            None
+        } else {
+            // SAFETY: In the constructor, a non-null context always comes
+            // from a &'a [u8]
+            Some(unsafe {
+                slice::from_raw_parts(self.inner.pContextData, self.inner.ulContextDataLength as _)
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn master_key_derive_version() {
+        let client_random = [1; 32];
+        let server_random = [2; 32];
+        let random_info = TlsRandomData::new(&client_random, &server_random).unwrap();
+        assert_eq!(random_info.client_random(), client_random);
+        assert_eq!(random_info.server_random(), server_random);
+
+        let params = Tls12MasterKeyDeriveParams::new(MechanismType::SHA256, random_info, None);
+        assert_eq!(params.prf_hash_mechanism(), MechanismType::SHA256);
+        assert!(params.version().is_none());
+
+        let mut version = Version::default();
+        let params =
+            Tls12MasterKeyDeriveParams::new(MechanismType::SHA384, random_info, Some(&mut version));
+        // Write the version as a token would
+        unsafe {
+            *params.inner.pVersion = CK_VERSION { major: 3, minor: 3 };
+        }
+        assert_eq!(params.version(), Some(Version::new(3, 3)));
+        assert_eq!(version, Version::new(3, 3));
+    }
+
+    #[test]
+    fn key_material_sizes() {
+        let random_info = TlsRandomData::new(&[1; 32], &[2; 32]).unwrap();
+        assert!(
+            Tls12KeyMaterialParams::new(MechanismType::SHA256, random_info, 0, 128, 7).is_err()
+        );
+        assert!(
+            Tls12KeyMaterialParams::new(MechanismType::SHA256, random_info, 0, 129, 32).is_err()
+        );
+        let params =
+            Tls12KeyMaterialParams::new(MechanismType::SHA256, random_info, 0, 128, 32).unwrap();
+        assert_eq!(*params.mac_size_bits(), 0);
+        assert_eq!(*params.key_size_bits(), 128);
+        assert_eq!(*params.iv_size_bits(), 32);
+        assert!(params.inner.pReturnedKeyMaterial.is_null());
+
+        let mut output = Tls12KeyMaterialOutput::new(&params).unwrap();
+        let inner = params.with_output(&mut output);
+        // Write the key material as a token would
+        unsafe {
+            let key_material = &mut *inner.pReturnedKeyMaterial;
+            key_material.hClientKey = 1;
+            key_material.hServerKey = 2;
+            *key_material.pIVClient = 3;
+            *key_material.pIVServer.add(3) = 4;
+        }
+        assert!(output.client_mac_secret().is_none());
+        assert!(output.server_mac_secret().is_none());
+        assert_eq!(output.client_key(), Some(ObjectHandle::new(1)));
+        assert_eq!(output.server_key(), Some(ObjectHandle::new(2)));
+        assert_eq!(output.client_iv(), [3, 0, 0, 0]);
+        assert_eq!(output.server_iv(), [0, 0, 0, 4]);
+
+        let params =
+            Tls12KeyMaterialParams::new(MechanismType::SHA256, random_info, 0, 128, 0).unwrap();
+        let output = Tls12KeyMaterialOutput::new(&params).unwrap();
+        assert!(output.client_iv().is_empty());
+        assert!(output.inner.pIVClient.is_null());
+    }
+
+    #[test]
+    fn mac_and_kdf() {
+        let params =
+            TlsMacParams::new(MechanismType::SHA256, 12, TlsKdfIdentifier::Client).unwrap();
+        assert_eq!(*params.mac_length(), 12);
+        assert_eq!(params.identifier(), TlsKdfIdentifier::Client);
+        assert_eq!(params.inner.ulServerOrClient, 2);
+
+        let random_info = TlsRandomData::new(&[1; 32], &[2; 32]).unwrap();
+        let params =
+            TlsKdfParams::new(MechanismType::SHA256, b"EXPORTER", random_info, None).unwrap();
+        assert_eq!(params.label(), b"EXPORTER");
+        assert!(params.context().is_none());
+        let params =
+            TlsKdfParams::new(MechanismType::SHA256, b"EXPORTER", random_info, Some(&[5])).unwrap();
+        assert_eq!(params.context(), Some(&[5][..]));
+    }
+}
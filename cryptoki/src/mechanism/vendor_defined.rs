@@ -0,0 +1,123 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Vendor defined mechanisms
+
+use super::{Mechanism, MechanismType};
+use cryptoki_sys::*;
+use std::convert::TryInto;
+use std::ffi::c_void;
+use std::mem::size_of;
+use std::ptr::null_mut;
+
+/// A vendor defined mechanism, with its raw parameter
+///
+/// The parameter is passed to the token as is: the caller is responsible for
+/// laying it out as the vendor documents, including the pointers it may
+/// hold, which must outlive the mechanism.
+#[derive(Debug, Clone, Copy)]
+pub struct VendorDefinedMechanism<'a> {
+    mechanism_type: MechanismType,
+    parameter: Option<&'a [u8]>,
+}
+
+impl<'a> VendorDefinedMechanism<'a> {
+    /// Construct a vendor defined mechanism.
+    ///
+    /// # Arguments
+    ///
+    /// * `mechanism_type` - The type of the mechanism, usually created with
+    ///   [`MechanismType::new_vendor_defined`].
+    ///
+    /// * `parameter` - The bytes of the parameter, if the mechanism has one.
+    pub fn new(mechanism_type: MechanismType, parameter: Option<&'a [u8]>) -> Self {
+        Self {
+            mechanism_type,
+            parameter,
+        }
+    }
+
+    /// Construct a vendor defined mechanism whose parameter is the `repr(C)`
+    /// structure `parameter`, passed by pointer.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be `repr(C)` and have no padding bytes, as every byte of the
+    /// parameter can be read back with [`VendorDefinedMechanism::parameter`].
+    pub unsafe fn with_struct<T>(mechanism_type: MechanismType, parameter: &'a T) -> Self {
+        // SAFETY: the caller guarantees that all the bytes of `T` are
+        // initialized, and they are borrowed for the lifetime of the mechanism
+        let bytes = std::slice::from_raw_parts(parameter as *const T as *const u8, size_of::<T>());
+        Self::new(mechanism_type, Some(bytes))
+    }
+
+    /// The type of the mechanism.
+    pub fn mechanism_type(&self) -> MechanismType {
+        self.mechanism_type
+    }
+
+    /// The bytes of the parameter, if any.
+    pub fn parameter(&self) -> Option<&'a [u8]> {
+        self.parameter
+    }
+}
+
+impl<'a> From<VendorDefinedMechanism<'a>> for Mechanism<'a> {
+    fn from(mechanism: VendorDefinedMechanism<'a>) -> Self {
+        Mechanism::VendorDefined(mechanism)
+    }
+}
+
+impl From<&VendorDefinedMechanism<'_>> for CK_MECHANISM {
+    fn from(mechanism: &VendorDefinedMechanism<'_>) -> Self {
+        CK_MECHANISM {
+            mechanism: *mechanism.mechanism_type,
+            // SAFETY: Although the type signature says *mut, the token does
+            // not modify the parameter
+            pParameter: mechanism
+                .parameter
+                .map_or(null_mut(), |parameter| parameter.as_ptr() as *mut c_void),
+            ulParameterLen: mechanism
+                .parameter
+                .map_or(0, <[u8]>::len)
+                .try_into()
+                .expect("usize can not fit in CK_ULONG"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn raw_mechanism() {
+        let mechanism_type = MechanismType::new_vendor_defined(0x17);
+        let parameter = [1, 2, 3, 4, 5];
+        let mechanism = Mechanism::from(VendorDefinedMechanism::new(
+            mechanism_type,
+            Some(&parameter),
+        ));
+        assert_eq!(mechanism.mechanism_type(), mechanism_type);
+        let raw = CK_MECHANISM::from(&mechanism);
+        assert_eq!(raw.mechanism, CKM_VENDOR_DEFINED | 0x17);
+        assert_eq!(raw.pParameter as *const u8, parameter.as_ptr());
+        assert_eq!(raw.ulParameterLen, 5);
+
+        let raw = CK_MECHANISM::from(&Mechanism::VendorDefined(VendorDefinedMechanism::new(
+            mechanism_type,
+            None,
+        )));
+        assert!(raw.pParameter.is_null());
+        assert_eq!(raw.ulParameterLen, 0);
+
+        let handle: CK_OBJECT_HANDLE = 42;
+        // SAFETY: CK_OBJECT_HANDLE is an integer, without padding
+        let mechanism = unsafe { VendorDefinedMechanism::with_struct(mechanism_type, &handle) };
+        let raw = CK_MECHANISM::from(&Mechanism::VendorDefined(mechanism));
+        assert_eq!(
+            raw.pParameter as *const CK_OBJECT_HANDLE,
+            &handle as *const _
+        );
+        assert_eq!(raw.ulParameterLen as usize, size_of::<CK_OBJECT_HANDLE>());
+    }
+}
@@ -62,6 +62,12 @@ pub enum AttributeType {
     Exponent2,
     /// Determines if a key is extractable and can be wrapped
     Extractable,
+    /// DER-encoding of the OID of the GOST R 34.10 parameter set
+    GostR3410Params,
+    /// DER-encoding of the OID of the GOST R 34.11 parameter set
+    GostR3411Params,
+    /// DER-encoding of the OID of the GOST 28147-89 parameter set
+    Gost28147Params,
     /// Hash of issuer public key
     HashOfIssuerPublicKey,
     /// Hash of subject public key
@@ -90,12 +96,16 @@ pub enum AttributeType {
     ObjectId,
     /// DER encoding of the attribute certificate's subject field
     Owner,
+    /// Parameter set of an ML-KEM or ML-DSA key
+    ParameterSet,
     /// Prime number value of a key
     Prime,
     /// The prime `p` of an RSA private key
     Prime1,
     /// The prime `q` of an RSA private key
     Prime2,
+    /// Length in bits of the prime `p` of DSA domain parameters
+    PrimeBits,
     /// Determines if the object is private
     Private,
     /// Private exponent `d`
@@ -116,6 +126,10 @@ pub enum AttributeType {
     StartDate,
     /// DER-encoding of certificate subject name
     Subject,
+    /// Subprime `q` of DSA domain parameters or of a DSA key
+    Subprime,
+    /// Length in bits of the subprime `q` of DSA domain parameters
+    SubprimeBits,
     /// Determines if the object is a token object
     Token,
     /// Determines if the object is trusted
@@ -128,6 +142,10 @@ pub enum AttributeType {
     Value,
     /// Length in bytes of the value
     ValueLen,
+    /// Vendor defined attribute, see [`AttributeType::new_vendor_defined`]
+    ///
+    /// The value is always encoded with the `CKA_VENDOR_DEFINED` bit set.
+    VendorDefined(CK_ATTRIBUTE_TYPE),
     /// Determines if a key supports verifying
     Verify,
     /// Determines if a key supports verifying where the data can be recovered from the signature
@@ -139,6 +157,18 @@ pub enum AttributeType {
 }
 
 impl AttributeType {
+    /// Create a vendor defined attribute type, `adding` being its offset
+    /// from `CKA_VENDOR_DEFINED`
+    ///
+    /// ```rust
+    /// use cryptoki::object::AttributeType;
+    ///
+    /// const CKA_VENDOR_TAG: AttributeType = AttributeType::new_vendor_defined(0x0101);
+    /// ```
+    pub const fn new_vendor_defined(adding: CK_ATTRIBUTE_TYPE) -> AttributeType {
+        AttributeType::VendorDefined(CKA_VENDOR_DEFINED | adding)
+    }
+
     pub(crate) fn stringify(val: CK_ATTRIBUTE_TYPE) -> String {
         match val {
             CKA_CLASS => String::from(stringify!(CKA_CLASS)),
@@ -254,6 +284,10 @@ impl AttributeType {
             CKA_UNWRAP_TEMPLATE => String::from(stringify!(CKA_UNWRAP_TEMPLATE)),
             CKA_DERIVE_TEMPLATE => String::from(stringify!(CKA_DERIVE_TEMPLATE)),
             CKA_ALLOWED_MECHANISMS => String::from(stringify!(CKA_ALLOWED_MECHANISMS)),
+            CKA_VENDOR_DEFINED => String::from(stringify!(CKA_VENDOR_DEFINED)),
+            vendor if vendor > CKA_VENDOR_DEFINED => {
+                format!("CKA_VENDOR_DEFINED | {:#x}", vendor - CKA_VENDOR_DEFINED)
+            }
             _ => format!("unknown ({val:08x})"),
         }
     }
@@ -291,6 +325,9 @@ impl From<AttributeType> for CK_ATTRIBUTE_TYPE {
             AttributeType::Exponent1 => CKA_EXPONENT_1,
             AttributeType::Exponent2 => CKA_EXPONENT_2,
             AttributeType::Extractable => CKA_EXTRACTABLE,
+            AttributeType::GostR3410Params => CKA_GOSTR3410_PARAMS,
+            AttributeType::GostR3411Params => CKA_GOSTR3411_PARAMS,
+            AttributeType::Gost28147Params => CKA_GOST28147_PARAMS,
             AttributeType::HashOfIssuerPublicKey => CKA_HASH_OF_ISSUER_PUBLIC_KEY,
             AttributeType::HashOfSubjectPublicKey => CKA_HASH_OF_SUBJECT_PUBLIC_KEY,
             AttributeType::Id => CKA_ID,
@@ -305,9 +342,11 @@ impl From<AttributeType> for CK_ATTRIBUTE_TYPE {
             AttributeType::NeverExtractable => CKA_NEVER_EXTRACTABLE,
             AttributeType::ObjectId => CKA_OBJECT_ID,
             AttributeType::Owner => CKA_OWNER,
+            AttributeType::ParameterSet => CKA_PARAMETER_SET,
             AttributeType::Prime => CKA_PRIME,
             AttributeType::Prime1 => CKA_PRIME_1,
             AttributeType::Prime2 => CKA_PRIME_2,
+            AttributeType::PrimeBits => CKA_PRIME_BITS,
             AttributeType::Private => CKA_PRIVATE,
             AttributeType::PrivateExponent => CKA_PRIVATE_EXPONENT,
             AttributeType::PublicExponent => CKA_PUBLIC_EXPONENT,
@@ -318,12 +357,15 @@ impl From<AttributeType> for CK_ATTRIBUTE_TYPE {
             AttributeType::SignRecover => CKA_SIGN_RECOVER,
             AttributeType::StartDate => CKA_START_DATE,
             AttributeType::Subject => CKA_SUBJECT,
+            AttributeType::Subprime => CKA_SUBPRIME,
+            AttributeType::SubprimeBits => CKA_SUBPRIME_BITS,
             AttributeType::Token => CKA_TOKEN,
             AttributeType::Trusted => CKA_TRUSTED,
             AttributeType::Unwrap => CKA_UNWRAP,
             AttributeType::Url => CKA_URL,
             AttributeType::Value => CKA_VALUE,
             AttributeType::ValueLen => CKA_VALUE_LEN,
+            AttributeType::VendorDefined(val) => CKA_VENDOR_DEFINED | val,
             AttributeType::Verify => CKA_VERIFY,
             AttributeType::VerifyRecover => CKA_VERIFY_RECOVER,
             AttributeType::Wrap => CKA_WRAP,
@@ -359,6 +401,9 @@ impl TryFrom<CK_ATTRIBUTE_TYPE> for AttributeType {
             CKA_EXPONENT_1 => Ok(AttributeType::Exponent1),
             CKA_EXPONENT_2 => Ok(AttributeType::Exponent2),
             CKA_EXTRACTABLE => Ok(AttributeType::Extractable),
+            CKA_GOSTR3410_PARAMS => Ok(AttributeType::GostR3410Params),
+            CKA_GOSTR3411_PARAMS => Ok(AttributeType::GostR3411Params),
+            CKA_GOST28147_PARAMS => Ok(AttributeType::Gost28147Params),
             CKA_HASH_OF_ISSUER_PUBLIC_KEY => Ok(AttributeType::HashOfIssuerPublicKey),
             CKA_HASH_OF_SUBJECT_PUBLIC_KEY => Ok(AttributeType::HashOfSubjectPublicKey),
             CKA_ID => Ok(AttributeType::Id),
@@ -373,9 +418,11 @@ impl TryFrom<CK_ATTRIBUTE_TYPE> for AttributeType {
             CKA_NEVER_EXTRACTABLE => Ok(AttributeType::NeverExtractable),
             CKA_OBJECT_ID => Ok(AttributeType::ObjectId),
             CKA_OWNER => Ok(AttributeType::Owner),
+            CKA_PARAMETER_SET => Ok(AttributeType::ParameterSet),
             CKA_PRIME => Ok(AttributeType::Prime),
             CKA_PRIME_1 => Ok(AttributeType::Prime1),
             CKA_PRIME_2 => Ok(AttributeType::Prime2),
+            CKA_PRIME_BITS => Ok(AttributeType::PrimeBits),
             CKA_PRIVATE => Ok(AttributeType::Private),
             CKA_PRIVATE_EXPONENT => Ok(AttributeType::PrivateExponent),
             CKA_PUBLIC_EXPONENT => Ok(AttributeType::PublicExponent),
@@ -386,6 +433,8 @@ impl TryFrom<CK_ATTRIBUTE_TYPE> for AttributeType {
             CKA_SIGN_RECOVER => Ok(AttributeType::SignRecover),
             CKA_START_DATE => Ok(AttributeType::StartDate),
             CKA_SUBJECT => Ok(AttributeType::Subject),
+            CKA_SUBPRIME => Ok(AttributeType::Subprime),
+            CKA_SUBPRIME_BITS => Ok(AttributeType::SubprimeBits),
             CKA_TOKEN => Ok(AttributeType::Token),
             CKA_TRUSTED => Ok(AttributeType::Trusted),
             CKA_UNWRAP => Ok(AttributeType::Unwrap),
@@ -396,6 +445,7 @@ impl TryFrom<CK_ATTRIBUTE_TYPE> for AttributeType {
             CKA_VERIFY_RECOVER => Ok(AttributeType::VerifyRecover),
             CKA_WRAP => Ok(AttributeType::Wrap),
             CKA_WRAP_WITH_TRUSTED => Ok(AttributeType::WrapWithTrusted),
+            vendor if vendor >= CKA_VENDOR_DEFINED => Ok(AttributeType::VendorDefined(vendor)),
             attr_type => {
                 error!("Attribute type {} not supported.", attr_type);
                 Err(Error::NotSupported)
@@ -407,6 +457,31 @@ impl TryFrom<CK_ATTRIBUTE_TYPE> for AttributeType {
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 /// Attribute value
+///
+/// Object templates are slices of attributes, which are converted to the
+/// `CK_ATTRIBUTE` structures of the C interface when calling the library.
+/// Attributes read from an object are converted back, see
+/// [`Session::get_attributes`](crate::session::Session::get_attributes).
+///
+/// ```no_run
+/// use cryptoki::mechanism::Mechanism;
+/// use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass};
+/// # use cryptoki::session::Session;
+///
+/// # fn create(session: &Session) -> testresult::TestResult {
+/// let template = [
+///     Attribute::Class(ObjectClass::SECRET_KEY),
+///     Attribute::KeyType(KeyType::AES),
+///     Attribute::Label(b"data key".to_vec()),
+///     Attribute::ValueLen(32.into()),
+///     Attribute::Sensitive(true),
+/// ];
+/// let key = session.generate_key(&Mechanism::AesKeyGen, &template)?;
+/// let attributes = session.get_attributes(key, &[AttributeType::Label])?;
+/// assert_eq!(attributes, [Attribute::Label(b"data key".to_vec())]);
+/// # Ok(())
+/// # }
+/// ```
 pub enum Attribute {
     /// DER-encoding of the attribute certificate's issuer
     AcIssuer(Vec<u8>),
@@ -452,6 +527,12 @@ pub enum Attribute {
     Exponent2(Vec<u8>),
     /// Determines if a key is extractable and can be wrapped
     Extractable(bool),
+    /// DER-encoding of the OID of the GOST R 34.10 parameter set
+    GostR3410Params(Vec<u8>),
+    /// DER-encoding of the OID of the GOST R 34.11 parameter set
+    GostR3411Params(Vec<u8>),
+    /// DER-encoding of the OID of the GOST 28147-89 parameter set
+    Gost28147Params(Vec<u8>),
     /// Hash of issuer public key
     HashOfIssuerPublicKey(Vec<u8>),
     /// Hash of subject public key
@@ -480,12 +561,16 @@ pub enum Attribute {
     ObjectId(Vec<u8>),
     /// DER encoding of the attribute certificate's subject field
     Owner(Vec<u8>),
+    /// Parameter set of an ML-KEM or ML-DSA key
+    ParameterSet(Ulong),
     /// Prime number value of a key
     Prime(Vec<u8>),
     /// The prime `p` of an RSA private key
     Prime1(Vec<u8>),
     /// The prime `q` of an RSA private key
     Prime2(Vec<u8>),
+    /// Length in bits of the prime `p` of DSA domain parameters
+    PrimeBits(Ulong),
     /// Determines if the object is private
     Private(bool),
     /// The private exponent `d`
@@ -506,6 +591,10 @@ pub enum Attribute {
     StartDate(Date),
     /// DER-encoding of certificate subject name
     Subject(Vec<u8>),
+    /// Subprime `q` of DSA domain parameters or of a DSA key
+    Subprime(Vec<u8>),
+    /// Length in bits of the subprime `q` of DSA domain parameters
+    SubprimeBits(Ulong),
     /// Determines if the object is a token object
     Token(bool),
     /// Determines if an object is trusted
@@ -518,6 +607,13 @@ pub enum Attribute {
     Value(Vec<u8>),
     /// Length in bytes of the value
     ValueLen(Ulong),
+    /// Raw value of a vendor defined attribute, whose encoding is vendor
+    /// specific
+    ///
+    /// The first field is the raw attribute type, which is always encoded
+    /// with the `CKA_VENDOR_DEFINED` bit set, see
+    /// [`AttributeType::new_vendor_defined`].
+    VendorDefined(CK_ATTRIBUTE_TYPE, Vec<u8>),
     /// Determines if a key supports verifying
     Verify(bool),
     /// Determines if a key supports verifying where the data can be recovered from the signature
@@ -554,6 +650,9 @@ impl Attribute {
             Attribute::Exponent1(_) => AttributeType::Exponent1,
             Attribute::Exponent2(_) => AttributeType::Exponent2,
             Attribute::Extractable(_) => AttributeType::Extractable,
+            Attribute::GostR3410Params(_) => AttributeType::GostR3410Params,
+            Attribute::GostR3411Params(_) => AttributeType::GostR3411Params,
+            Attribute::Gost28147Params(_) => AttributeType::Gost28147Params,
             Attribute::HashOfIssuerPublicKey(_) => AttributeType::HashOfIssuerPublicKey,
             Attribute::HashOfSubjectPublicKey(_) => AttributeType::HashOfSubjectPublicKey,
             Attribute::Id(_) => AttributeType::Id,
@@ -568,9 +667,11 @@ impl Attribute {
             Attribute::NeverExtractable(_) => AttributeType::NeverExtractable,
             Attribute::ObjectId(_) => AttributeType::ObjectId,
             Attribute::Owner(_) => AttributeType::Owner,
+            Attribute::ParameterSet(_) => AttributeType::ParameterSet,
             Attribute::Prime(_) => AttributeType::Prime,
             Attribute::Prime1(_) => AttributeType::Prime1,
             Attribute::Prime2(_) => AttributeType::Prime2,
+            Attribute::PrimeBits(_) => AttributeType::PrimeBits,
             Attribute::Private(_) => AttributeType::Private,
             Attribute::PrivateExponent(_) => AttributeType::PrivateExponent,
             Attribute::PublicExponent(_) => AttributeType::PublicExponent,
@@ -581,12 +682,17 @@ impl Attribute {
             Attribute::SignRecover(_) => AttributeType::SignRecover,
             Attribute::StartDate(_) => AttributeType::StartDate,
             Attribute::Subject(_) => AttributeType::Subject,
+            Attribute::Subprime(_) => AttributeType::Subprime,
+            Attribute::SubprimeBits(_) => AttributeType::SubprimeBits,
             Attribute::Token(_) => AttributeType::Token,
             Attribute::Trusted(_) => AttributeType::Trusted,
             Attribute::Unwrap(_) => AttributeType::Unwrap,
             Attribute::Url(_) => AttributeType::Url,
             Attribute::Value(_) => AttributeType::Value,
             Attribute::ValueLen(_) => AttributeType::ValueLen,
+            Attribute::VendorDefined(attribute_type, _) => {
+                AttributeType::VendorDefined(*attribute_type)
+            }
             Attribute::Verify(_) => AttributeType::Verify,
             Attribute::VerifyRecover(_) => AttributeType::VerifyRecover,
             Attribute::Wrap(_) => AttributeType::Wrap,
@@ -619,12 +725,12 @@ impl Attribute {
             | Attribute::VerifyRecover(_)
             | Attribute::Wrap(_)
             | Attribute::WrapWithTrusted(_) => size_of::<bool>(),
-            Attribute::Base(_) => 1,
             Attribute::Application(bytes) | Attribute::Label(bytes) | Attribute::Url(bytes) => {
                 size_of::<CK_UTF8CHAR>() * bytes.len()
             }
             Attribute::AcIssuer(bytes) => bytes.len(),
             Attribute::AttrTypes(bytes) => bytes.len(),
+            Attribute::Base(bytes) => bytes.len(),
             Attribute::CertificateType(_) => size_of::<CK_CERTIFICATE_TYPE>(),
             Attribute::CheckValue(bytes) => bytes.len(),
             Attribute::Class(_) => size_of::<CK_OBJECT_CLASS>(),
@@ -633,6 +739,9 @@ impl Attribute {
             Attribute::EcPoint(bytes) => bytes.len(),
             Attribute::Exponent1(bytes) => bytes.len(),
             Attribute::Exponent2(bytes) => bytes.len(),
+            Attribute::GostR3410Params(bytes) => bytes.len(),
+            Attribute::GostR3411Params(bytes) => bytes.len(),
+            Attribute::Gost28147Params(bytes) => bytes.len(),
             Attribute::HashOfIssuerPublicKey(bytes) => bytes.len(),
             Attribute::HashOfSubjectPublicKey(bytes) => bytes.len(),
             Attribute::Id(bytes) => bytes.len(),
@@ -651,7 +760,12 @@ impl Attribute {
             Attribute::PublicKeyInfo(bytes) => bytes.len(),
             Attribute::SerialNumber(bytes) => bytes.len(),
             Attribute::Subject(bytes) => bytes.len(),
+            Attribute::Subprime(bytes) => bytes.len(),
             Attribute::Value(bytes) => bytes.len(),
+            Attribute::VendorDefined(_, bytes) => bytes.len(),
+            Attribute::ParameterSet(_) => size_of::<CK_ULONG>(),
+            Attribute::PrimeBits(_) => size_of::<CK_ULONG>(),
+            Attribute::SubprimeBits(_) => size_of::<CK_ULONG>(),
             Attribute::ValueLen(_) => size_of::<CK_ULONG>(),
             Attribute::EndDate(_) | Attribute::StartDate(_) => size_of::<CK_DATE>(),
 
@@ -699,9 +813,11 @@ impl Attribute {
             | Attribute::Wrap(b)
             | Attribute::WrapWithTrusted(b) => b as *const _ as *mut c_void,
             // CK_ULONG
-            Attribute::ModulusBits(val) | Attribute::ValueLen(val) => {
-                val as *const _ as *mut c_void
-            }
+            Attribute::ModulusBits(val)
+            | Attribute::ParameterSet(val)
+            | Attribute::PrimeBits(val)
+            | Attribute::SubprimeBits(val)
+            | Attribute::ValueLen(val) => val as *const _ as *mut c_void,
             // Vec<u8>
             Attribute::AcIssuer(bytes)
             | Attribute::Application(bytes)
@@ -713,6 +829,9 @@ impl Attribute {
             | Attribute::EcPoint(bytes)
             | Attribute::Exponent1(bytes)
             | Attribute::Exponent2(bytes)
+            | Attribute::GostR3410Params(bytes)
+            | Attribute::GostR3411Params(bytes)
+            | Attribute::Gost28147Params(bytes)
             | Attribute::HashOfIssuerPublicKey(bytes)
             | Attribute::HashOfSubjectPublicKey(bytes)
             | Attribute::Issuer(bytes)
@@ -728,9 +847,11 @@ impl Attribute {
             | Attribute::Owner(bytes)
             | Attribute::SerialNumber(bytes)
             | Attribute::Subject(bytes)
+            | Attribute::Subprime(bytes)
             | Attribute::Url(bytes)
             | Attribute::Value(bytes)
-            | Attribute::Id(bytes) => bytes.as_ptr() as *mut c_void,
+            | Attribute::Id(bytes)
+            | Attribute::VendorDefined(_, bytes) => bytes.as_ptr() as *mut c_void,
             // Unique types
             Attribute::CertificateType(certificate_type) => {
                 certificate_type as *const _ as *mut c_void
@@ -821,6 +942,15 @@ impl TryFrom<CK_ATTRIBUTE> for Attribute {
             AttributeType::ModulusBits => Ok(Attribute::ModulusBits(
                 CK_ULONG::from_ne_bytes(val.try_into()?).into(),
             )),
+            AttributeType::ParameterSet => Ok(Attribute::ParameterSet(
+                CK_ULONG::from_ne_bytes(val.try_into()?).into(),
+            )),
+            AttributeType::PrimeBits => Ok(Attribute::PrimeBits(
+                CK_ULONG::from_ne_bytes(val.try_into()?).into(),
+            )),
+            AttributeType::SubprimeBits => Ok(Attribute::SubprimeBits(
+                CK_ULONG::from_ne_bytes(val.try_into()?).into(),
+            )),
             AttributeType::ValueLen => Ok(Attribute::ValueLen(
                 CK_ULONG::from_ne_bytes(val.try_into()?).into(),
             )),
@@ -835,6 +965,9 @@ impl TryFrom<CK_ATTRIBUTE> for Attribute {
             AttributeType::EcPoint => Ok(Attribute::EcPoint(val.to_vec())),
             AttributeType::Exponent1 => Ok(Attribute::Exponent1(val.to_vec())),
             AttributeType::Exponent2 => Ok(Attribute::Exponent2(val.to_vec())),
+            AttributeType::GostR3410Params => Ok(Attribute::GostR3410Params(val.to_vec())),
+            AttributeType::GostR3411Params => Ok(Attribute::GostR3411Params(val.to_vec())),
+            AttributeType::Gost28147Params => Ok(Attribute::Gost28147Params(val.to_vec())),
             AttributeType::HashOfIssuerPublicKey => {
                 Ok(Attribute::HashOfIssuerPublicKey(val.to_vec()))
             }
@@ -854,8 +987,12 @@ impl TryFrom<CK_ATTRIBUTE> for Attribute {
             AttributeType::Owner => Ok(Attribute::Owner(val.to_vec())),
             AttributeType::SerialNumber => Ok(Attribute::SerialNumber(val.to_vec())),
             AttributeType::Subject => Ok(Attribute::Subject(val.to_vec())),
+            AttributeType::Subprime => Ok(Attribute::Subprime(val.to_vec())),
             AttributeType::Url => Ok(Attribute::Url(val.to_vec())),
             AttributeType::Value => Ok(Attribute::Value(val.to_vec())),
+            AttributeType::VendorDefined(attribute_type) => {
+                Ok(Attribute::VendorDefined(attribute_type, val.to_vec()))
+            }
             AttributeType::Id => Ok(Attribute::Id(val.to_vec())),
             // Unique types
             AttributeType::CertificateType => Ok(Attribute::CertificateType(
@@ -935,6 +1072,7 @@ impl TryFrom<CK_ATTRIBUTE> for Attribute {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
 /// Token specific identifier for an object
 pub struct ObjectHandle {
     handle: CK_OBJECT_HANDLE,
@@ -1006,6 +1144,16 @@ impl ObjectClass {
     pub const MECHANISM: ObjectClass = ObjectClass { val: CKO_MECHANISM };
     /// An OTP key object
     pub const OTP_KEY: ObjectClass = ObjectClass { val: CKO_OTP_KEY };
+    /// A profile object, describing a profile implemented by the token
+    pub const PROFILE: ObjectClass = ObjectClass { val: CKO_PROFILE };
+
+    /// Create a vendor defined object class, `adding` being its offset from
+    /// `CKO_VENDOR_DEFINED`
+    pub const fn new_vendor_defined(adding: CK_OBJECT_CLASS) -> ObjectClass {
+        ObjectClass {
+            val: CKO_VENDOR_DEFINED | adding,
+        }
+    }
 
     pub(crate) fn stringify(class: CK_OBJECT_CLASS) -> String {
         match class {
@@ -1018,6 +1166,11 @@ impl ObjectClass {
             CKO_DOMAIN_PARAMETERS => String::from(stringify!(CKO_DOMAIN_PARAMETERS)),
             CKO_MECHANISM => String::from(stringify!(CKO_MECHANISM)),
             CKO_OTP_KEY => String::from(stringify!(CKO_OTP_KEY)),
+            CKO_PROFILE => String::from(stringify!(CKO_PROFILE)),
+            CKO_VENDOR_DEFINED => String::from(stringify!(CKO_VENDOR_DEFINED)),
+            vendor if vendor > CKO_VENDOR_DEFINED => {
+                format!("CKO_VENDOR_DEFINED | {:#x}", vendor - CKO_VENDOR_DEFINED)
+            }
             _ => format!("unknown ({class:08x})"),
         }
     }
@@ -1057,6 +1210,8 @@ impl TryFrom<CK_OBJECT_CLASS> for ObjectClass {
             CKO_DOMAIN_PARAMETERS => Ok(ObjectClass::DOMAIN_PARAMETERS),
             CKO_MECHANISM => Ok(ObjectClass::MECHANISM),
             CKO_OTP_KEY => Ok(ObjectClass::OTP_KEY),
+            CKO_PROFILE => Ok(ObjectClass::PROFILE),
+            vendor if vendor >= CKO_VENDOR_DEFINED => Ok(ObjectClass { val: vendor }),
 
             _ => {
                 error!("Object class {} is not supported.", object_class);
@@ -1158,17 +1313,17 @@ impl KeyType {
 
     /// SHA384 HMAC key
     pub const SHA384_HMAC: KeyType = KeyType {
-        val: CKK_SHA256_HMAC,
+        val: CKK_SHA384_HMAC,
     };
 
     /// SHA512 HMAC key
     pub const SHA512_HMAC: KeyType = KeyType {
-        val: CKK_SHA256_HMAC,
+        val: CKK_SHA512_HMAC,
     };
 
     /// SHA224 HMAC key
     pub const SHA224_HMAC: KeyType = KeyType {
-        val: CKK_SHA256_HMAC,
+        val: CKK_SHA224_HMAC,
     };
 
     /// SEED key
@@ -1195,6 +1350,93 @@ impl KeyType {
     /// HKDF key
     pub const HKDF: KeyType = KeyType { val: CKK_HKDF };
 
+    /// ChaCha20 key
+    pub const CHACHA20: KeyType = KeyType { val: CKK_CHACHA20 };
+
+    /// Salsa20 key
+    pub const SALSA20: KeyType = KeyType { val: CKK_SALSA20 };
+
+    /// AES-XTS key, holding the two concatenated AES keys of XTS
+    pub const AES_XTS: KeyType = KeyType { val: CKK_AES_XTS };
+
+    /// SHA3-224 HMAC key
+    pub const SHA3_224_HMAC: KeyType = KeyType {
+        val: CKK_SHA3_224_HMAC,
+    };
+
+    /// SHA3-256 HMAC key
+    pub const SHA3_256_HMAC: KeyType = KeyType {
+        val: CKK_SHA3_256_HMAC,
+    };
+
+    /// SHA3-384 HMAC key
+    pub const SHA3_384_HMAC: KeyType = KeyType {
+        val: CKK_SHA3_384_HMAC,
+    };
+
+    /// SHA3-512 HMAC key
+    pub const SHA3_512_HMAC: KeyType = KeyType {
+        val: CKK_SHA3_512_HMAC,
+    };
+
+    /// SHA-512/224 HMAC key
+    pub const SHA512_224_HMAC: KeyType = KeyType {
+        val: CKK_SHA512_224_HMAC,
+    };
+
+    /// SHA-512/256 HMAC key
+    pub const SHA512_256_HMAC: KeyType = KeyType {
+        val: CKK_SHA512_256_HMAC,
+    };
+
+    /// SHA-512/t HMAC key
+    pub const SHA512_T_HMAC: KeyType = KeyType {
+        val: CKK_SHA512_T_HMAC,
+    };
+
+    /// Poly1305 key
+    pub const POLY1305: KeyType = KeyType { val: CKK_POLY1305 };
+
+    /// BLAKE2b-160 HMAC key
+    pub const BLAKE2B_160_HMAC: KeyType = KeyType {
+        val: CKK_BLAKE2B_160_HMAC,
+    };
+
+    /// BLAKE2b-256 HMAC key
+    pub const BLAKE2B_256_HMAC: KeyType = KeyType {
+        val: CKK_BLAKE2B_256_HMAC,
+    };
+
+    /// BLAKE2b-384 HMAC key
+    pub const BLAKE2B_384_HMAC: KeyType = KeyType {
+        val: CKK_BLAKE2B_384_HMAC,
+    };
+
+    /// BLAKE2b-512 HMAC key
+    pub const BLAKE2B_512_HMAC: KeyType = KeyType {
+        val: CKK_BLAKE2B_512_HMAC,
+    };
+
+    /// X2Ratchet key
+    pub const X2RATCHET: KeyType = KeyType { val: CKK_X2RATCHET };
+
+    /// ML-KEM key
+    pub const ML_KEM: KeyType = KeyType { val: CKK_ML_KEM };
+
+    /// ML-DSA key
+    pub const ML_DSA: KeyType = KeyType { val: CKK_ML_DSA };
+
+    /// Create a vendor defined key type, `adding` being its offset from
+    /// `CKK_VENDOR_DEFINED`
+    ///
+    /// Key types which are not part of the standard, such as SM2 and SM4
+    /// keys, are only supported through the values defined by each vendor.
+    pub const fn new_vendor_defined(adding: CK_KEY_TYPE) -> KeyType {
+        KeyType {
+            val: CKK_VENDOR_DEFINED | adding,
+        }
+    }
+
     fn stringify(key_type: CK_KEY_TYPE) -> String {
         match key_type {
             CKK_RSA => String::from(stringify!(CKK_RSA)),
@@ -1241,6 +1483,28 @@ impl KeyType {
             CKK_EC_EDWARDS => String::from(stringify!(CKK_EC_EDWARDS)),
             CKK_EC_MONTGOMERY => String::from(stringify!(CKK_EC_MONTGOMERY)),
             CKK_HKDF => String::from(stringify!(CKK_HKDF)),
+            CKK_CHACHA20 => String::from(stringify!(CKK_CHACHA20)),
+            CKK_SALSA20 => String::from(stringify!(CKK_SALSA20)),
+            CKK_AES_XTS => String::from(stringify!(CKK_AES_XTS)),
+            CKK_SHA3_224_HMAC => String::from(stringify!(CKK_SHA3_224_HMAC)),
+            CKK_SHA3_256_HMAC => String::from(stringify!(CKK_SHA3_256_HMAC)),
+            CKK_SHA3_384_HMAC => String::from(stringify!(CKK_SHA3_384_HMAC)),
+            CKK_SHA3_512_HMAC => String::from(stringify!(CKK_SHA3_512_HMAC)),
+            CKK_SHA512_224_HMAC => String::from(stringify!(CKK_SHA512_224_HMAC)),
+            CKK_SHA512_256_HMAC => String::from(stringify!(CKK_SHA512_256_HMAC)),
+            CKK_SHA512_T_HMAC => String::from(stringify!(CKK_SHA512_T_HMAC)),
+            CKK_POLY1305 => String::from(stringify!(CKK_POLY1305)),
+            CKK_BLAKE2B_160_HMAC => String::from(stringify!(CKK_BLAKE2B_160_HMAC)),
+            CKK_BLAKE2B_256_HMAC => String::from(stringify!(CKK_BLAKE2B_256_HMAC)),
+            CKK_BLAKE2B_384_HMAC => String::from(stringify!(CKK_BLAKE2B_384_HMAC)),
+            CKK_BLAKE2B_512_HMAC => String::from(stringify!(CKK_BLAKE2B_512_HMAC)),
+            CKK_X2RATCHET => String::from(stringify!(CKK_X2RATCHET)),
+            CKK_ML_KEM => String::from(stringify!(CKK_ML_KEM)),
+            CKK_ML_DSA => String::from(stringify!(CKK_ML_DSA)),
+            CKK_VENDOR_DEFINED => String::from(stringify!(CKK_VENDOR_DEFINED)),
+            vendor if vendor > CKK_VENDOR_DEFINED => {
+                format!("CKK_VENDOR_DEFINED | {:#x}", vendor - CKK_VENDOR_DEFINED)
+            }
             _ => format!("unknown ({key_type:08x})"),
         }
     }
@@ -1315,6 +1579,25 @@ impl TryFrom<CK_KEY_TYPE> for KeyType {
             CKK_EC_EDWARDS => Ok(KeyType::EC_EDWARDS),
             CKK_EC_MONTGOMERY => Ok(KeyType::EC_MONTGOMERY),
             CKK_HKDF => Ok(KeyType::HKDF),
+            CKK_CHACHA20 => Ok(KeyType::CHACHA20),
+            CKK_SALSA20 => Ok(KeyType::SALSA20),
+            CKK_AES_XTS => Ok(KeyType::AES_XTS),
+            CKK_SHA3_224_HMAC => Ok(KeyType::SHA3_224_HMAC),
+            CKK_SHA3_256_HMAC => Ok(KeyType::SHA3_256_HMAC),
+            CKK_SHA3_384_HMAC => Ok(KeyType::SHA3_384_HMAC),
+            CKK_SHA3_512_HMAC => Ok(KeyType::SHA3_512_HMAC),
+            CKK_SHA512_224_HMAC => Ok(KeyType::SHA512_224_HMAC),
+            CKK_SHA512_256_HMAC => Ok(KeyType::SHA512_256_HMAC),
+            CKK_SHA512_T_HMAC => Ok(KeyType::SHA512_T_HMAC),
+            CKK_POLY1305 => Ok(KeyType::POLY1305),
+            CKK_BLAKE2B_160_HMAC => Ok(KeyType::BLAKE2B_160_HMAC),
+            CKK_BLAKE2B_256_HMAC => Ok(KeyType::BLAKE2B_256_HMAC),
+            CKK_BLAKE2B_384_HMAC => Ok(KeyType::BLAKE2B_384_HMAC),
+            CKK_BLAKE2B_512_HMAC => Ok(KeyType::BLAKE2B_512_HMAC),
+            CKK_X2RATCHET => Ok(KeyType::X2RATCHET),
+            CKK_ML_KEM => Ok(KeyType::ML_KEM),
+            CKK_ML_DSA => Ok(KeyType::ML_DSA),
+            vendor if vendor >= CKK_VENDOR_DEFINED => Ok(KeyType { val: vendor }),
             _ => {
                 error!("Key type {} is not supported.", key_type);
                 Err(Error::NotSupported)
@@ -1323,7 +1606,7 @@ impl TryFrom<CK_KEY_TYPE> for KeyType {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 /// Information about the attribute of an object
 pub enum AttributeInfo {
     /// The requested attribute is not a valid attribute for the object
@@ -1334,6 +1617,18 @@ pub enum AttributeInfo {
     Available(usize),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Value of the attribute of an object, see
+/// [`Session::get_attribute_values`](crate::session::Session::get_attribute_values)
+pub enum AttributeValue {
+    /// The requested attribute is not a valid attribute for the object
+    TypeInvalid,
+    /// The value of the attribute is sensitive and was not returned
+    Sensitive,
+    /// The value of the attribute
+    Available(Attribute),
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
 /// The certificate type
@@ -1396,3 +1691,68 @@ impl TryFrom<CK_CERTIFICATE_TYPE> for CertificateType {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hmac_key_types() {
+        assert_eq!(*KeyType::SHA224_HMAC, CKK_SHA224_HMAC);
+        assert_eq!(*KeyType::SHA384_HMAC, CKK_SHA384_HMAC);
+        assert_eq!(*KeyType::SHA512_HMAC, CKK_SHA512_HMAC);
+        for key_type in [
+            KeyType::SHA224_HMAC,
+            KeyType::SHA384_HMAC,
+            KeyType::SHA512_HMAC,
+            KeyType::SHA512_256_HMAC,
+            KeyType::BLAKE2B_256_HMAC,
+        ] {
+            assert_eq!(KeyType::try_from(*key_type).unwrap(), key_type);
+        }
+        assert_eq!(KeyType::SHA384_HMAC.to_string(), "CKK_SHA384_HMAC");
+    }
+
+    #[test]
+    fn object_class_round_trip() {
+        assert_eq!(
+            ObjectClass::try_from(CKO_PROFILE).unwrap(),
+            ObjectClass::PROFILE
+        );
+        assert_eq!(ObjectClass::PROFILE.to_string(), "CKO_PROFILE");
+
+        let vendor = ObjectClass::new_vendor_defined(0x2a);
+        assert_eq!(*vendor, CKO_VENDOR_DEFINED | 0x2a);
+        assert_eq!(ObjectClass::try_from(*vendor).unwrap(), vendor);
+        assert_eq!(vendor.to_string(), "CKO_VENDOR_DEFINED | 0x2a");
+
+        assert!(matches!(
+            ObjectClass::try_from(0x1234),
+            Err(Error::NotSupported)
+        ));
+    }
+
+    #[test]
+    fn vendor_defined_attribute() {
+        let attribute_type = AttributeType::new_vendor_defined(0x101);
+        assert_eq!(
+            AttributeType::try_from(CKA_VENDOR_DEFINED | 0x101).unwrap(),
+            attribute_type
+        );
+        assert_eq!(attribute_type.to_string(), "CKA_VENDOR_DEFINED | 0x101");
+
+        let attribute = Attribute::VendorDefined(CKA_VENDOR_DEFINED | 0x101, vec![1, 2, 3]);
+        assert_eq!(attribute.attribute_type(), attribute_type);
+        let raw = CK_ATTRIBUTE::from(&attribute);
+        assert_eq!(raw.type_, CKA_VENDOR_DEFINED | 0x101);
+        assert_eq!(raw.ulValueLen, 3);
+        assert_eq!(Attribute::try_from(raw).unwrap(), attribute);
+
+        // A standard attribute type is not encoded as such
+        let attribute = Attribute::VendorDefined(CKA_TOKEN, vec![1]);
+        assert_eq!(
+            CK_ATTRIBUTE::from(&attribute).type_,
+            CKA_VENDOR_DEFINED | CKA_TOKEN
+        );
+    }
+}
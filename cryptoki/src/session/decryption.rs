@@ -6,12 +6,17 @@ use crate::context::Function;
 use crate::error::{Result, Rv};
 use crate::mechanism::Mechanism;
 use crate::object::ObjectHandle;
-use crate::session::Session;
+use crate::session::{SecretBuffer, Session};
 use cryptoki_sys::*;
 use std::convert::TryInto;
 
 impl Session {
     /// Single-part decryption operation
+    ///
+    /// With the `zeroize` feature, the output buffer is scrubbed if the call
+    /// fails, as well as the part of it not used by the plaintext. The
+    /// returned vector itself is not scrubbed when dropped: wrap it in
+    /// `zeroize::Zeroizing` to do so.
     pub fn decrypt(
         &self,
         mechanism: &Mechanism,
@@ -43,7 +48,7 @@ impl Session {
             .into_result(Function::Decrypt)?;
         }
 
-        let mut data = vec![0; data_len.try_into()?];
+        let mut data = SecretBuffer::new(data_len.try_into()?);
 
         unsafe {
             Rv::from(get_pkcs11!(self.client(), C_Decrypt)(
@@ -56,8 +61,97 @@ impl Session {
             .into_result(Function::Decrypt)?;
         }
 
-        data.resize(data_len.try_into()?, 0);
+        Ok(data.into_vec(data_len.try_into()?))
+    }
+
+    /// Starts new multi-part decryption operation
+    ///
+    /// The operation is continued with [`Session::decrypt_update`] and
+    /// concluded with [`Session::decrypt_final`], in the same way as
+    /// encryption (see the example of [`Session::encrypt_init`]).
+    pub fn decrypt_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::DecryptInit)?;
+        }
+
+        Ok(())
+    }
+
+    /// Continues an ongoing multi-part decryption operation,
+    /// taking in the next part of the encrypted data and returning its decryption
+    ///
+    /// Padded and authenticated mechanisms may hold back part of the data
+    /// until [`Session::decrypt_final`] is called.
+    pub fn decrypt_update(&self, encrypted_data: &[u8]) -> Result<Vec<u8>> {
+        let mut data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptUpdate)(
+                self.handle(),
+                // C_DecryptUpdate should not modify this buffer
+                encrypted_data.as_ptr() as *mut u8,
+                encrypted_data.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptUpdate)?;
+        }
+
+        let mut data = SecretBuffer::new(data_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptUpdate)(
+                self.handle(),
+                encrypted_data.as_ptr() as *mut u8,
+                encrypted_data.len().try_into()?,
+                data.as_mut_ptr(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptUpdate)?;
+        }
+
+        Ok(data.into_vec(data_len.try_into()?))
+    }
+
+    /// Finalizes ongoing multi-part decryption operation,
+    /// returning any remaining bytes in the decrypted data
+    ///
+    /// Invalid padding or a failed authentication check is reported here, as
+    /// [`RvError::EncryptedDataInvalid`](crate::error::RvError::EncryptedDataInvalid)
+    /// or [`RvError::EncryptedDataLenRange`](crate::error::RvError::EncryptedDataLenRange)
+    /// from [`Function::DecryptFinal`].
+    pub fn decrypt_final(&self) -> Result<Vec<u8>> {
+        let mut data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptFinal)(
+                self.handle(),
+                std::ptr::null_mut(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptFinal)?;
+        }
+
+        let mut data = SecretBuffer::new(data_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptFinal)(
+                self.handle(),
+                data.as_mut_ptr(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptFinal)?;
+        }
 
-        Ok(data)
+        Ok(data.into_vec(data_len.try_into()?))
     }
 }
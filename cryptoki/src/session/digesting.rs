@@ -3,12 +3,86 @@
 //! Digesting functions
 
 use crate::context::Function;
-use crate::error::{Result, Rv};
+use crate::error::{Error, Result, Rv};
 use crate::mechanism::Mechanism;
+use crate::object::ObjectHandle;
 use crate::session::Session;
 use cryptoki_sys::*;
+use log::error;
 use std::convert::TryInto;
 
+/// A multi-part digesting operation, see [`Session::digest_streaming`]
+///
+/// The operation borrows its session mutably, so no other operation can be
+/// started on the session until the operation is finalized or dropped.
+/// Dropping an operation which was not finalized terminates it.
+#[derive(Debug)]
+pub struct Digester<'a> {
+    session: &'a mut Session,
+    active: bool,
+}
+
+impl Digester<'_> {
+    /// Digest the next part of the data
+    ///
+    /// See [`Session::digest_update`]. If this call fails, the operation is
+    /// terminated.
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        let res = self.session.digest_update(data);
+        self.active = res.is_ok();
+        res
+    }
+
+    /// Digest the value of a secret key as the next part of the data
+    ///
+    /// See [`Session::digest_key`]. If this call fails, the operation is
+    /// terminated.
+    pub fn digest_key(&mut self, key: ObjectHandle) -> Result<()> {
+        let res = self.session.digest_key(key);
+        self.active = res.is_ok();
+        res
+    }
+
+    /// Finalize the operation, returning the digest
+    pub fn finalize(mut self) -> Result<Vec<u8>> {
+        self.active = false;
+        self.session.digest_final()
+    }
+}
+
+impl Drop for Digester<'_> {
+    fn drop(&mut self) {
+        if self.active {
+            // Never panic here: this may run while unwinding
+            if let Err(e) = self.session.digest_final() {
+                error!("Failed to terminate digesting operation: {}", e);
+            }
+        }
+    }
+}
+
+// Only hash mechanisms can be used for digesting. Vendor defined mechanisms
+// are left for the token to check.
+fn check_digest_mechanism(mechanism: &Mechanism) -> Result<()> {
+    match mechanism {
+        Mechanism::Sha1
+        | Mechanism::Sha224
+        | Mechanism::Sha256
+        | Mechanism::Sha384
+        | Mechanism::Sha512
+        | Mechanism::Sha3_224
+        | Mechanism::Sha3_256
+        | Mechanism::Sha3_384
+        | Mechanism::Sha3_512
+        | Mechanism::GostR3411
+        | Mechanism::VendorDefined(_) => Ok(()),
+        _ => {
+            error!("{} is not a digest mechanism.", mechanism.mechanism_type());
+            Err(Error::InvalidValue)
+        }
+    }
+}
+
 impl Session {
     /// Single-part digesting operation
     pub fn digest(&self, m: &Mechanism, data: &[u8]) -> Result<Vec<u8>> {
@@ -52,4 +126,135 @@ impl Session {
 
         Ok(digest)
     }
+
+    /// Starts new multi-part digesting operation
+    ///
+    /// The operation is continued with [`Session::digest_update`] and
+    /// [`Session::digest_key`], and concluded with [`Session::digest_final`].
+    pub fn digest_init(&self, m: &Mechanism) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = m.into();
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+            ))
+            .into_result(Function::DigestInit)?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts new multi-part digesting operation, returned as a
+    /// [`Digester`]
+    ///
+    /// Unlike with [`Session::digest_init`], the steps of the operation can
+    /// only be called in order, and the session cannot be used for anything
+    /// else until the operation is over.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `mechanism` is not a
+    /// hash mechanism, such as [`Mechanism::Sha256`].
+    pub fn digest_streaming(&mut self, mechanism: &Mechanism) -> Result<Digester<'_>> {
+        check_digest_mechanism(mechanism)?;
+        self.digest_init(mechanism)?;
+        Ok(Digester {
+            session: self,
+            active: true,
+        })
+    }
+
+    /// Continues an ongoing multi-part digesting operation,
+    /// taking in the next part of the data to digest
+    pub fn digest_update(&self, data: &[u8]) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestUpdate)(
+                self.handle(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+            ))
+            .into_result(Function::DigestUpdate)
+        }
+    }
+
+    /// Continues an ongoing multi-part digesting operation,
+    /// using the value of a secret key as the next part of the data to digest
+    ///
+    /// Keys whose value cannot be digested, for example because the token
+    /// does not allow it for sensitive keys, are rejected with
+    /// [`RvError::KeyIndigestible`](crate::error::RvError::KeyIndigestible).
+    pub fn digest_key(&self, key: ObjectHandle) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestKey)(
+                self.handle(),
+                key.handle(),
+            ))
+            .into_result(Function::DigestKey)
+        }
+    }
+
+    /// Finalizes ongoing multi-part digesting operation,
+    /// returning the digest
+    pub fn digest_final(&self) -> Result<Vec<u8>> {
+        let mut digest_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestFinal)(
+                self.handle(),
+                std::ptr::null_mut(),
+                &mut digest_len,
+            ))
+            .into_result(Function::DigestFinal)?;
+        }
+
+        let mut digest = vec![0; digest_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestFinal)(
+                self.handle(),
+                digest.as_mut_ptr(),
+                &mut digest_len,
+            ))
+            .into_result(Function::DigestFinal)?;
+        }
+
+        digest.resize(digest_len.try_into()?, 0);
+
+        Ok(digest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mechanism::vendor_defined::VendorDefinedMechanism;
+    use crate::mechanism::MechanismType;
+
+    #[test]
+    fn digest_mechanisms() {
+        for mechanism in [
+            Mechanism::Sha1,
+            Mechanism::Sha256,
+            Mechanism::Sha3_512,
+            Mechanism::GostR3411,
+            Mechanism::VendorDefined(VendorDefinedMechanism::new(
+                MechanismType::new_vendor_defined(0x1),
+                None,
+            )),
+        ] {
+            assert!(check_digest_mechanism(&mechanism).is_ok());
+        }
+        for mechanism in [
+            Mechanism::Sha256RsaPkcs,
+            Mechanism::Sha256Hmac,
+            Mechanism::AesKeyGen,
+        ] {
+            assert!(matches!(
+                check_digest_mechanism(&mechanism),
+                Err(Error::InvalidValue)
+            ));
+        }
+    }
 }
@@ -0,0 +1,162 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Dual-function cryptographic functions
+//!
+//! These functions process a part of the data with two multi-part operations
+//! active in the same session, saving a call to the token.
+
+use crate::context::Function;
+use crate::error::{Result, Rv};
+use crate::session::{SecretBuffer, Session};
+use std::convert::TryInto;
+
+impl Session {
+    /// Digest and encrypt a part of the data, in a single call
+    ///
+    /// Both operations must be active in the session, started with
+    /// [`Session::digest_init`] and [`Session::encrypt_init`]. The encrypted
+    /// part is returned, and the digest and encryption are concluded with
+    /// [`Session::digest_final`] and [`Session::encrypt_final`].
+    pub fn digest_encrypt_update(&self, part: &[u8]) -> Result<Vec<u8>> {
+        let mut encrypted_data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestEncryptUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::DigestEncryptUpdate)?;
+        }
+
+        let mut encrypted_data = vec![0; encrypted_data_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DigestEncryptUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                encrypted_data.as_mut_ptr(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::DigestEncryptUpdate)?;
+        }
+
+        encrypted_data.resize(encrypted_data_len.try_into()?, 0);
+
+        Ok(encrypted_data)
+    }
+
+    /// Decrypt and digest a part of the data, in a single call
+    ///
+    /// Both operations must be active in the session, started with
+    /// [`Session::decrypt_init`] and [`Session::digest_init`]. The decrypted
+    /// part is returned, and digested.
+    pub fn decrypt_digest_update(&self, part: &[u8]) -> Result<Vec<u8>> {
+        let mut data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptDigestUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptDigestUpdate)?;
+        }
+
+        let mut data = SecretBuffer::new(data_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptDigestUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                data.as_mut_ptr(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptDigestUpdate)?;
+        }
+
+        Ok(data.into_vec(data_len.try_into()?))
+    }
+
+    /// Sign and encrypt a part of the data, in a single call
+    ///
+    /// Both operations must be active in the session, started with
+    /// [`Session::sign_init`] and [`Session::encrypt_init`]. The encrypted
+    /// part is returned.
+    pub fn sign_encrypt_update(&self, part: &[u8]) -> Result<Vec<u8>> {
+        let mut encrypted_data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SignEncryptUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::SignEncryptUpdate)?;
+        }
+
+        let mut encrypted_data = vec![0; encrypted_data_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SignEncryptUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                encrypted_data.as_mut_ptr(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::SignEncryptUpdate)?;
+        }
+
+        encrypted_data.resize(encrypted_data_len.try_into()?, 0);
+
+        Ok(encrypted_data)
+    }
+
+    /// Decrypt and verify a part of the data, in a single call
+    ///
+    /// Both operations must be active in the session, started with
+    /// [`Session::decrypt_init`] and [`Session::verify_init`]. The decrypted
+    /// part is returned, and verified.
+    pub fn decrypt_verify_update(&self, part: &[u8]) -> Result<Vec<u8>> {
+        let mut data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptVerifyUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptVerifyUpdate)?;
+        }
+
+        let mut data = SecretBuffer::new(data_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DecryptVerifyUpdate)(
+                self.handle(),
+                part.as_ptr() as *mut u8,
+                part.len().try_into()?,
+                data.as_mut_ptr(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptVerifyUpdate)?;
+        }
+
+        Ok(data.into_vec(data_len.try_into()?))
+    }
+}
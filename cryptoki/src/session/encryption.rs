@@ -8,10 +8,58 @@ use crate::mechanism::Mechanism;
 use crate::object::ObjectHandle;
 use crate::session::Session;
 use cryptoki_sys::*;
+use log::error;
 use std::convert::TryInto;
 
+/// A multi-part encryption operation, see [`Session::encrypt_streaming`]
+///
+/// The operation borrows its session mutably, so no other operation can be
+/// started on the session until the operation is finalized or dropped.
+/// Dropping an operation which was not finalized terminates it, discarding
+/// the rest of the encrypted data.
+#[derive(Debug)]
+pub struct Encryptor<'a> {
+    session: &'a mut Session,
+    active: bool,
+}
+
+impl Encryptor<'_> {
+    /// Encrypt the next part of the data, returning its encryption
+    ///
+    /// See [`Session::encrypt_update`]. If this call fails, the operation
+    /// is terminated.
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>> {
+        let encrypted_data = self.session.encrypt_update(data);
+        self.active = encrypted_data.is_ok();
+        encrypted_data
+    }
+
+    /// Finalize the operation, returning any remaining bytes in the
+    /// encrypted data
+    pub fn finalize(mut self) -> Result<Vec<u8>> {
+        self.active = false;
+        self.session.encrypt_final()
+    }
+}
+
+impl Drop for Encryptor<'_> {
+    fn drop(&mut self) {
+        if self.active {
+            // Never panic here: this may run while unwinding
+            if let Err(e) = self.session.encrypt_final() {
+                error!("Failed to terminate encryption operation: {}", e);
+            }
+        }
+    }
+}
+
 impl Session {
     /// Single-part encryption operation
+    ///
+    /// This fails with
+    /// [`RvError::OperationActive`](crate::error::RvError::OperationActive)
+    /// from [`Function::EncryptInit`] if a multi-part encryption operation,
+    /// started with [`Session::encrypt_init`], has not been finalized yet.
     pub fn encrypt(
         &self,
         mechanism: &Mechanism,
@@ -59,4 +107,164 @@ impl Session {
 
         Ok(encrypted_data)
     }
+
+    /// Starts new multi-part encryption operation
+    ///
+    /// The operation is continued with [`Session::encrypt_update`] and
+    /// concluded with [`Session::encrypt_final`]. Only one encryption
+    /// operation can be active on a session at a time: while it is active,
+    /// calling [`Session::encrypt`] or [`Session::encrypt_init`] fails with
+    /// [`RvError::OperationActive`](crate::error::RvError::OperationActive).
+    ///
+    /// # Example
+    ///
+    /// Encrypting a file too large to be held in memory, chunk by chunk:
+    ///
+    /// ```no_run
+    /// # fn main() -> testresult::TestResult {
+    /// # use cryptoki::context::{CInitializeArgs, Pkcs11};
+    /// # use cryptoki::mechanism::Mechanism;
+    /// # use cryptoki::object::{Attribute, ObjectClass};
+    /// # use std::io::{Read, Write};
+    /// # let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+    /// # pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    /// # let slot = pkcs11.get_slots_with_token()?.remove(0);
+    /// # let session = pkcs11.open_ro_session(slot)?;
+    /// # let key = session.find_objects(&[Attribute::Class(ObjectClass::SECRET_KEY)])?[0];
+    /// let mut input = std::fs::File::open("backup.tar")?;
+    /// let mut output = std::fs::File::create("backup.tar.enc")?;
+    ///
+    /// session.encrypt_init(&Mechanism::AesCbcPad([0; 16]), key)?;
+    /// let mut buffer = vec![0; 64 * 1024];
+    /// loop {
+    ///     let read = input.read(&mut buffer)?;
+    ///     if read == 0 {
+    ///         break;
+    ///     }
+    ///     output.write_all(&session.encrypt_update(&buffer[..read])?)?;
+    /// }
+    /// output.write_all(&session.encrypt_final()?)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn encrypt_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_EncryptInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::EncryptInit)?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts new multi-part encryption operation, returned as an
+    /// [`Encryptor`]
+    ///
+    /// Unlike with [`Session::encrypt_init`], the steps of the operation can
+    /// only be called in order: [`Encryptor::update`] for each part of the
+    /// data, then [`Encryptor::finalize`] once.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> testresult::TestResult {
+    /// # use cryptoki::context::{CInitializeArgs, Pkcs11};
+    /// # use cryptoki::mechanism::Mechanism;
+    /// # use cryptoki::object::{Attribute, ObjectClass};
+    /// # let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+    /// # pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    /// # let slot = pkcs11.get_slots_with_token()?.remove(0);
+    /// # let mut session = pkcs11.open_ro_session(slot)?;
+    /// # let key = session.find_objects(&[Attribute::Class(ObjectClass::SECRET_KEY)])?[0];
+    /// let mut encryptor = session.encrypt_streaming(&Mechanism::AesCbcPad([0; 16]), key)?;
+    /// let mut encrypted = encryptor.update(b"first part, ")?;
+    /// encrypted.extend(encryptor.update(b"second part")?);
+    /// encrypted.extend(encryptor.finalize()?);
+    /// # Ok(()) }
+    /// ```
+    pub fn encrypt_streaming(
+        &mut self,
+        mechanism: &Mechanism,
+        key: ObjectHandle,
+    ) -> Result<Encryptor<'_>> {
+        self.encrypt_init(mechanism, key)?;
+        Ok(Encryptor {
+            session: self,
+            active: true,
+        })
+    }
+
+    /// Continues an ongoing multi-part encryption operation,
+    /// taking in the next part of the data and returning its encryption
+    ///
+    /// Mechanisms that buffer data internally (such as block ciphers with
+    /// padding) may return fewer bytes than given, including none at all.
+    /// If this call fails, the encryption operation is terminated.
+    pub fn encrypt_update(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut encrypted_data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_EncryptUpdate)(
+                self.handle(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::EncryptUpdate)?;
+        }
+
+        let mut encrypted_data = vec![0; encrypted_data_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_EncryptUpdate)(
+                self.handle(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                encrypted_data.as_mut_ptr(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::EncryptUpdate)?;
+        }
+
+        encrypted_data.resize(encrypted_data_len.try_into()?, 0);
+
+        Ok(encrypted_data)
+    }
+
+    /// Finalizes ongoing multi-part encryption operation,
+    /// returning any remaining bytes in the encrypted data
+    pub fn encrypt_final(&self) -> Result<Vec<u8>> {
+        let mut encrypted_data_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_EncryptFinal)(
+                self.handle(),
+                std::ptr::null_mut(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::EncryptFinal)?;
+        }
+
+        let mut encrypted_data = vec![0; encrypted_data_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_EncryptFinal)(
+                self.handle(),
+                encrypted_data.as_mut_ptr(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::EncryptFinal)?;
+        }
+
+        encrypted_data.resize(encrypted_data_len.try_into()?, 0);
+
+        Ok(encrypted_data)
+    }
 }
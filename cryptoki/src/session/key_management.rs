@@ -3,11 +3,16 @@
 //! Key management functions
 
 use crate::context::Function;
-use crate::error::{Result, Rv};
+use crate::error::{Error, Result, Rv};
+use crate::mechanism::dsa::DsaParams;
+use crate::mechanism::tls::Tls12KeyMaterialOutput;
 use crate::mechanism::Mechanism;
-use crate::object::{Attribute, ObjectHandle};
+use crate::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
 use crate::session::Session;
-use cryptoki_sys::{CK_ATTRIBUTE, CK_MECHANISM, CK_MECHANISM_PTR};
+use cryptoki_sys::{
+    CK_ATTRIBUTE, CK_MECHANISM, CK_MECHANISM_PTR, CK_TLS12_KEY_MAT_PARAMS, CK_VOID_PTR,
+};
+use log::error;
 use std::convert::TryInto;
 
 impl Session {
@@ -93,7 +98,123 @@ impl Session {
         Ok(ObjectHandle::new(handle))
     }
 
+    /// Generate new DSA domain parameters, with a prime of `prime_bits` bits
+    ///
+    /// The parameters are read from a temporary domain parameters object,
+    /// which is then destroyed.
+    pub fn generate_dsa_params(&self, prime_bits: u64) -> Result<DsaParams> {
+        let template = [
+            Attribute::Class(ObjectClass::DOMAIN_PARAMETERS),
+            Attribute::KeyType(KeyType::DSA),
+            Attribute::Token(false),
+            Attribute::PrimeBits(prime_bits.into()),
+        ];
+        let object = self.generate_key(&Mechanism::DsaParameterGen, &template)?;
+        let attributes = self.get_attributes(
+            object,
+            &[
+                AttributeType::Prime,
+                AttributeType::Subprime,
+                AttributeType::Base,
+            ],
+        );
+        if let Err(e) = self.destroy_object(object) {
+            // Keep the error of reading the parameters, if any
+            if attributes.is_ok() {
+                return Err(e);
+            }
+            error!("Failed to destroy the domain parameters object: {}", e);
+        }
+
+        match attributes?[..] {
+            [Attribute::Prime(ref prime), Attribute::Subprime(ref subprime), Attribute::Base(ref base)] => {
+                Ok(DsaParams::new(prime, subprime, base))
+            }
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    /// Derives data from a base key
+    ///
+    /// This is for mechanisms such as [`Mechanism::HkdfData`], which derive a
+    /// data object instead of a key. The `len` bytes of derived data are
+    /// read from a temporary session object, which is then destroyed.
+    pub fn derive_data(
+        &self,
+        mechanism: &Mechanism,
+        base_key: ObjectHandle,
+        len: u64,
+    ) -> Result<Vec<u8>> {
+        let template = [
+            Attribute::Class(ObjectClass::DATA),
+            Attribute::Token(false),
+            Attribute::ValueLen(len.into()),
+        ];
+        let object = self.derive_key(mechanism, base_key, &template)?;
+        let value = self.get_attributes(object, &[AttributeType::Value]);
+        if let Err(e) = self.destroy_object(object) {
+            // Keep the error of reading the value, if any
+            if value.is_ok() {
+                return Err(e);
+            }
+            error!("Failed to destroy the derived data object: {}", e);
+        }
+
+        match value?.pop() {
+            Some(Attribute::Value(value)) => Ok(value),
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    /// Derives the keys of a TLS 1.2 connection from its master secret
+    ///
+    /// `mechanism` must be [`Mechanism::Tls12KeyAndMacDerive`] or
+    /// [`Mechanism::Tls12KeySafeDerive`]. These mechanisms derive up to four
+    /// keys, which are returned along with the IVs instead of a single handle
+    /// like with [`Session::derive_key`], which must not be used with them.
+    /// `template` is used for the keys, whose class, type and length are set
+    /// by the token.
+    pub fn derive_tls_key_material(
+        &self,
+        mechanism: &Mechanism,
+        master_key: ObjectHandle,
+        template: &[Attribute],
+    ) -> Result<Tls12KeyMaterialOutput> {
+        let params = match mechanism {
+            Mechanism::Tls12KeyAndMacDerive(params) | Mechanism::Tls12KeySafeDerive(params) => {
+                params
+            }
+            _ => {
+                error!(
+                    "{} is not a TLS 1.2 key material derivation mechanism",
+                    mechanism.mechanism_type()
+                );
+                return Err(Error::InvalidValue);
+            }
+        };
+        let mut output = Tls12KeyMaterialOutput::new(params)?;
+        let mut params = params.with_output(&mut output);
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        mechanism.pParameter = &mut params as *mut CK_TLS12_KEY_MAT_PARAMS as CK_VOID_PTR;
+        let mut template: Vec<CK_ATTRIBUTE> = template.iter().map(|attr| attr.into()).collect();
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_DeriveKey)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                master_key.handle(),
+                template.as_mut_ptr(),
+                template.len().try_into()?,
+                std::ptr::null_mut(),
+            ))
+            .into_result(Function::DeriveKey)?;
+        }
+        Ok(output)
+    }
+
     /// Wrap key
+    ///
+    /// Encrypts `key` with `wrapping_key` and returns the wrapped key.  The
+    /// key to wrap must be extractable.
     pub fn wrap_key(
         &self,
         mechanism: &Mechanism,
@@ -126,11 +247,19 @@ impl Session {
             ))
             .into_result(Function::WrapKey)?;
 
+            wrapped_key.resize(wrapped_key_len.try_into()?, 0);
+
             Ok(wrapped_key)
         }
     }
 
     /// Unwrap previously wrapped key
+    ///
+    /// Decrypts `wrapped_key` with `unwrapping_key` and creates a new key
+    /// object from it, with the attributes given in `template`.
+    ///
+    /// The values of `template` are passed to the token by pointer: they are
+    /// not copied, so scrubbing them is left to the owner of the template.
     pub fn unwrap_key(
         &self,
         mechanism: &Mechanism,
@@ -157,4 +286,88 @@ impl Session {
 
         Ok(ObjectHandle::new(handle))
     }
+
+    /// Encapsulate a new shared secret key with `public_key`, as done by
+    /// key encapsulation mechanisms such as ML-KEM
+    ///
+    /// Returns the ciphertext to send to the owner of the private key, and
+    /// the new key, created with the attributes given in `template`.
+    ///
+    /// This fails with
+    /// [`Error::LibraryVersionNotSupported`] if the library does not provide
+    /// the PKCS #11 3.2 interface.
+    pub fn encapsulate_key(
+        &self,
+        mechanism: &Mechanism,
+        public_key: ObjectHandle,
+        template: &[Attribute],
+    ) -> Result<(Vec<u8>, ObjectHandle)> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        let mut template: Vec<CK_ATTRIBUTE> = template.iter().map(|attr| attr.into()).collect();
+        let mut handle = 0;
+        unsafe {
+            let mut ciphertext_len = 0;
+
+            Rv::from(get_pkcs11_32!(self.client(), C_EncapsulateKey)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                public_key.handle(),
+                template.as_mut_ptr(),
+                template.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut ciphertext_len,
+                &mut handle,
+            ))
+            .into_result(Function::EncapsulateKey)?;
+
+            let mut ciphertext = vec![0; ciphertext_len.try_into()?];
+
+            Rv::from(get_pkcs11_32!(self.client(), C_EncapsulateKey)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                public_key.handle(),
+                template.as_mut_ptr(),
+                template.len().try_into()?,
+                ciphertext.as_mut_ptr(),
+                &mut ciphertext_len,
+                &mut handle,
+            ))
+            .into_result(Function::EncapsulateKey)?;
+
+            ciphertext.resize(ciphertext_len.try_into()?, 0);
+
+            Ok((ciphertext, ObjectHandle::new(handle)))
+        }
+    }
+
+    /// Decapsulate the shared secret key of `ciphertext` with `private_key`
+    ///
+    /// The key is created with the attributes given in `template`, see
+    /// [`Session::encapsulate_key`].
+    pub fn decapsulate_key(
+        &self,
+        mechanism: &Mechanism,
+        private_key: ObjectHandle,
+        ciphertext: &[u8],
+        template: &[Attribute],
+    ) -> Result<ObjectHandle> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        let mut template: Vec<CK_ATTRIBUTE> = template.iter().map(|attr| attr.into()).collect();
+        let mut handle = 0;
+        unsafe {
+            Rv::from(get_pkcs11_32!(self.client(), C_DecapsulateKey)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                private_key.handle(),
+                template.as_mut_ptr(),
+                template.len().try_into()?,
+                ciphertext.as_ptr() as *mut u8,
+                ciphertext.len().try_into()?,
+                &mut handle,
+            ))
+            .into_result(Function::DecapsulateKey)?;
+        }
+
+        Ok(ObjectHandle::new(handle))
+    }
 }
@@ -0,0 +1,156 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Decrypting messages
+
+use crate::context::Function;
+use crate::error::{Result, Rv};
+use crate::mechanism::{Mechanism, MessageParam};
+use crate::object::ObjectHandle;
+use crate::session::{SecretBuffer, Session};
+use cryptoki_sys::*;
+use std::convert::TryInto;
+
+impl Session {
+    /// Prepare a session for one or more message-based decryption operations
+    ///
+    /// Each message is then decrypted with its own parameters through
+    /// [`Session::decrypt_message`] or [`Session::decrypt_message_begin`].
+    /// The operation is concluded with [`Session::message_decrypt_final`].
+    ///
+    /// This fails with
+    /// [`Error::LibraryVersionNotSupported`](crate::error::Error::LibraryVersionNotSupported)
+    /// if the library only provides the PKCS #11 2.40 interface.
+    pub fn message_decrypt_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageDecryptInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::MessageDecryptInit)?;
+        }
+        Ok(())
+    }
+
+    /// Decrypts a message in a single part
+    ///
+    /// With AEAD mechanisms, the tag is not expected at the end of the
+    /// ciphertext but in the buffer in the message parameters.
+    pub fn decrypt_message(
+        &self,
+        param: &MessageParam,
+        aad: &[u8],
+        encrypted_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut data_len = 0;
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_DecryptMessage)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                aad.as_ptr() as *mut u8,
+                aad.len().try_into()?,
+                encrypted_data.as_ptr() as *mut u8,
+                encrypted_data.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptMessage)?;
+        }
+
+        let mut data = SecretBuffer::new(data_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_DecryptMessage)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                aad.as_ptr() as *mut u8,
+                aad.len().try_into()?,
+                encrypted_data.as_ptr() as *mut u8,
+                encrypted_data.len().try_into()?,
+                data.as_mut_ptr(),
+                &mut data_len,
+            ))
+            .into_result(Function::DecryptMessage)?;
+        }
+
+        Ok(data.into_vec(data_len.try_into()?))
+    }
+
+    /// Begin a multi-part decryption of a message
+    ///
+    /// The message is continued with [`Session::decrypt_message_next`].
+    pub fn decrypt_message_begin(&self, param: &MessageParam, aad: &[u8]) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_DecryptMessageBegin)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                aad.as_ptr() as *mut u8,
+                aad.len().try_into()?,
+            ))
+            .into_result(Function::DecryptMessageBegin)?;
+        }
+        Ok(())
+    }
+
+    /// Continue a multi-part decryption of a message
+    ///
+    /// `end` must be set for the last part of the message, which concludes
+    /// the decryption of the message and checks its tag, if any.
+    pub fn decrypt_message_next(
+        &self,
+        param: &MessageParam,
+        encrypted_data: &[u8],
+        end: bool,
+    ) -> Result<Vec<u8>> {
+        let flags = if end { CKF_END_OF_MESSAGE } else { 0 };
+        let mut data_len = 0;
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_DecryptMessageNext)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                encrypted_data.as_ptr() as *mut u8,
+                encrypted_data.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut data_len,
+                flags,
+            ))
+            .into_result(Function::DecryptMessageNext)?;
+        }
+
+        let mut data = SecretBuffer::new(data_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_DecryptMessageNext)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                encrypted_data.as_ptr() as *mut u8,
+                encrypted_data.len().try_into()?,
+                data.as_mut_ptr(),
+                &mut data_len,
+                flags,
+            ))
+            .into_result(Function::DecryptMessageNext)?;
+        }
+
+        Ok(data.into_vec(data_len.try_into()?))
+    }
+
+    /// Finalize a message-based decryption operation
+    pub fn message_decrypt_final(&self) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageDecryptFinal)(
+                self.handle(),
+            ))
+            .into_result(Function::MessageDecryptFinal)?;
+        }
+        Ok(())
+    }
+}
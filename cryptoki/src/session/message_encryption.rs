@@ -0,0 +1,163 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Encrypting messages
+
+use crate::context::Function;
+use crate::error::{Result, Rv};
+use crate::mechanism::{Mechanism, MessageParam};
+use crate::object::ObjectHandle;
+use crate::session::Session;
+use cryptoki_sys::*;
+use std::convert::TryInto;
+
+impl Session {
+    /// Prepare a session for one or more message-based encryption operations
+    ///
+    /// Unlike [`Session::encrypt_init`], this is done once for many messages,
+    /// each of which is then encrypted with its own parameters, e.g. its own
+    /// IV, through [`Session::encrypt_message`] or
+    /// [`Session::encrypt_message_begin`]. The operation is concluded with
+    /// [`Session::message_encrypt_final`].
+    ///
+    /// The message-based functions were added in PKCS #11 3.0: they fail
+    /// with [`Error::LibraryVersionNotSupported`](crate::error::Error::LibraryVersionNotSupported)
+    /// if the library only provides the 2.40 interface.
+    pub fn message_encrypt_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageEncryptInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::MessageEncryptInit)?;
+        }
+        Ok(())
+    }
+
+    /// Encrypts a message in a single part
+    ///
+    /// With AEAD mechanisms, the tag is not appended to the ciphertext but
+    /// written to the buffer in the message parameters.
+    pub fn encrypt_message(
+        &self,
+        param: &MessageParam,
+        aad: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut encrypted_data_len = 0;
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_EncryptMessage)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                aad.as_ptr() as *mut u8,
+                aad.len().try_into()?,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::EncryptMessage)?;
+        }
+
+        let mut encrypted_data = vec![0; encrypted_data_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_EncryptMessage)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                aad.as_ptr() as *mut u8,
+                aad.len().try_into()?,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                encrypted_data.as_mut_ptr(),
+                &mut encrypted_data_len,
+            ))
+            .into_result(Function::EncryptMessage)?;
+        }
+
+        encrypted_data.resize(encrypted_data_len.try_into()?, 0);
+
+        Ok(encrypted_data)
+    }
+
+    /// Begin a multi-part encryption of a message
+    ///
+    /// The message is continued with [`Session::encrypt_message_next`].
+    pub fn encrypt_message_begin(&self, param: &MessageParam, aad: &[u8]) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_EncryptMessageBegin)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                aad.as_ptr() as *mut u8,
+                aad.len().try_into()?,
+            ))
+            .into_result(Function::EncryptMessageBegin)?;
+        }
+        Ok(())
+    }
+
+    /// Continue a multi-part encryption of a message
+    ///
+    /// `end` must be set for the last part of the message, which concludes
+    /// the encryption of the message; the same parameters as for
+    /// [`Session::encrypt_message_begin`] must be passed for every part.
+    pub fn encrypt_message_next(
+        &self,
+        param: &MessageParam,
+        data: &[u8],
+        end: bool,
+    ) -> Result<Vec<u8>> {
+        let flags = if end { CKF_END_OF_MESSAGE } else { 0 };
+        let mut encrypted_data_len = 0;
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_EncryptMessageNext)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                std::ptr::null_mut(),
+                &mut encrypted_data_len,
+                flags,
+            ))
+            .into_result(Function::EncryptMessageNext)?;
+        }
+
+        let mut encrypted_data = vec![0; encrypted_data_len.try_into()?];
+
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_EncryptMessageNext)(
+                self.handle(),
+                param.as_ptr(),
+                param.len(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                encrypted_data.as_mut_ptr(),
+                &mut encrypted_data_len,
+                flags,
+            ))
+            .into_result(Function::EncryptMessageNext)?;
+        }
+
+        encrypted_data.resize(encrypted_data_len.try_into()?, 0);
+
+        Ok(encrypted_data)
+    }
+
+    /// Finalize a message-based encryption operation
+    pub fn message_encrypt_final(&self) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageEncryptFinal)(
+                self.handle(),
+            ))
+            .into_result(Function::MessageEncryptFinal)?;
+        }
+        Ok(())
+    }
+}
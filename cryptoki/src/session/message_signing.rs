@@ -0,0 +1,283 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Signing and verifying messages
+
+use crate::context::Function;
+use crate::error::{Result, Rv};
+use crate::mechanism::{Mechanism, MessageParam};
+use crate::object::ObjectHandle;
+use crate::session::{SecretBuffer, Session};
+use cryptoki_sys::*;
+use std::convert::TryInto;
+use std::ffi::c_void;
+use std::ptr::null_mut;
+
+// Mechanisms without per-message parameters, such as HMAC, take none
+fn message_param(param: Option<&MessageParam>) -> (*mut c_void, CK_ULONG) {
+    match param {
+        Some(param) => (param.as_ptr(), param.len()),
+        None => (null_mut(), 0),
+    }
+}
+
+impl Session {
+    /// Prepare a session for one or more message-based signature or MAC
+    /// operations
+    ///
+    /// Each message is then signed through [`Session::sign_message`] or
+    /// [`Session::sign_message_begin`], without initializing the key again.
+    /// The operation is concluded with [`Session::message_sign_final`].
+    ///
+    /// This fails with
+    /// [`Error::LibraryVersionNotSupported`](crate::error::Error::LibraryVersionNotSupported)
+    /// if the library only provides the PKCS #11 2.40 interface.
+    pub fn message_sign_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageSignInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::MessageSignInit)?;
+        }
+        Ok(())
+    }
+
+    /// Signs a message in a single part
+    ///
+    /// `param` holds the parameters of this message, if the mechanism has
+    /// any, e.g. the EdDSA context.
+    pub fn sign_message(&self, param: Option<&MessageParam>, data: &[u8]) -> Result<Vec<u8>> {
+        let (param_ptr, param_len) = message_param(param);
+        let mut signature_len = 0;
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_SignMessage)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                null_mut(),
+                &mut signature_len,
+            ))
+            .into_result(Function::SignMessage)?;
+        }
+
+        let mut signature = SecretBuffer::new(signature_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_SignMessage)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                signature.as_mut_ptr(),
+                &mut signature_len,
+            ))
+            .into_result(Function::SignMessage)?;
+        }
+
+        Ok(signature.into_vec(signature_len.try_into()?))
+    }
+
+    /// Begin a multi-part signature of a message
+    ///
+    /// The message is continued with [`Session::sign_message_next`] and
+    /// concluded with [`Session::sign_message_final`].
+    pub fn sign_message_begin(&self, param: Option<&MessageParam>) -> Result<()> {
+        let (param_ptr, param_len) = message_param(param);
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_SignMessageBegin)(
+                self.handle(),
+                param_ptr,
+                param_len,
+            ))
+            .into_result(Function::SignMessageBegin)?;
+        }
+        Ok(())
+    }
+
+    /// Continue a multi-part signature of a message
+    pub fn sign_message_next(&self, param: Option<&MessageParam>, data: &[u8]) -> Result<()> {
+        let (param_ptr, param_len) = message_param(param);
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_SignMessageNext)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                null_mut(),
+                null_mut(),
+            ))
+            .into_result(Function::SignMessageNext)?;
+        }
+        Ok(())
+    }
+
+    /// Sign the last part of a message and return the signature of the whole
+    /// message
+    ///
+    /// The session is then ready for the next message.
+    pub fn sign_message_final(&self, param: Option<&MessageParam>, data: &[u8]) -> Result<Vec<u8>> {
+        let (param_ptr, param_len) = message_param(param);
+        let mut signature_len = 0;
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_SignMessageNext)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                null_mut(),
+                &mut signature_len,
+            ))
+            .into_result(Function::SignMessageNext)?;
+        }
+
+        let mut signature = SecretBuffer::new(signature_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_SignMessageNext)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                signature.as_mut_ptr(),
+                &mut signature_len,
+            ))
+            .into_result(Function::SignMessageNext)?;
+        }
+
+        Ok(signature.into_vec(signature_len.try_into()?))
+    }
+
+    /// Finalize a message-based signature operation
+    pub fn message_sign_final(&self) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageSignFinal)(
+                self.handle(),
+            ))
+            .into_result(Function::MessageSignFinal)?;
+        }
+        Ok(())
+    }
+
+    /// Prepare a session for one or more message-based verification
+    /// operations
+    ///
+    /// Each message is then verified through [`Session::verify_message`] or
+    /// [`Session::verify_message_begin`]. The operation is concluded with
+    /// [`Session::message_verify_final`].
+    ///
+    /// This fails with
+    /// [`Error::LibraryVersionNotSupported`](crate::error::Error::LibraryVersionNotSupported)
+    /// if the library only provides the PKCS #11 2.40 interface.
+    pub fn message_verify_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageVerifyInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::MessageVerifyInit)?;
+        }
+        Ok(())
+    }
+
+    /// Verifies the signature of a message in a single part
+    pub fn verify_message(
+        &self,
+        param: Option<&MessageParam>,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        let (param_ptr, param_len) = message_param(param);
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_VerifyMessage)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                signature.as_ptr() as *mut u8,
+                signature.len().try_into()?,
+            ))
+            .into_result(Function::VerifyMessage)
+        }
+    }
+
+    /// Begin a multi-part verification of a message
+    ///
+    /// The message is continued with [`Session::verify_message_next`] and
+    /// concluded with [`Session::verify_message_final`].
+    pub fn verify_message_begin(&self, param: Option<&MessageParam>) -> Result<()> {
+        let (param_ptr, param_len) = message_param(param);
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_VerifyMessageBegin)(
+                self.handle(),
+                param_ptr,
+                param_len,
+            ))
+            .into_result(Function::VerifyMessageBegin)
+        }
+    }
+
+    /// Continue a multi-part verification of a message
+    pub fn verify_message_next(&self, param: Option<&MessageParam>, data: &[u8]) -> Result<()> {
+        let (param_ptr, param_len) = message_param(param);
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_VerifyMessageNext)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                null_mut(),
+                0,
+            ))
+            .into_result(Function::VerifyMessageNext)
+        }
+    }
+
+    /// Verify the last part of a message against the signature of the whole
+    /// message
+    ///
+    /// The session is then ready for the next message.
+    pub fn verify_message_final(
+        &self,
+        param: Option<&MessageParam>,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        let (param_ptr, param_len) = message_param(param);
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_VerifyMessageNext)(
+                self.handle(),
+                param_ptr,
+                param_len,
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+                signature.as_ptr() as *mut u8,
+                signature.len().try_into()?,
+            ))
+            .into_result(Function::VerifyMessageNext)
+        }
+    }
+
+    /// Finalize a message-based verification operation
+    pub fn message_verify_final(&self) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11_30!(self.client(), C_MessageVerifyFinal)(
+                self.handle(),
+            ))
+            .into_result(Function::MessageVerifyFinal)
+        }
+    }
+}
@@ -10,17 +10,27 @@ use std::marker::PhantomData;
 
 mod decryption;
 mod digesting;
+mod dual_function;
 mod encryption;
 mod key_management;
+mod message_decryption;
+mod message_encryption;
+mod message_signing;
 mod object_management;
+mod pool;
 mod random;
 mod session_info;
 mod session_management;
 mod signing_macing;
 mod slot_token_management;
 
+pub use digesting::Digester;
+pub use encryption::Encryptor;
 pub use object_management::ObjectHandleIterator;
+pub use pool::{PooledSession, SessionPool};
 pub use session_info::{SessionInfo, SessionState};
+pub use session_management::LoginGuard;
+pub use signing_macing::{Signer, Verifier};
 
 /// Type that identifies a session
 ///
@@ -28,6 +38,13 @@ pub use session_info::{SessionInfo, SessionState};
 /// Session does not implement Sync to prevent the same Session instance to be used from multiple
 /// threads. A Session needs to be created in its own thread or to be passed by ownership to
 /// another thread.
+///
+/// Multi-part operations such as [`Session::sign_init`], [`Session::sign_update`] and
+/// [`Session::sign_final`] keep their state in the token, not in this type. A session can only
+/// run one operation at a time: starting another one before the current one is finalized
+/// fails with [`RvError::OperationActive`](crate::error::RvError::OperationActive). To run
+/// several operations side by side, open one session for each. The exceptions are the pairs
+/// of operations of the dual-function calls, such as [`Session::digest_encrypt_update`].
 #[derive(Debug)]
 pub struct Session {
     handle: CK_SESSION_HANDLE,
@@ -82,6 +99,55 @@ impl Session {
     }
 }
 
+// Scrub a buffer which held secret data, with the `zeroize` feature
+#[cfg(feature = "zeroize")]
+fn scrub(buffer: &mut [u8]) {
+    zeroize::Zeroize::zeroize(buffer);
+}
+
+#[cfg(not(feature = "zeroize"))]
+fn scrub(_buffer: &mut [u8]) {}
+
+// Output buffer for secret data written by the token. It is scrubbed when
+// dropped, so that the data does not linger in memory when a call fails, and
+// only the bytes returned by the token are moved out of it.
+struct SecretBuffer {
+    buffer: Vec<u8>,
+}
+
+impl SecretBuffer {
+    fn new(len: usize) -> Self {
+        SecretBuffer {
+            buffer: vec![0; len],
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr()
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    // Keep the first `len` bytes, the length returned by the token. The bytes
+    // cut off stay in the capacity of the vector, so they are scrubbed first.
+    fn into_vec(mut self, len: usize) -> Vec<u8> {
+        if let Some(tail) = self.buffer.get_mut(len..) {
+            scrub(tail);
+        }
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.truncate(len);
+        buffer
+    }
+}
+
+impl Drop for SecretBuffer {
+    fn drop(&mut self) {
+        scrub(&mut self.buffer);
+    }
+}
+
 /// Types of PKCS11 users
 #[derive(Copy, Clone, Debug)]
 pub enum UserType {
@@ -107,3 +173,18 @@ impl From<UserType> for CK_USER_TYPE {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn secret_buffer_into_vec() {
+        let mut buffer = SecretBuffer::new(16);
+        assert_eq!(buffer.len(), 16);
+        // SAFETY: the buffer is 16 bytes long
+        unsafe { std::ptr::write_bytes(buffer.as_mut_ptr(), 0x42, 16) };
+        assert_eq!(buffer.into_vec(4), [0x42; 4]);
+        assert_eq!(SecretBuffer::new(4).into_vec(8), [0; 4]);
+    }
+}
@@ -4,8 +4,8 @@
 
 use crate::context::Function;
 use crate::error::{Error, Result, Rv, RvError};
-use crate::object::{Attribute, AttributeInfo, AttributeType, ObjectHandle};
-use crate::session::Session;
+use crate::object::{Attribute, AttributeInfo, AttributeType, AttributeValue, ObjectHandle};
+use crate::session::{SecretBuffer, Session};
 use cryptoki_sys::*;
 use std::collections::HashMap;
 use std::convert::TryInto;
@@ -224,7 +224,7 @@ impl Session {
     /// * [`ObjectHandleIterator`] for more information on how to use the iterator
     /// * [`Session::iter_objects_with_cache_size`] for a way to specify the cache size
     #[inline(always)]
-    pub fn iter_objects(&self, template: &[Attribute]) -> Result<ObjectHandleIterator> {
+    pub fn iter_objects(&self, template: &[Attribute]) -> Result<ObjectHandleIterator<'_>> {
         self.iter_objects_with_cache_size(template, MAX_OBJECT_COUNT)
     }
 
@@ -248,7 +248,7 @@ impl Session {
         &self,
         template: &[Attribute],
         cache_size: NonZeroUsize,
-    ) -> Result<ObjectHandleIterator> {
+    ) -> Result<ObjectHandleIterator<'_>> {
         let template: Vec<CK_ATTRIBUTE> = template.iter().map(Into::into).collect();
         ObjectHandleIterator::new(self, template, cache_size)
     }
@@ -258,7 +258,7 @@ impl Session {
     /// # Arguments
     ///
     /// * `template` - A reference to [Attribute] of search parameters that will be used
-    ///                to find objects.
+    ///   to find objects.
     ///
     /// # Returns
     ///
@@ -272,7 +272,7 @@ impl Session {
     /// # See also
     ///
     /// * [`Session::iter_objects`] for a way to specify the cache size
-
+    ///
     /// # Example
     ///
     /// ```rust
@@ -352,6 +352,9 @@ impl Session {
     ///
     /// This function will return a new [ObjectHandle] that references the newly created object.
     ///
+    /// A session object can be made persistent by copying it with
+    /// [`Attribute::Token`] set to `true`.  The original object is left
+    /// untouched, and destroyed with its session unless destroyed before.
     pub fn copy_object(
         &self,
         object: ObjectHandle,
@@ -374,6 +377,41 @@ impl Session {
         Ok(ObjectHandle::new(object_handle))
     }
 
+    /// Get the size of an object, in bytes
+    ///
+    /// The size is the amount of token memory used by the object, whose
+    /// meaning is token specific: it is not necessarily the size of its
+    /// attribute values.
+    ///
+    /// Returns `None` if the token does not reveal the size of the object,
+    /// either because it considers it sensitive or because it cannot
+    /// determine it. Tokens not supporting this function at all fail with
+    /// [`RvError::FunctionNotSupported`].
+    pub fn get_object_size(&self, object: ObjectHandle) -> Result<Option<u64>> {
+        let mut size = 0;
+
+        unsafe {
+            match Rv::from(get_pkcs11!(self.client(), C_GetObjectSize)(
+                self.handle(),
+                object.handle(),
+                &mut size,
+            ))
+            .into_result(Function::GetObjectSize)
+            {
+                Ok(()) => (),
+                Err(Error::Pkcs11(RvError::InformationSensitive, _)) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if size == CK_UNAVAILABLE_INFORMATION {
+            return Ok(None);
+        }
+
+        #[allow(clippy::useless_conversion)]
+        Ok(Some(size.into()))
+    }
+
     /// Get the attribute info of an object: if the attribute is present and its size.
     ///
     /// # Arguments
@@ -496,20 +534,33 @@ impl Session {
     /// Get the attributes values of an object.
     /// Ignore the unavailable one. One has to call the get_attribute_info method to check which
     /// ones are unavailable.
+    ///
+    /// With the `zeroize` feature, the buffers the values are read into are
+    /// scrubbed, but the returned attributes are not scrubbed when dropped.
     pub fn get_attributes(
         &self,
         object: ObjectHandle,
         attributes: &[AttributeType],
     ) -> Result<Vec<Attribute>> {
         let attrs_info = self.get_attribute_info(object, attributes)?;
+        self.get_available_attributes(object, attributes, &attrs_info)
+    }
 
+    // Get the values of the attributes which are available according to
+    // `attrs_info`, in a single call
+    fn get_available_attributes(
+        &self,
+        object: ObjectHandle,
+        attributes: &[AttributeType],
+        attrs_info: &[AttributeInfo],
+    ) -> Result<Vec<Attribute>> {
         // Allocating a chunk of memory where to put the attributes value.
-        let attrs_memory: Vec<(AttributeType, Vec<u8>)> = attrs_info
+        let mut attrs_memory: Vec<(AttributeType, SecretBuffer)> = attrs_info
             .iter()
             .zip(attributes.iter())
             .filter_map(|(attr_info, attr_type)| {
                 if let AttributeInfo::Available(size) = attr_info {
-                    Some((*attr_type, vec![0; *size]))
+                    Some((*attr_type, SecretBuffer::new(*size)))
                 } else {
                     None
                 }
@@ -517,11 +568,11 @@ impl Session {
             .collect();
 
         let mut template: Vec<CK_ATTRIBUTE> = attrs_memory
-            .iter()
+            .iter_mut()
             .map(|(attr_type, memory)| {
                 Ok(CK_ATTRIBUTE {
                     type_: (*attr_type).into(),
-                    pValue: memory.as_ptr() as *mut std::ffi::c_void,
+                    pValue: memory.as_mut_ptr() as *mut std::ffi::c_void,
                     ulValueLen: memory.len().try_into()?,
                 })
             })
@@ -539,11 +590,47 @@ impl Session {
             .into_result(Function::GetAttributeValue)?;
         }
 
-        // Convert from CK_ATTRIBUTE to Attribute
+        // Convert from CK_ATTRIBUTE to Attribute, copying the values: the
+        // buffers, which may hold key material, are scrubbed when dropped
         template.into_iter().map(|attr| attr.try_into()).collect()
     }
 
+    /// Get the attributes values of an object, one for each of `attributes`
+    ///
+    /// Unlike [`Session::get_attributes`], the unavailable attributes are not
+    /// ignored: they are [`AttributeValue::Sensitive`] or
+    /// [`AttributeValue::TypeInvalid`]. The values are read with a single
+    /// `C_GetAttributeValue` call, after the information about the attributes.
+    pub fn get_attribute_values(
+        &self,
+        object: ObjectHandle,
+        attributes: &[AttributeType],
+    ) -> Result<Vec<AttributeValue>> {
+        let attrs_info = self.get_attribute_info(object, attributes)?;
+        let mut values = self
+            .get_available_attributes(object, attributes, &attrs_info)?
+            .into_iter();
+
+        attrs_info
+            .iter()
+            .map(|attr_info| match attr_info {
+                // There is one value for each available attribute
+                AttributeInfo::Available(_) => values
+                    .next()
+                    .map(AttributeValue::Available)
+                    .ok_or(Error::InvalidValue),
+                AttributeInfo::Sensitive => Ok(AttributeValue::Sensitive),
+                AttributeInfo::TypeInvalid => Ok(AttributeValue::TypeInvalid),
+            })
+            .collect()
+    }
+
     /// Sets the attributes of an object
+    ///
+    /// Some attributes can not be modified once the object is created, or only
+    /// in one direction, e.g. [`Attribute::Extractable`] can be set to `false`
+    /// but not back to `true`.  Attempts to do so fail with
+    /// [`RvError::AttributeReadOnly`].
     pub fn update_attributes(&self, object: ObjectHandle, template: &[Attribute]) -> Result<()> {
         let mut template: Vec<CK_ATTRIBUTE> = template.iter().map(|attr| attr.into()).collect();
 
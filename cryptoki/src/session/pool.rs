@@ -0,0 +1,252 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Pool of sessions
+
+use crate::context::{Function, Pkcs11};
+use crate::error::{Error, Result, RvError};
+use crate::session::{Session, SessionState, UserType};
+use crate::slot::Slot;
+use crate::types::AuthPin;
+use log::warn;
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
+
+/// A pool of sessions on a slot, which can be shared between threads
+///
+/// Sessions are taken from the pool with [`SessionPool::get`] and given back
+/// to it when the returned [`PooledSession`] is dropped, saving the cost of
+/// opening a session, and of logging it in, for each use. This matters with
+/// network HSMs, where each call is a round-trip.
+///
+/// When a login is configured, every session handed out is logged in: as
+/// the login state is shared by all the sessions of an application on a
+/// token, this only logs in again after the token logged the application
+/// out. Sessions that became invalid, e.g. after the token was removed, are
+/// replaced by new ones.
+///
+/// # Example
+///
+/// ```no_run
+/// use cryptoki::context::{CInitializeArgs, Pkcs11};
+/// use cryptoki::session::{SessionPool, UserType};
+/// use cryptoki::types::AuthPin;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// # fn main() -> testresult::TestResult {
+/// let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+/// pkcs11.initialize(CInitializeArgs::OsThreads)?;
+/// let slot = pkcs11.get_slots_with_token()?[0];
+///
+/// let pool = Arc::new(
+///     SessionPool::new(pkcs11, slot, false)
+///         .with_login(UserType::User, AuthPin::new("fedcba".into()))
+///         .with_idle_sessions(2, 8)?,
+/// );
+/// pool.fill()?;
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let pool = pool.clone();
+///         thread::spawn(move || -> cryptoki::error::Result<Vec<u8>> {
+///             let session = pool.get()?;
+///             session.generate_random_vec(32)
+///         })
+///     })
+///     .collect();
+/// for worker in workers {
+///     let _ = worker.join().unwrap()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SessionPool {
+    pkcs11: Pkcs11,
+    slot: Slot,
+    read_write: bool,
+    login: Option<(UserType, AuthPin)>,
+    min_idle: usize,
+    max_idle: usize,
+    idle: Mutex<Vec<Session>>,
+}
+
+impl SessionPool {
+    /// Create an empty pool of read-only, or read-write, sessions on `slot`
+    ///
+    /// The sessions are not logged in, and up to 8 idle sessions are kept.
+    pub fn new(pkcs11: Pkcs11, slot: Slot, read_write: bool) -> Self {
+        SessionPool {
+            pkcs11,
+            slot,
+            read_write,
+            login: None,
+            min_idle: 0,
+            max_idle: 8,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Log the sessions in as `user_type`, with `pin`
+    pub fn with_login(mut self, user_type: UserType, pin: AuthPin) -> Self {
+        self.login = Some((user_type, pin));
+        self
+    }
+
+    /// Set the number of idle sessions
+    ///
+    /// [`SessionPool::fill`] opens sessions until `min` are idle, and
+    /// sessions given back to the pool are closed once `max` are idle.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidValue`] if `min` is greater than
+    /// `max`.
+    pub fn with_idle_sessions(mut self, min: usize, max: usize) -> Result<Self> {
+        if min > max {
+            log::error!(
+                "The minimum number of idle sessions ({}) is greater than the maximum ({})",
+                min,
+                max
+            );
+            return Err(Error::InvalidValue);
+        }
+        self.min_idle = min;
+        self.max_idle = max;
+        Ok(self)
+    }
+
+    /// Open and log in sessions until the minimum number of idle sessions is
+    /// reached
+    pub fn fill(&self) -> Result<()> {
+        while self.idle_sessions() < self.min_idle {
+            let session = self.open()?;
+            // Other threads may have filled the pool while the session was
+            // being opened: the extra session is closed
+            let mut idle = self.idle();
+            if idle.len() < self.min_idle {
+                idle.push(session);
+            }
+        }
+        Ok(())
+    }
+
+    /// Take a session out of the pool, or open a new one if none is idle
+    ///
+    /// The session is given back to the pool when the returned guard is
+    /// dropped.
+    pub fn get(&self) -> Result<PooledSession<'_>> {
+        loop {
+            let session = self.idle().pop();
+            let session = match session {
+                Some(session) => session,
+                None => self.open()?,
+            };
+            match self.check(&session) {
+                Ok(()) => {
+                    return Ok(PooledSession {
+                        pool: self,
+                        session: Some(session),
+                    })
+                }
+                Err(Error::Pkcs11(
+                    RvError::SessionHandleInvalid
+                    | RvError::SessionClosed
+                    | RvError::DeviceRemoved
+                    | RvError::TokenNotPresent,
+                    Function::GetSessionInfo,
+                )) => warn!("Dropping invalid session {}", session),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// The number of idle sessions in the pool
+    pub fn idle_sessions(&self) -> usize {
+        self.idle().len()
+    }
+
+    fn idle(&self) -> MutexGuard<'_, Vec<Session>> {
+        // The sessions are still valid if another thread panicked while
+        // holding the lock
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn open(&self) -> Result<Session> {
+        if self.read_write {
+            self.pkcs11.open_rw_session(self.slot)
+        } else {
+            self.pkcs11.open_ro_session(self.slot)
+        }
+    }
+
+    // Check that the session is still valid, and log it in if needed
+    fn check(&self, session: &Session) -> Result<()> {
+        let state = session.get_session_info()?.session_state();
+        if let Some((user_type, pin)) = &self.login {
+            if matches!(state, SessionState::RoPublic | SessionState::RwPublic) {
+                match session.login(*user_type, Some(pin)) {
+                    // Another session logged in in the meantime
+                    Ok(()) | Err(Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A session taken from a [`SessionPool`]
+///
+/// The session is given back to the pool on drop. The operations left active
+/// in it, such as an abandoned multi-part operation, are terminated first.
+#[derive(Debug)]
+pub struct PooledSession<'a> {
+    pool: &'a SessionPool,
+    // Only None while dropping
+    session: Option<Session>,
+}
+
+impl PooledSession<'_> {
+    /// Close the session instead of giving it back to the pool, e.g. after
+    /// the token reported an error that may have left it in a bad state
+    pub fn discard(mut self) {
+        let _ = self.session.take();
+    }
+}
+
+impl Deref for PooledSession<'_> {
+    type Target = Session;
+
+    fn deref(&self) -> &Session {
+        self.session
+            .as_ref()
+            .expect("the session is only taken on drop")
+    }
+}
+
+// Terminate the operations a user of the session may have left active, so
+// that the next user can start its own. Finalizing an operation terminates
+// it, whether it succeeds or not, and fails right away when no operation of
+// its kind is active.
+fn reset(session: &Session) {
+    let _ = session.encrypt_final();
+    let _ = session.decrypt_final();
+    let _ = session.digest_final();
+    let _ = session.sign_final();
+    let _ = session.verify_final(&[]);
+}
+
+impl Drop for PooledSession<'_> {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            reset(&session);
+            let mut idle = self.pool.idle();
+            if idle.len() < self.pool.max_idle {
+                idle.push(session);
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::context::Function;
 use crate::error::{Result, Rv};
-use crate::session::Session;
+use crate::session::{SecretBuffer, Session};
 use std::convert::TryInto;
 
 impl Session {
@@ -28,8 +28,11 @@ impl Session {
 
     /// Generates random data and returns it as a `Vec<u8>`.  The length of the returned Vector will
     /// be the amount of random requested, which is `random_len`.
+    ///
+    /// With the `zeroize` feature, the buffer is scrubbed if the call fails. The returned vector
+    /// is not scrubbed when dropped: wrap it in `zeroize::Zeroizing` if it holds key material.
     pub fn generate_random_vec(&self, random_len: u32) -> Result<Vec<u8>> {
-        let mut result: Vec<u8> = vec![0; random_len as usize];
+        let mut result = SecretBuffer::new(random_len as usize);
         unsafe {
             Rv::from(get_pkcs11!(self.client(), C_GenerateRandom)(
                 self.handle(),
@@ -38,10 +41,32 @@ impl Session {
             ))
             .into_result(Function::GenerateRandom)?;
         }
-        Ok(result)
+        Ok(result.into_vec(random_len as usize))
     }
 
     /// Seeds the RNG
+    ///
+    /// The seed is mixed into the state of the token's RNG, it does not
+    /// replace it. Tokens which do not accept seeding from an application
+    /// fail with [`RvError::RandomSeedNotSupported`](crate::error::RvError::RandomSeedNotSupported),
+    /// which callers contributing entropy on a best-effort basis can ignore:
+    ///
+    /// ```no_run
+    /// # fn main() -> testresult::TestResult {
+    /// # use cryptoki::context::{CInitializeArgs, Pkcs11};
+    /// use cryptoki::error::{Error, RvError};
+    ///
+    /// # let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+    /// # pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    /// # let slot = pkcs11.get_slots_with_token()?[0];
+    /// let session = pkcs11.open_ro_session(slot)?;
+    /// match session.seed_random(&[0x42; 32]) {
+    ///     Ok(()) | Err(Error::Pkcs11(RvError::RandomSeedNotSupported, _)) => (),
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn seed_random(&self, seed: &[u8]) -> Result<()> {
         unsafe {
             Rv::from(get_pkcs11!(self.client(), C_SeedRandom)(
@@ -4,15 +4,17 @@
 
 use crate::context::Function;
 use crate::error::{Result, Rv};
-use crate::session::{Session, SessionInfo, UserType};
+use crate::object::ObjectHandle;
+use crate::session::{SecretBuffer, Session, SessionInfo, UserType};
 use crate::types::{AuthPin, RawAuthPin};
 
 #[cfg(doc)]
 use cryptoki_sys::CKF_PROTECTED_AUTHENTICATION_PATH;
-use cryptoki_sys::CK_SESSION_INFO;
+use cryptoki_sys::{CK_INVALID_HANDLE, CK_SESSION_INFO};
 use log::error;
 use secrecy::ExposeSecret;
 use std::convert::{TryFrom, TryInto};
+use std::ops::Deref;
 
 impl Drop for Session {
     fn drop(&mut self) {
@@ -61,6 +63,20 @@ impl Session {
         }
     }
 
+    /// Log a session in through the protected authentication path of the
+    /// token, e.g. a PIN pad on a card reader
+    ///
+    /// This is [`Session::login`] without a PIN: the PIN is entered on the
+    /// device, and the call returns once the user did so. An incorrect PIN
+    /// fails with [`RvError::PinIncorrect`](crate::error::RvError::PinIncorrect)
+    /// like a normal login.
+    ///
+    /// _NOTE: The [CKF_PROTECTED_AUTHENTICATION_PATH] flag must be set in the
+    /// `TokenFlags`._
+    pub fn login_with_protected_path(&self, user_type: UserType) -> Result<()> {
+        self.login(user_type, None)
+    }
+
     /// Logs a session in using a slice of raw bytes as a PIN. Some dongle drivers allow
     /// non UTF-8 characters in the PIN and as a result, we aren't guaranteed that we can
     /// pass in a UTF-8 string to login. Therefore, it's useful to be able to pass in raw bytes
@@ -85,6 +101,27 @@ impl Session {
         }
     }
 
+    /// Log a session in until the returned guard is dropped.
+    ///
+    /// This is [`Session::login`], followed by [`Session::logout`] when the
+    /// guard is dropped, including on early returns and during unwinding.
+    /// Errors of the logout on drop are logged and ignored: use
+    /// [`LoginGuard::logout`] to handle them.
+    ///
+    /// The login state is shared by all sessions of the application on the
+    /// token, so logging out also logs out the other sessions.
+    pub fn login_scoped(
+        &self,
+        user_type: UserType,
+        pin: Option<&AuthPin>,
+    ) -> Result<LoginGuard<'_>> {
+        self.login(user_type, pin)?;
+        Ok(LoginGuard {
+            session: self,
+            logged_in: true,
+        })
+    }
+
     /// Log a session out
     pub fn logout(&self) -> Result<()> {
         unsafe {
@@ -105,4 +142,94 @@ impl Session {
             SessionInfo::try_from(session_info)
         }
     }
+
+    /// Save the state of the operations active in a session
+    ///
+    /// The state can be restored with [`Session::set_operation_state`], in this
+    /// session or in another session of the same token. Its format is
+    /// specific to the token, and it may hold secret data.
+    pub fn get_operation_state(&self) -> Result<Vec<u8>> {
+        let mut state_len = 0;
+        // Get the state length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_GetOperationState)(
+                self.handle(),
+                std::ptr::null_mut(),
+                &mut state_len,
+            ))
+            .into_result(Function::GetOperationState)?;
+        }
+
+        let mut state = SecretBuffer::new(state_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_GetOperationState)(
+                self.handle(),
+                state.as_mut_ptr(),
+                &mut state_len,
+            ))
+            .into_result(Function::GetOperationState)?;
+        }
+
+        Ok(state.into_vec(state_len.try_into()?))
+    }
+
+    /// Restore the state of operations saved with
+    /// [`Session::get_operation_state`]
+    ///
+    /// The keys of the saved operations are not part of the state:
+    /// `encryption_key` is the key of a saved encryption or decryption
+    /// operation, and `authentication_key` the key of a saved signature, MAC
+    /// or verification operation.
+    pub fn set_operation_state(
+        &self,
+        state: &[u8],
+        encryption_key: Option<ObjectHandle>,
+        authentication_key: Option<ObjectHandle>,
+    ) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SetOperationState)(
+                self.handle(),
+                state.as_ptr() as *mut u8,
+                state.len().try_into()?,
+                encryption_key.map_or(CK_INVALID_HANDLE, |key| key.handle()),
+                authentication_key.map_or(CK_INVALID_HANDLE, |key| key.handle()),
+            ))
+            .into_result(Function::SetOperationState)
+        }
+    }
+}
+
+/// Keeps a session logged in, see [`Session::login_scoped`]
+#[derive(Debug)]
+pub struct LoginGuard<'a> {
+    session: &'a Session,
+    logged_in: bool,
+}
+
+impl LoginGuard<'_> {
+    /// Log the session out now, returning any error
+    pub fn logout(mut self) -> Result<()> {
+        self.logged_in = false;
+        self.session.logout()
+    }
+}
+
+impl Deref for LoginGuard<'_> {
+    type Target = Session;
+
+    fn deref(&self) -> &Session {
+        self.session
+    }
+}
+
+impl Drop for LoginGuard<'_> {
+    fn drop(&mut self) {
+        if self.logged_in {
+            // Never panic here: this may run while unwinding
+            if let Err(e) = self.session.logout() {
+                error!("Failed to log out session: {}", e);
+            }
+        }
+    }
 }
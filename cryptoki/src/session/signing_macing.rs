@@ -6,10 +6,92 @@ use crate::context::Function;
 use crate::error::{Result, Rv};
 use crate::mechanism::Mechanism;
 use crate::object::ObjectHandle;
-use crate::session::Session;
+use crate::session::{SecretBuffer, Session};
 use cryptoki_sys::*;
+use log::error;
 use std::convert::TryInto;
 
+/// A multi-part signing or MACing operation, see [`Session::sign_streaming`]
+///
+/// The operation borrows its session mutably, so no other operation can be
+/// started on the session until the operation is finalized or dropped.
+/// Dropping an operation which was not finalized terminates it.
+#[derive(Debug)]
+pub struct Signer<'a> {
+    session: &'a mut Session,
+    active: bool,
+}
+
+impl Signer<'_> {
+    /// Sign the next part of the data
+    ///
+    /// See [`Session::sign_update`]. If this call fails, the operation is
+    /// terminated.
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        let res = self.session.sign_update(data);
+        self.active = res.is_ok();
+        res
+    }
+
+    /// Finalize the operation, returning the signature
+    pub fn finalize(mut self) -> Result<Vec<u8>> {
+        self.active = false;
+        self.session.sign_final()
+    }
+}
+
+impl Drop for Signer<'_> {
+    fn drop(&mut self) {
+        if self.active {
+            // Never panic here: this may run while unwinding
+            if let Err(e) = self.session.sign_final() {
+                error!("Failed to terminate signing operation: {}", e);
+            }
+        }
+    }
+}
+
+/// A multi-part verifying operation, see [`Session::verify_streaming`]
+///
+/// The operation borrows its session mutably, so no other operation can be
+/// started on the session until the operation is finalized or dropped.
+/// Dropping an operation which was not finalized terminates it.
+#[derive(Debug)]
+pub struct Verifier<'a> {
+    session: &'a mut Session,
+    active: bool,
+}
+
+impl Verifier<'_> {
+    /// Verify the next part of the data
+    ///
+    /// See [`Session::verify_update`]. If this call fails, the operation is
+    /// terminated.
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        let res = self.session.verify_update(data);
+        self.active = res.is_ok();
+        res
+    }
+
+    /// Finalize the operation, checking the data against `signature`
+    ///
+    /// See [`Session::verify_final`] for the errors of a signature which
+    /// does not match.
+    pub fn finalize(mut self, signature: &[u8]) -> Result<()> {
+        self.active = false;
+        self.session.verify_final(signature)
+    }
+}
+
+impl Drop for Verifier<'_> {
+    fn drop(&mut self) {
+        if self.active {
+            // The empty signature is rejected, which terminates the operation
+            let _ = self.session.verify_final(&[]);
+        }
+    }
+}
+
 impl Session {
     /// Sign data in single-part
     pub fn sign(&self, mechanism: &Mechanism, key: ObjectHandle, data: &[u8]) -> Result<Vec<u8>> {
@@ -37,7 +119,7 @@ impl Session {
             .into_result(Function::Sign)?;
         }
 
-        let mut signature = vec![0; signature_len.try_into()?];
+        let mut signature = SecretBuffer::new(signature_len.try_into()?);
 
         //TODO: we should add a new error instead of those unwrap!
         unsafe {
@@ -51,9 +133,110 @@ impl Session {
             .into_result(Function::Sign)?;
         }
 
-        signature.resize(signature_len.try_into()?, 0);
+        Ok(signature.into_vec(signature_len.try_into()?))
+    }
+
+    /// Starts new multi-part signing operation
+    ///
+    /// The operation is continued with [`Session::sign_update`] and
+    /// concluded with [`Session::sign_final`]. It stays active on the session
+    /// until [`Session::sign_final`] returns a signature or one of the calls
+    /// fails; an abandoned operation prevents other signing operations from
+    /// being started on this session.
+    pub fn sign_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SignInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::SignInit)?;
+        }
+
+        Ok(())
+    }
 
-        Ok(signature)
+    /// Starts new multi-part signing operation, returned as a [`Signer`]
+    ///
+    /// Unlike with [`Session::sign_init`], the steps of the operation can
+    /// only be called in order, and the session cannot be used for anything
+    /// else until the operation is over.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> testresult::TestResult {
+    /// # use cryptoki::context::{CInitializeArgs, Pkcs11};
+    /// # use cryptoki::mechanism::Mechanism;
+    /// # use cryptoki::object::{Attribute, ObjectClass};
+    /// # let pkcs11 = Pkcs11::new("/usr/local/lib/softhsm/libsofthsm2.so")?;
+    /// # pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    /// # let slot = pkcs11.get_slots_with_token()?.remove(0);
+    /// # let mut session = pkcs11.open_ro_session(slot)?;
+    /// # let key = session.find_objects(&[Attribute::Class(ObjectClass::PRIVATE_KEY)])?[0];
+    /// let mut signer = session.sign_streaming(&Mechanism::Sha256RsaPkcs, key)?;
+    /// signer.update(b"first part, ")?;
+    /// signer.update(b"second part")?;
+    /// let signature = signer.finalize()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn sign_streaming(
+        &mut self,
+        mechanism: &Mechanism,
+        key: ObjectHandle,
+    ) -> Result<Signer<'_>> {
+        self.sign_init(mechanism, key)?;
+        Ok(Signer {
+            session: self,
+            active: true,
+        })
+    }
+
+    /// Continues an ongoing multi-part signing operation,
+    /// taking in the next part of the data to sign
+    ///
+    /// Calling this without an active signing operation fails with
+    /// [`RvError::OperationNotInitialized`](crate::error::RvError::OperationNotInitialized).
+    pub fn sign_update(&self, data: &[u8]) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SignUpdate)(
+                self.handle(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+            ))
+            .into_result(Function::SignUpdate)
+        }
+    }
+
+    /// Finalizes ongoing multi-part signing operation,
+    /// returning the signature
+    pub fn sign_final(&self) -> Result<Vec<u8>> {
+        let mut signature_len = 0;
+
+        // Get the output buffer length
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SignFinal)(
+                self.handle(),
+                std::ptr::null_mut(),
+                &mut signature_len,
+            ))
+            .into_result(Function::SignFinal)?;
+        }
+
+        let mut signature = SecretBuffer::new(signature_len.try_into()?);
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SignFinal)(
+                self.handle(),
+                signature.as_mut_ptr(),
+                &mut signature_len,
+            ))
+            .into_result(Function::SignFinal)?;
+        }
+
+        Ok(signature.into_vec(signature_len.try_into()?))
     }
 
     /// Verify data in single-part
@@ -86,4 +269,74 @@ impl Session {
             .into_result(Function::Verify)
         }
     }
+
+    /// Starts new multi-part verifying operation
+    ///
+    /// The operation is continued with [`Session::verify_update`] and
+    /// concluded with [`Session::verify_final`]. While it is active, starting
+    /// another operation on this session fails with
+    /// [`RvError::OperationActive`](crate::error::RvError::OperationActive).
+    pub fn verify_init(&self, mechanism: &Mechanism, key: ObjectHandle) -> Result<()> {
+        let mut mechanism: CK_MECHANISM = mechanism.into();
+
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_VerifyInit)(
+                self.handle(),
+                &mut mechanism as CK_MECHANISM_PTR,
+                key.handle(),
+            ))
+            .into_result(Function::VerifyInit)?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts new multi-part verifying operation, returned as a
+    /// [`Verifier`]
+    ///
+    /// Like [`Session::sign_streaming`], the steps of the operation can only
+    /// be called in order.
+    pub fn verify_streaming(
+        &mut self,
+        mechanism: &Mechanism,
+        key: ObjectHandle,
+    ) -> Result<Verifier<'_>> {
+        self.verify_init(mechanism, key)?;
+        Ok(Verifier {
+            session: self,
+            active: true,
+        })
+    }
+
+    /// Continues an ongoing multi-part verifying operation,
+    /// taking in the next part of the data to verify
+    pub fn verify_update(&self, data: &[u8]) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_VerifyUpdate)(
+                self.handle(),
+                data.as_ptr() as *mut u8,
+                data.len().try_into()?,
+            ))
+            .into_result(Function::VerifyUpdate)
+        }
+    }
+
+    /// Finalizes ongoing multi-part verifying operation,
+    /// checking the data against `signature`
+    ///
+    /// A signature that does not match fails with
+    /// [`RvError::SignatureInvalid`](crate::error::RvError::SignatureInvalid),
+    /// and one that does not have the expected length with
+    /// [`RvError::SignatureLenRange`](crate::error::RvError::SignatureLenRange).
+    /// Both terminate the verifying operation.
+    pub fn verify_final(&self, signature: &[u8]) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_VerifyFinal)(
+                self.handle(),
+                signature.as_ptr() as *mut u8,
+                signature.len().try_into()?,
+            ))
+            .into_result(Function::VerifyFinal)
+        }
+    }
 }
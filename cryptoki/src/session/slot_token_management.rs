@@ -5,7 +5,7 @@
 use crate::context::Function;
 use crate::error::{Result, Rv};
 use crate::session::Session;
-use crate::types::AuthPin;
+use crate::types::{AuthPin, RawAuthPin};
 use secrecy::ExposeSecret;
 use std::convert::TryInto;
 
@@ -36,4 +36,34 @@ impl Session {
             .into_result(Function::SetPIN)
         }
     }
+
+    /// Initialize the normal user's pin for a token, with a PIN of raw bytes
+    ///
+    /// See [`Session::login_with_raw`] for why PINs may not be UTF-8.
+    pub fn init_pin_with_raw(&self, pin: &RawAuthPin) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_InitPIN)(
+                self.handle(),
+                pin.expose_secret().as_ptr() as *mut u8,
+                pin.expose_secret().len().try_into()?,
+            ))
+            .into_result(Function::InitPIN)
+        }
+    }
+
+    /// Changes the PIN like [`Session::set_pin`], with PINs of raw bytes
+    ///
+    /// See [`Session::login_with_raw`] for why PINs may not be UTF-8.
+    pub fn set_pin_with_raw(&self, old_pin: &RawAuthPin, new_pin: &RawAuthPin) -> Result<()> {
+        unsafe {
+            Rv::from(get_pkcs11!(self.client(), C_SetPIN)(
+                self.handle(),
+                old_pin.expose_secret().as_ptr() as *mut u8,
+                old_pin.expose_secret().len().try_into()?,
+                new_pin.expose_secret().as_ptr() as *mut u8,
+                new_pin.expose_secret().len().try_into()?,
+            ))
+            .into_result(Function::SetPIN)
+        }
+    }
 }
@@ -0,0 +1,75 @@
+// Copyright 2024 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+//! Adapters to the traits of the [`signature`](https://docs.rs/signature) crate
+//!
+//! [`Pkcs11Signer`] and [`Pkcs11Verifier`] implement
+//! [`Signer`](::signature::Signer) and [`Verifier`](::signature::Verifier)
+//! for a key in a token, so that it can be used by code written against those
+//! traits. The signatures are the raw output of the mechanism, e.g. `r || s`
+//! for ECDSA, not a DER encoding.
+//!
+//! # Thread safety
+//!
+//! The adapters borrow a [`Session`], which is not `Sync`: they can only be
+//! used on the thread owning the session. As a session runs a single
+//! operation at a time, signing with one adapter while another operation,
+//! e.g. a multi-part digest, is active in the same session fails. Use a
+//! session per adapter to avoid this.
+
+use crate::mechanism::Mechanism;
+use crate::object::ObjectHandle;
+use crate::session::Session;
+
+/// A signer backed by a private or secret key in a token
+#[derive(Debug)]
+pub struct Pkcs11Signer<'a> {
+    session: &'a Session,
+    mechanism: Mechanism<'a>,
+    key: ObjectHandle,
+}
+
+impl<'a> Pkcs11Signer<'a> {
+    /// Sign with `key`, through `mechanism`, in `session`
+    pub fn new(session: &'a Session, mechanism: Mechanism<'a>, key: ObjectHandle) -> Self {
+        Pkcs11Signer {
+            session,
+            mechanism,
+            key,
+        }
+    }
+}
+
+impl ::signature::Signer<Vec<u8>> for Pkcs11Signer<'_> {
+    fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>, ::signature::Error> {
+        self.session
+            .sign(&self.mechanism, self.key, msg)
+            .map_err(::signature::Error::from_source)
+    }
+}
+
+/// A verifier backed by a public or secret key in a token
+#[derive(Debug)]
+pub struct Pkcs11Verifier<'a> {
+    session: &'a Session,
+    mechanism: Mechanism<'a>,
+    key: ObjectHandle,
+}
+
+impl<'a> Pkcs11Verifier<'a> {
+    /// Verify with `key`, through `mechanism`, in `session`
+    pub fn new(session: &'a Session, mechanism: Mechanism<'a>, key: ObjectHandle) -> Self {
+        Pkcs11Verifier {
+            session,
+            mechanism,
+            key,
+        }
+    }
+}
+
+impl ::signature::Verifier<Vec<u8>> for Pkcs11Verifier<'_> {
+    fn verify(&self, msg: &[u8], signature: &Vec<u8>) -> Result<(), ::signature::Error> {
+        self.session
+            .verify(&self.mechanism, self.key, msg, signature)
+            .map_err(::signature::Error::from_source)
+    }
+}
@@ -170,7 +170,9 @@ impl std::fmt::Display for Ulong {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+// Same layout as CK_VERSION, so that tokens can write to it
+#[repr(C)]
 /// Represents a version
 pub struct Version {
     major: CK_BYTE,
@@ -3,23 +3,41 @@
 mod common;
 
 use crate::common::{get_pkcs11, SO_PIN, USER_PIN};
-use common::init_pins;
+use common::{init_pins, require_mechanisms};
 use cryptoki::context::Function;
 use cryptoki::error::{Error, RvError};
-use cryptoki::mechanism::aead::GcmParams;
-use cryptoki::mechanism::rsa::{PkcsMgfType, PkcsOaepParams, PkcsOaepSource};
-use cryptoki::mechanism::{Mechanism, MechanismType};
+use cryptoki::mechanism::aead::{CcmParams, GcmMessageParams, GcmParams, GeneratorFunction};
+use cryptoki::mechanism::aes::{AesCtrParams, AesXtsParams};
+use cryptoki::mechanism::camellia::CamelliaCtrParams;
+use cryptoki::mechanism::chacha::{ChaCha20Params, ChaCha20Poly1305Params};
+use cryptoki::mechanism::dh::{DhPkcsDeriveParams, DhPkcsParams, X942Dh1DeriveParams, X942Kdf};
+use cryptoki::mechanism::elliptic_curve::{EcKdf, EcdhAesKeyWrapParams};
+use cryptoki::mechanism::gost::GostR3410ParamSet;
+use cryptoki::mechanism::ml_dsa::MlDsaParameterSet;
+use cryptoki::mechanism::ml_kem::MlKemParameterSet;
+use cryptoki::mechanism::rsa::{
+    PkcsMgfType, PkcsOaepParams, PkcsOaepSource, PkcsPssParams, RsaAesKeyWrapParams,
+};
+use cryptoki::mechanism::simple_kdf::{ExtractParams, KeyDerivationStringData};
+use cryptoki::mechanism::tls::{Tls12KeyMaterialParams, Tls12MasterKeyDeriveParams, TlsRandomData};
+use cryptoki::mechanism::vendor_defined::VendorDefinedMechanism;
+use cryptoki::mechanism::{Mechanism, MechanismType, MessageParam};
 use cryptoki::object::{
-    Attribute, AttributeInfo, AttributeType, KeyType, ObjectClass, ObjectHandle,
+    Attribute, AttributeInfo, AttributeType, AttributeValue, KeyType, ObjectClass, ObjectHandle,
 };
 use cryptoki::session::{SessionState, UserType};
-use cryptoki::types::AuthPin;
+use cryptoki::types::{AuthPin, RawAuthPin, Version};
 use serial_test::serial;
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::thread;
 
-use cryptoki::mechanism::ekdf::AesCbcDeriveParams;
+use cryptoki::mechanism::ekdf::{AesCbcDeriveParams, AesEcbDeriveParams};
+use cryptoki::mechanism::hkdf::{HkdfParams, HkdfSalt};
+use cryptoki::mechanism::kbkdf::{
+    DerivedKeys, DkmLengthMethod, KbkdfCounterFormat, KbkdfDkmLengthFormat, KbkdfFeedbackParams,
+    KbkdfParams, PrfDataParam, PrfDataParamType,
+};
 use testresult::TestResult;
 
 #[test]
@@ -70,6 +88,245 @@ fn sign_verify() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn sign_verify_multipart() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [
+        Attribute::Token(true),
+        Attribute::Private(false),
+        Attribute::PublicExponent(vec![0x01, 0x00, 0x01]),
+        Attribute::ModulusBits(2048.into()),
+        Attribute::Verify(true),
+    ];
+    let priv_key_template = [Attribute::Token(true), Attribute::Sign(true)];
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::RsaPkcsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let data = [0xFF, 0x55, 0xDD, 0x11, 0xAA, 0x33];
+    session.sign_init(&Mechanism::Sha256RsaPkcs, private)?;
+    for part in data.chunks(4) {
+        session.sign_update(part)?;
+    }
+    let signature = session.sign_final()?;
+
+    session.verify(&Mechanism::Sha256RsaPkcs, public, &data, &signature)?;
+
+    session.verify_init(&Mechanism::Sha256RsaPkcs, public)?;
+    for part in data.chunks(4) {
+        session.verify_update(part)?;
+    }
+    session.verify_final(&signature)?;
+
+    session.verify_init(&Mechanism::Sha256RsaPkcs, public)?;
+    session.verify_update(&data[1..])?;
+    let res = session.verify_final(&signature);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::SignatureInvalid,
+            Function::VerifyFinal
+        ))
+    ));
+
+    session.verify_init(&Mechanism::Sha256RsaPkcs, public)?;
+    session.verify_update(&data)?;
+    let res = session.verify_final(&signature[1..]);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::SignatureLenRange,
+            Function::VerifyFinal
+        ))
+    ));
+
+    // a session runs a single operation at a time
+    session.verify_init(&Mechanism::Sha256RsaPkcs, public)?;
+    let res = session.sign_init(&Mechanism::Sha256RsaPkcs, private);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(RvError::OperationActive, Function::SignInit))
+    ));
+    session.verify_update(&data)?;
+    session.verify_final(&signature)?;
+
+    // the operation is over once the signature has been produced
+    let res = session.sign_update(&data);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationNotInitialized,
+            Function::SignUpdate
+        ))
+    ));
+
+    session.destroy_object(public)?;
+    session.destroy_object(private)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_verify_streaming() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let mut session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [
+        Attribute::PublicExponent(vec![0x01, 0x00, 0x01]),
+        Attribute::ModulusBits(2048.into()),
+        Attribute::Verify(true),
+    ];
+    let priv_key_template = [Attribute::Sign(true)];
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::RsaPkcsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let data = [0x5a; 1000];
+    let mut signer = session.sign_streaming(&Mechanism::Sha256RsaPkcs, private)?;
+    for part in data.chunks(64) {
+        signer.update(part)?;
+    }
+    let signature = signer.finalize()?;
+    assert_eq!(
+        signature,
+        session.sign(&Mechanism::Sha256RsaPkcs, private, &data)?
+    );
+
+    let mut verifier = session.verify_streaming(&Mechanism::Sha256RsaPkcs, public)?;
+    for part in data.chunks(64) {
+        verifier.update(part)?;
+    }
+    verifier.finalize(&signature)?;
+
+    let mut verifier = session.verify_streaming(&Mechanism::Sha256RsaPkcs, public)?;
+    verifier.update(&data[1..])?;
+    let res = verifier.finalize(&signature);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::SignatureInvalid,
+            Function::VerifyFinal
+        ))
+    ));
+
+    // dropping unfinished operations terminates them
+    let mut signer = session.sign_streaming(&Mechanism::Sha256RsaPkcs, private)?;
+    signer.update(&data)?;
+    drop(signer);
+    let mut verifier = session.verify_streaming(&Mechanism::Sha256RsaPkcs, public)?;
+    verifier.update(&data)?;
+    drop(verifier);
+    session.verify(&Mechanism::Sha256RsaPkcs, public, &data, &signature)?;
+
+    session.destroy_object(public)?;
+    session.destroy_object(private)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_verify_rsa_pkcs_pss() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [
+        Attribute::PublicExponent(vec![0x01, 0x00, 0x01]),
+        Attribute::ModulusBits(2048.into()),
+        Attribute::Verify(true),
+    ];
+    let priv_key_template = [Attribute::Sign(true)];
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::RsaPkcsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let data = [0xFF, 0x55, 0xDD];
+    let mechanisms = [
+        Mechanism::sha256_rsa_pkcs_pss(32)?,
+        Mechanism::sha384_rsa_pkcs_pss(48)?,
+        Mechanism::sha512_rsa_pkcs_pss(0)?,
+    ];
+    for mechanism in &mechanisms {
+        let signature = session.sign(mechanism, private, &data)?;
+        session.verify(mechanism, public, &data, &signature)?;
+    }
+
+    // PSS over an externally computed digest
+    let digest = session.digest(&Mechanism::Sha256, &data)?;
+    let mechanism = Mechanism::RsaPkcsPss(PkcsPssParams::new(
+        MechanismType::SHA256,
+        PkcsMgfType::MGF1_SHA256,
+        32,
+    )?);
+    let signature = session.sign(&mechanism, private, &digest)?;
+    session.verify(
+        &Mechanism::sha256_rsa_pkcs_pss(32)?,
+        public,
+        &data,
+        &signature,
+    )?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_multipart_matches_single_part() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let hmac_key = session.generate_key(
+        &Mechanism::GenericSecretKeyGen,
+        &[
+            Attribute::Sign(true),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::ValueLen(32.into()),
+        ],
+    )?;
+    let aes_key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[Attribute::Sign(true), Attribute::ValueLen(16.into())],
+    )?;
+    let (_, rsa_key) = session.generate_key_pair(
+        &Mechanism::RsaPkcsKeyPairGen,
+        &[Attribute::ModulusBits(2048.into())],
+        &[Attribute::Sign(true)],
+    )?;
+
+    // all of these mechanisms are deterministic
+    let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+    for (mechanism, key) in [
+        (Mechanism::Sha256Hmac, hmac_key),
+        (Mechanism::AesCMac, aes_key),
+        (Mechanism::Sha256RsaPkcs, rsa_key),
+    ] {
+        let expected = session.sign(&mechanism, key, &data)?;
+
+        session.sign_init(&mechanism, key)?;
+        for part in data.chunks(333) {
+            session.sign_update(part)?;
+        }
+        assert_eq!(expected, session.sign_final()?);
+    }
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn sign_verify_ed25519() -> TestResult {
@@ -109,6 +366,81 @@ fn sign_verify_ed25519() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn sign_verify_ed25519_oid() -> TestResult {
+    use cryptoki::mechanism::eddsa::ED25519_OID;
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [
+        Attribute::Verify(true),
+        Attribute::EcParams(ED25519_OID.to_vec()),
+    ];
+    let priv_key_template = [Attribute::Sign(true)];
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::EccEdwardsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let attrs = session.get_attributes(public, &[AttributeType::KeyType])?;
+    assert!(matches!(
+        attrs[..],
+        [Attribute::KeyType(KeyType::EC_EDWARDS)]
+    ));
+
+    let data = [0xFF, 0x55, 0xDD];
+    let signature = session.sign(&Mechanism::Eddsa, private, &data)?;
+    assert_eq!(signature.len(), 64);
+    session.verify(&Mechanism::Eddsa, public, &data, &signature)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_verify_ed448_with_context() -> TestResult {
+    use cryptoki::mechanism::eddsa::EddsaParams;
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [
+        Attribute::Verify(true),
+        // Ed448 as a PrintableString, the way SoftHSM identifies it
+        Attribute::EcParams(vec![
+            0x13, 0x0a, 0x65, 0x64, 0x77, 0x61, 0x72, 0x64, 0x73, 0x34, 0x34, 0x38,
+        ]),
+    ];
+    let priv_key_template = [Attribute::Sign(true)];
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::EccEdwardsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let data = [0xFF, 0x55, 0xDD];
+    let params = EddsaParams::new(false, Some(b"my context"))?;
+    assert!(!params.ph_flag());
+    assert_eq!(params.context(), Some(&b"my context"[..]));
+
+    let mechanism = Mechanism::EddsaWithParams(params);
+    let signature = session.sign(&mechanism, private, &data)?;
+    session.verify(&mechanism, public, &data, &signature)?;
+
+    // the context is part of what is signed
+    let other_context = Mechanism::EddsaWithParams(EddsaParams::new(false, Some(b"other"))?);
+    assert!(session
+        .verify(&other_context, public, &data, &signature)
+        .is_err());
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn encrypt_decrypt() -> TestResult {
@@ -161,6 +493,39 @@ fn encrypt_decrypt() -> TestResult {
     Ok(())
 }
 
+#[cfg(feature = "signature-traits")]
+#[test]
+#[serial]
+fn signature_traits_ecdsa() -> TestResult {
+    use cryptoki::signer::{Pkcs11Signer, Pkcs11Verifier};
+    use signature::{Signer, Verifier};
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let secp256r1_oid: Vec<u8> = vec![0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::EccKeyPairGen,
+        &[Attribute::EcParams(secp256r1_oid), Attribute::Verify(true)],
+        &[Attribute::Sign(true)],
+    )?;
+
+    let signer = Pkcs11Signer::new(&session, Mechanism::EcdsaSha256, private);
+    let verifier = Pkcs11Verifier::new(&session, Mechanism::EcdsaSha256, public);
+
+    let signature: Vec<u8> = signer.try_sign(b"message")?;
+    assert_eq!(signature.len(), 64);
+    verifier.verify(b"message", &signature)?;
+    assert!(verifier.verify(b"other message", &signature).is_err());
+
+    // signing with the public key fails in the token
+    let signer = Pkcs11Signer::new(&session, Mechanism::EcdsaSha256, public);
+    assert!(signer.try_sign(b"message").is_err());
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn derive_key() -> TestResult {
@@ -247,57 +612,576 @@ fn derive_key() -> TestResult {
 
 #[test]
 #[serial]
-fn import_export() -> TestResult {
-    let (pkcs11, slot) = init_pins();
+fn derive_key_sha256_kdf() -> TestResult {
+    use cryptoki::mechanism::elliptic_curve::*;
 
-    // open a session
+    let (pkcs11, slot) = init_pins();
     let session = pkcs11.open_rw_session(slot)?;
-
-    // log in the session
     session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    let public_exponent: Vec<u8> = vec![0x01, 0x00, 0x01];
-    let modulus = vec![0xFF; 1024];
-
-    let template = vec![
-        Attribute::Token(true),
-        Attribute::Private(false),
-        Attribute::PublicExponent(public_exponent),
-        Attribute::Modulus(modulus.clone()),
-        Attribute::Class(ObjectClass::PUBLIC_KEY),
-        Attribute::KeyType(KeyType::RSA),
-        Attribute::Verify(true),
+    let secp256r1_oid: Vec<u8> = vec![0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+    let pub_key_template = [
+        Attribute::Derive(true),
+        Attribute::KeyType(KeyType::EC),
+        Attribute::EcParams(secp256r1_oid),
+    ];
+    let priv_key_template = [Attribute::Derive(true)];
+    let secret_template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+        Attribute::ValueLen(32.into()),
     ];
 
-    {
-        // Intentionally forget the object handle to find it later
-        let _public_key = session.create_object(&template)?;
+    let mut values = Vec::new();
+    let alice = session.generate_key_pair(
+        &Mechanism::EccKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+    let bob = session.generate_key_pair(
+        &Mechanism::EccKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+    for ((_, private), (public, _)) in [(alice, bob), (bob, alice)] {
+        let ec_point = match session.get_attributes(public, &[AttributeType::EcPoint])?[..] {
+            [Attribute::EcPoint(ref point)] => point.clone(),
+            _ => panic!("Expected EC point attribute."),
+        };
+        let kdf = EcKdf::sha256(Some(b"shared info"));
+        let params = Ecdh1DeriveParams::new(kdf, &ec_point);
+        let secret =
+            session.derive_key(&Mechanism::Ecdh1Derive(params), private, &secret_template)?;
+        match session.get_attributes(secret, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => values.push(value.clone()),
+            _ => panic!("Expected value attribute."),
+        }
     }
 
-    let is_it_the_public_key = session.find_objects(&template)?.remove(0);
+    assert_eq!(values[0].len(), 32);
+    assert_eq!(values[0], values[1]);
 
-    let attribute_info = session
-        .get_attribute_info(is_it_the_public_key, &[AttributeType::Modulus])?
-        .remove(0);
+    Ok(())
+}
 
-    if let AttributeInfo::Available(size) = attribute_info {
-        assert_eq!(size, 1024);
-    } else {
-        panic!("The Modulus attribute was expected to be present.")
-    };
+#[test]
+#[serial]
+fn derive_key_x25519() -> TestResult {
+    use cryptoki::mechanism::elliptic_curve::*;
 
-    let attr = session
-        .get_attributes(is_it_the_public_key, &[AttributeType::Modulus])?
-        .remove(0);
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::ECC_MONTGOMERY_KEY_PAIR_GEN]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    if let Attribute::Modulus(modulus_cmp) = attr {
-        assert_eq!(modulus[..], modulus_cmp[..]);
-    } else {
-        panic!("Expected the Modulus attribute.");
+    let pub_key_template = [
+        Attribute::Derive(true),
+        Attribute::EcParams(X25519_OID.to_vec()),
+    ];
+    let priv_key_template = [Attribute::Derive(true)];
+    let secret_template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+        Attribute::ValueLen(32.into()),
+    ];
+
+    let mut values = Vec::new();
+    let alice = session.generate_key_pair(
+        &Mechanism::EccMontgomeryKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+    let bob = session.generate_key_pair(
+        &Mechanism::EccMontgomeryKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+    let attrs = session.get_attributes(alice.0, &[AttributeType::KeyType])?;
+    assert!(matches!(
+        attrs[..],
+        [Attribute::KeyType(KeyType::EC_MONTGOMERY)]
+    ));
+    for ((_, private), (public, _)) in [(alice, bob), (bob, alice)] {
+        let ec_point = match session.get_attributes(public, &[AttributeType::EcPoint])?[..] {
+            [Attribute::EcPoint(ref point)] => point.clone(),
+            _ => panic!("Expected EC point attribute."),
+        };
+        let params = Ecdh1DeriveParams::new(EcKdf::null(), &ec_point);
+        let secret =
+            session.derive_key(&Mechanism::Ecdh1Derive(params), private, &secret_template)?;
+        match session.get_attributes(secret, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => values.push(value.clone()),
+            _ => panic!("Expected value attribute."),
+        }
     }
 
-    // delete key
-    session.destroy_object(is_it_the_public_key)?;
+    assert_eq!(values[0].len(), 32);
+    assert_eq!(values[0], values[1]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn derive_key_x25519_known_answer() -> TestResult {
+    use cryptoki::mechanism::elliptic_curve::*;
+
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::ECC_MONTGOMERY_KEY_PAIR_GEN]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // Test vector of RFC 7748, section 6.1
+    let alice_private = session.create_object(&[
+        Attribute::Class(ObjectClass::PRIVATE_KEY),
+        Attribute::KeyType(KeyType::EC_MONTGOMERY),
+        Attribute::EcParams(X25519_OID.to_vec()),
+        Attribute::Value(hex::decode(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        )?),
+        Attribute::Derive(true),
+    ])?;
+    // The raw 32-byte public value, not a DER OCTET STRING
+    let bob_public =
+        hex::decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")?;
+
+    let params = Ecdh1DeriveParams::new(EcKdf::null(), &bob_public);
+    let secret = session.derive_key(
+        &Mechanism::Ecdh1Derive(params),
+        alice_private,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+            Attribute::ValueLen(32.into()),
+        ],
+    )?;
+    let value = session.get_attributes(secret, &[AttributeType::Value])?;
+    let expected = hex::decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")?;
+    assert_eq!(value, [Attribute::Value(expected)]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_verify_dsa() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let params = session.generate_dsa_params(1024)?;
+    assert_eq!(params.prime().len(), 128);
+    assert_eq!(params.subprime().len(), 20);
+
+    let mut pub_key_template = vec![Attribute::Verify(true)];
+    pub_key_template.extend(params.attributes());
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::DsaKeyPairGen,
+        &pub_key_template,
+        &[Attribute::Sign(true)],
+    )?;
+
+    let data = [0xFF, 0x55, 0xDD];
+    let signature = session.sign(&Mechanism::DsaSha1, private, &data)?;
+    // r and s, of the size of the subprime each
+    assert_eq!(signature.len(), 40);
+    session.verify(&Mechanism::DsaSha1, public, &data, &signature)?;
+    assert!(session
+        .verify(&Mechanism::DsaSha1, public, &[0xFF, 0x55], &signature)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn derive_key_dh_pkcs() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // 2048-bit MODP group of RFC 3526
+    let prime = hex::decode(concat!(
+        "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+        "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+        "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+        "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+        "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+        "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+        "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+        "3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF",
+    ))?;
+    let params = DhPkcsParams::new(&prime, &[2]);
+
+    let mut pub_key_template = vec![Attribute::Token(false)];
+    pub_key_template.extend(params.attributes());
+    let priv_key_template = [Attribute::Token(false), Attribute::Derive(true)];
+    let alice = session.generate_key_pair(
+        &Mechanism::DhPkcsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+    let bob = session.generate_key_pair(
+        &Mechanism::DhPkcsKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let secret_template = [
+        Attribute::Token(false),
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+        Attribute::ValueLen(32.into()),
+    ];
+    let mut values = Vec::new();
+    for ((_, private), (public, _)) in [(alice, bob), (bob, alice)] {
+        let public_value = match session.get_attributes(public, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => value.clone(),
+            _ => panic!("Expected value attribute."),
+        };
+        let params = DhPkcsDeriveParams::new(&public_value);
+        let secret =
+            session.derive_key(&Mechanism::DhPkcsDerive(params), private, &secret_template)?;
+        match session.get_attributes(secret, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => values.push(value.clone()),
+            _ => panic!("Expected value attribute."),
+        }
+    }
+    assert_eq!(values[0], values[1]);
+    assert_eq!(values[0].len(), 32);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_verify_dsa_2048() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let params = session.generate_dsa_params(2048)?;
+    assert_eq!(params.prime().len(), 256);
+
+    let mut pub_key_template = vec![Attribute::Token(false), Attribute::Verify(true)];
+    pub_key_template.extend(params.attributes());
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::DsaKeyPairGen,
+        &pub_key_template,
+        &[Attribute::Token(false), Attribute::Sign(true)],
+    )?;
+
+    // The private key shares the domain parameters of the public key
+    let attributes = session.get_attributes(
+        private,
+        &[
+            AttributeType::KeyType,
+            AttributeType::Prime,
+            AttributeType::Subprime,
+            AttributeType::Base,
+        ],
+    )?;
+    let mut expected = vec![Attribute::KeyType(KeyType::DSA)];
+    expected.extend(params.attributes());
+    assert_eq!(attributes, expected);
+
+    let data = [0x42; 100];
+    let signature = session.sign(&Mechanism::DsaSha256, private, &data)?;
+    assert_eq!(signature.len(), 2 * params.subprime().len());
+    session.verify(&Mechanism::DsaSha256, public, &data, &signature)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sign_verify_ml_dsa() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::ML_DSA]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::MlDsaKeyPairGen,
+        &[
+            Attribute::Token(false),
+            Attribute::Verify(true),
+            MlDsaParameterSet::MlDsa65.into(),
+        ],
+        &[Attribute::Token(false), Attribute::Sign(true)],
+    )?;
+
+    let attributes = session.get_attributes(
+        private,
+        &[AttributeType::KeyType, AttributeType::ParameterSet],
+    )?;
+    assert_eq!(
+        attributes,
+        [
+            Attribute::KeyType(KeyType::ML_DSA),
+            MlDsaParameterSet::MlDsa65.into()
+        ]
+    );
+
+    let data = [0x42; 100];
+    let signature = session.sign(&Mechanism::MlDsa, private, &data)?;
+    // ML-DSA-65 signatures have a fixed length
+    assert_eq!(signature.len(), 3309);
+    session.verify(&Mechanism::MlDsa, public, &data, &signature)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn encapsulate_key_ml_kem() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::ML_KEM]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::MlKemKeyPairGen,
+        &[Attribute::Token(false), MlKemParameterSet::MlKem768.into()],
+        &[Attribute::Token(false)],
+    )?;
+
+    let template = [
+        Attribute::Token(false),
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+    ];
+    let (ciphertext, encapsulated) =
+        session.encapsulate_key(&Mechanism::MlKem, public, &template)?;
+    // ML-KEM-768 ciphertexts have a fixed length
+    assert_eq!(ciphertext.len(), 1088);
+    let decapsulated =
+        session.decapsulate_key(&Mechanism::MlKem, private, &ciphertext, &template)?;
+
+    let value = |key| -> TestResult<Vec<u8>> {
+        match session.get_attributes(key, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => Ok(value.clone()),
+            _ => panic!("Expected value attribute."),
+        }
+    };
+    let shared_secret = value(encapsulated)?;
+    assert_eq!(shared_secret.len(), 32);
+    assert_eq!(shared_secret, value(decapsulated)?);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn vendor_defined_attribute() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // SoftHSM does not define any vendor attribute
+    let result = session.create_object(&[
+        Attribute::Class(ObjectClass::DATA),
+        Attribute::Token(false),
+        Attribute::Value(vec![0x42; 4]),
+        Attribute::VendorDefined(AttributeType::new_vendor_defined(0x0001).into(), vec![0x01]),
+    ]);
+    assert!(matches!(
+        result,
+        Err(Error::Pkcs11(
+            RvError::AttributeTypeInvalid,
+            Function::CreateObject
+        ))
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn get_attribute_values() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::Token(false),
+            Attribute::Label(b"values".to_vec()),
+            Attribute::ValueLen(16.into()),
+            Attribute::Sensitive(true),
+        ],
+    )?;
+
+    let attributes = [
+        AttributeType::Label,
+        AttributeType::Value,
+        AttributeType::Modulus,
+        AttributeType::ValueLen,
+    ];
+    let info = session.get_attribute_info(key, &attributes)?;
+    assert_eq!(info[1], AttributeInfo::Sensitive);
+    assert_eq!(info[2], AttributeInfo::TypeInvalid);
+
+    let values = session.get_attribute_values(key, &attributes)?;
+    assert_eq!(
+        values,
+        [
+            AttributeValue::Available(Attribute::Label(b"values".to_vec())),
+            AttributeValue::Sensitive,
+            AttributeValue::TypeInvalid,
+            AttributeValue::Available(Attribute::ValueLen(16.into())),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn derive_key_x942_dh() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::X9_42_DH_PARAMETER_GEN,
+            MechanismType::X9_42_DH_DERIVE,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let domain_params = session.generate_key(
+        &Mechanism::X942DhParameterGen,
+        &[
+            Attribute::Class(ObjectClass::DOMAIN_PARAMETERS),
+            Attribute::KeyType(KeyType::X9_42_DH),
+            Attribute::Token(false),
+            Attribute::PrimeBits(2048.into()),
+        ],
+    )?;
+    let mut pub_key_template = session.get_attributes(
+        domain_params,
+        &[
+            AttributeType::Prime,
+            AttributeType::Subprime,
+            AttributeType::Base,
+        ],
+    )?;
+    pub_key_template.push(Attribute::Token(false));
+    let priv_key_template = [Attribute::Token(false), Attribute::Derive(true)];
+    let alice = session.generate_key_pair(
+        &Mechanism::X942DhKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+    let bob = session.generate_key_pair(
+        &Mechanism::X942DhKeyPairGen,
+        &pub_key_template,
+        &priv_key_template,
+    )?;
+
+    let secret_template = [
+        Attribute::Token(false),
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::ValueLen(16.into()),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+    ];
+    let mut values = Vec::new();
+    for ((_, private), (public, _)) in [(alice, bob), (bob, alice)] {
+        let public_value = match session.get_attributes(public, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => value.clone(),
+            _ => panic!("Expected value attribute."),
+        };
+        let params = X942Dh1DeriveParams::new(X942Kdf::sha1_concatenate(None), &public_value);
+        let secret =
+            session.derive_key(&Mechanism::X942DhDerive(params), private, &secret_template)?;
+        match session.get_attributes(secret, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => values.push(value.clone()),
+            _ => panic!("Expected value attribute."),
+        }
+    }
+    assert_eq!(values[0], values[1]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn import_export() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+
+    // open a session
+    let session = pkcs11.open_rw_session(slot)?;
+
+    // log in the session
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let public_exponent: Vec<u8> = vec![0x01, 0x00, 0x01];
+    let modulus = vec![0xFF; 1024];
+
+    let template = vec![
+        Attribute::Token(true),
+        Attribute::Private(false),
+        Attribute::PublicExponent(public_exponent),
+        Attribute::Modulus(modulus.clone()),
+        Attribute::Class(ObjectClass::PUBLIC_KEY),
+        Attribute::KeyType(KeyType::RSA),
+        Attribute::Verify(true),
+    ];
+
+    {
+        // Intentionally forget the object handle to find it later
+        let _public_key = session.create_object(&template)?;
+    }
+
+    let is_it_the_public_key = session.find_objects(&template)?.remove(0);
+
+    let attribute_info = session
+        .get_attribute_info(is_it_the_public_key, &[AttributeType::Modulus])?
+        .remove(0);
+
+    if let AttributeInfo::Available(size) = attribute_info {
+        assert_eq!(size, 1024);
+    } else {
+        panic!("The Modulus attribute was expected to be present.")
+    };
+
+    let attr = session
+        .get_attributes(is_it_the_public_key, &[AttributeType::Modulus])?
+        .remove(0);
+
+    if let Attribute::Modulus(modulus_cmp) = attr {
+        assert_eq!(modulus[..], modulus_cmp[..]);
+    } else {
+        panic!("Expected the Modulus attribute.");
+    }
+
+    // delete key
+    session.destroy_object(is_it_the_public_key)?;
 
     Ok(())
 }
@@ -454,6 +1338,16 @@ fn session_objecthandle_iterator() -> testresult::TestResult {
         iter2,
         Err(Error::Pkcs11(RvError::OperationActive, _))
     ));
+    drop(iter);
+
+    // stopping early finalizes the search when the iterator is dropped
+    let first_keys = session
+        .iter_objects_with_cache_size(&key_search_template, NonZeroUsize::new(2).unwrap())?
+        .take(3)
+        .collect::<cryptoki::error::Result<Vec<_>>>()?;
+    assert_eq!(first_keys.len(), 3);
+    let found_keys = session.iter_objects(&key_search_template)?.count();
+    assert_eq!(found_keys, 9);
     Ok(())
 }
 
@@ -544,13 +1438,303 @@ fn wrap_and_unwrap_key() {
 
 #[test]
 #[serial]
-fn login_feast() {
-    const SESSIONS: usize = 100;
-
+fn wrap_and_unwrap_key_aes_key_wrap() -> TestResult {
     let (pkcs11, slot) = init_pins();
-    let mut threads = Vec::new();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    for _ in 0..SESSIONS {
+    let wrapping_key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(16.into()),
+            Attribute::Wrap(true),
+            Attribute::Unwrap(true),
+        ],
+    )?;
+    let key_to_be_wrapped = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(32.into()),
+            Attribute::Extractable(true),
+            Attribute::Encrypt(true),
+        ],
+    )?;
+    let encrypted_with_original =
+        session.encrypt(&Mechanism::AesEcb, key_to_be_wrapped, &[0x42; 16])?;
+
+    // RFC 3394 adds one 8-byte block to the wrapped key
+    let wrapped_key = session.wrap_key(&Mechanism::AesKeyWrap, wrapping_key, key_to_be_wrapped)?;
+    assert_eq!(wrapped_key.len(), 40);
+
+    let unwrapped_key = session.unwrap_key(
+        &Mechanism::AesKeyWrap,
+        wrapping_key,
+        &wrapped_key,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::AES),
+            Attribute::Encrypt(true),
+        ],
+    )?;
+    let encrypted_with_unwrapped =
+        session.encrypt(&Mechanism::AesEcb, unwrapped_key, &[0x42; 16])?;
+    assert_eq!(encrypted_with_original, encrypted_with_unwrapped);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn wrap_and_unwrap_key_rsa_aes_key_wrap() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let (wrapping_key, unwrapping_key) = session.generate_key_pair(
+        &Mechanism::RsaPkcsKeyPairGen,
+        &[
+            Attribute::PublicExponent(vec![0x01, 0x00, 0x01]),
+            Attribute::ModulusBits(2048.into()),
+            Attribute::Wrap(true),
+        ],
+        &[Attribute::Unwrap(true)],
+    )?;
+    let (public_key, key_to_be_wrapped) = session.generate_key_pair(
+        &Mechanism::RsaPkcsKeyPairGen,
+        &[
+            Attribute::PublicExponent(vec![0x01, 0x00, 0x01]),
+            Attribute::ModulusBits(2048.into()),
+            Attribute::Verify(true),
+        ],
+        &[
+            Attribute::Sign(true),
+            // the key needs to be extractable to be suitable for being wrapped
+            Attribute::Extractable(true),
+        ],
+    )?;
+
+    let oaep = PkcsOaepParams::new(
+        MechanismType::SHA1,
+        PkcsMgfType::MGF1_SHA1,
+        PkcsOaepSource::empty(),
+    );
+    let mechanism = Mechanism::RsaAesKeyWrap(RsaAesKeyWrapParams::new(256, &oaep)?);
+
+    let wrapped_key = session.wrap_key(&mechanism, wrapping_key, key_to_be_wrapped)?;
+    let unwrapped_key = session.unwrap_key(
+        &mechanism,
+        unwrapping_key,
+        &wrapped_key,
+        &[
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::KeyType(KeyType::RSA),
+            Attribute::Sign(true),
+        ],
+    )?;
+
+    // RSA PKCS #1 v1.5 signatures are deterministic
+    let data = [0xFF, 0x55, 0xDD];
+    let signature = session.sign(&Mechanism::Sha256RsaPkcs, key_to_be_wrapped, &data)?;
+    let signature_with_unwrapped = session.sign(&Mechanism::Sha256RsaPkcs, unwrapped_key, &data)?;
+    assert_eq!(signature, signature_with_unwrapped);
+    session.verify(
+        &Mechanism::Sha256RsaPkcs,
+        public_key,
+        &data,
+        &signature_with_unwrapped,
+    )?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn wrap_and_unwrap_key_ecdh_aes_key_wrap() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::ECDH_AES_KEY_WRAP]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let secp256r1_oid: Vec<u8> = vec![0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+    let (wrapping_key, unwrapping_key) = session.generate_key_pair(
+        &Mechanism::EccKeyPairGen,
+        &[Attribute::EcParams(secp256r1_oid), Attribute::Wrap(true)],
+        &[Attribute::Unwrap(true)],
+    )?;
+    let key_to_be_wrapped = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(32.into()),
+            Attribute::Extractable(true),
+            Attribute::Encrypt(true),
+        ],
+    )?;
+    let encrypted_with_original =
+        session.encrypt(&Mechanism::AesEcb, key_to_be_wrapped, &[0x42; 16])?;
+
+    let shared_data = [0x5a; 16];
+    let mechanism = Mechanism::EcdhAesKeyWrap(EcdhAesKeyWrapParams::new(
+        256,
+        EcKdf::sha256(Some(&shared_data)),
+    )?);
+    let wrapped_key = session.wrap_key(&mechanism, wrapping_key, key_to_be_wrapped)?;
+    let unwrapped_key = session.unwrap_key(
+        &mechanism,
+        unwrapping_key,
+        &wrapped_key,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::AES),
+            Attribute::Encrypt(true),
+        ],
+    )?;
+    let encrypted_with_unwrapped =
+        session.encrypt(&Mechanism::AesEcb, unwrapped_key, &[0x42; 16])?;
+    assert_eq!(encrypted_with_original, encrypted_with_unwrapped);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_key_wrap_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // RFC 3394 section 4.1: wrap 128 bits of key data with a 128-bit KEK
+    let wrapping_key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(hex::decode("000102030405060708090A0B0C0D0E0F")?),
+        Attribute::Wrap(true),
+        Attribute::Unwrap(true),
+    ])?;
+    let key_to_be_wrapped = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(hex::decode("00112233445566778899AABBCCDDEEFF")?),
+        Attribute::Extractable(true),
+    ])?;
+
+    let wrapped_key = session.wrap_key(&Mechanism::AesKeyWrap, wrapping_key, key_to_be_wrapped)?;
+    assert_eq!(
+        wrapped_key,
+        hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5")?
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_key_wrap_with_padding() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let wrapping_key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value((0..16).collect()),
+        Attribute::Wrap(true),
+        Attribute::Unwrap(true),
+    ])?;
+    // 25 bytes, not a multiple of the AES key wrap block size
+    let secret = vec![0x11; 25];
+    let key_to_be_wrapped = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Value(secret.clone()),
+        Attribute::Extractable(true),
+    ])?;
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+    ];
+
+    let mut mechanisms = vec![Mechanism::AesKeyWrapPad];
+    if pkcs11
+        .get_mechanism_list(slot)?
+        .contains(&MechanismType::AES_KEY_WRAP_KWP)
+    {
+        mechanisms.push(Mechanism::AesKeyWrapKwp(None));
+    }
+    for mechanism in mechanisms {
+        let wrapped_key = session.wrap_key(&mechanism, wrapping_key, key_to_be_wrapped)?;
+        if mechanism.mechanism_type() == MechanismType::AES_KEY_WRAP_KWP {
+            // RFC 5649 with the default initial value
+            let expected = hex::decode(
+                "273b1670eb478716bee2ce8e0b302585f0337d16e1c875ab614ce3a4ad6d6e0e6d494552994dbd1f",
+            )?;
+            assert_eq!(wrapped_key, expected);
+        }
+        let unwrapped_key =
+            session.unwrap_key(&mechanism, wrapping_key, &wrapped_key, &template)?;
+        let value = session.get_attributes(unwrapped_key, &[AttributeType::Value])?;
+        assert_eq!(value, [Attribute::Value(secret.clone())]);
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+#[ignore = "SoftHSM does not accept an initial value for AES key wrap"]
+fn aes_key_wrap_with_iv() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let wrapping_key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(16.into()),
+            Attribute::Wrap(true),
+            Attribute::Unwrap(true),
+        ],
+    )?;
+    let key_to_be_wrapped = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[Attribute::ValueLen(16.into()), Attribute::Extractable(true)],
+    )?;
+
+    // Passing the default initial value of RFC 3394 explicitly gives the
+    // same result as passing none
+    let default_iv = Mechanism::AesKeyWrapWithIv([0xa6; 8]);
+    let wrapped_with_iv = session.wrap_key(&default_iv, wrapping_key, key_to_be_wrapped)?;
+    let wrapped = session.wrap_key(&Mechanism::AesKeyWrap, wrapping_key, key_to_be_wrapped)?;
+    assert_eq!(wrapped, wrapped_with_iv);
+
+    // A key wrapped with another initial value only unwraps with that value
+    let iv = Mechanism::AesKeyWrapWithIv([1, 2, 3, 4, 5, 6, 7, 8]);
+    let wrapped_with_iv = session.wrap_key(&iv, wrapping_key, key_to_be_wrapped)?;
+    assert_ne!(wrapped, wrapped_with_iv);
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+    ];
+    session.unwrap_key(&iv, wrapping_key, &wrapped_with_iv, &template)?;
+    assert!(session
+        .unwrap_key(&default_iv, wrapping_key, &wrapped_with_iv, &template)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn login_feast() {
+    const SESSIONS: usize = 100;
+
+    let (pkcs11, slot) = init_pins();
+    let mut threads = Vec::new();
+
+    for _ in 0..SESSIONS {
         let pkcs11 = pkcs11.clone();
         threads.push(thread::spawn(move || {
             let session = pkcs11.open_rw_session(slot).unwrap();
@@ -586,6 +1770,118 @@ fn login_feast() {
     }
 }
 
+#[test]
+#[serial]
+fn login_scoped() -> TestResult {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    let pin = AuthPin::new(USER_PIN.into());
+
+    {
+        let guard = session.login_scoped(UserType::User, Some(&pin))?;
+        assert_eq!(
+            guard.get_session_info()?.session_state(),
+            SessionState::RwUser
+        );
+    }
+    assert_eq!(
+        session.get_session_info()?.session_state(),
+        SessionState::RwPublic
+    );
+
+    // the session is logged out while unwinding too
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = session.login_scoped(UserType::User, Some(&pin)).unwrap();
+        panic!("error while logged in");
+    }));
+    assert!(res.is_err());
+    assert_eq!(
+        session.get_session_info()?.session_state(),
+        SessionState::RwPublic
+    );
+
+    // an explicit logout reports errors, and does not log out again on drop
+    let guard = session.login_scoped(UserType::User, Some(&pin))?;
+    guard.logout()?;
+    let res = session.logout();
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(RvError::UserNotLoggedIn, Function::Logout))
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn session_pool() -> TestResult {
+    use cryptoki::session::SessionPool;
+    use std::sync::Arc;
+
+    let (pkcs11, slot) = init_pins();
+
+    assert!(matches!(
+        SessionPool::new(pkcs11.clone(), slot, true).with_idle_sessions(4, 2),
+        Err(Error::InvalidValue)
+    ));
+
+    let pool = Arc::new(
+        SessionPool::new(pkcs11, slot, true)
+            .with_login(UserType::User, AuthPin::new(USER_PIN.into()))
+            .with_idle_sessions(2, 4)?,
+    );
+    pool.fill()?;
+    assert_eq!(pool.idle_sessions(), 2);
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || -> TestResult {
+                let session = pool.get()?;
+                assert_eq!(
+                    session.get_session_info()?.session_state(),
+                    SessionState::RwUser
+                );
+                let _ = session.generate_random_vec(16)?;
+                Ok(())
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap()?;
+    }
+    // no more than the maximum number of idle sessions is kept
+    assert!(pool.idle_sessions() <= 4);
+
+    // sessions are logged in again after a logout
+    pool.get()?.logout()?;
+    let session = pool.get()?;
+    assert_eq!(
+        session.get_session_info()?.session_state(),
+        SessionState::RwUser
+    );
+
+    drop(session);
+
+    // operations left active are terminated before the session is given back
+    let session = pool.get()?;
+    session.digest_init(&Mechanism::Sha256)?;
+    session.digest_update(&[0; 16])?;
+    drop(session);
+    let session = pool.get()?;
+    session.digest_init(&Mechanism::Sha256)?;
+    let _ = session.digest_final()?;
+
+    // discarded sessions are not given back
+    let idle = pool.idle_sessions();
+    session.discard();
+    assert_eq!(pool.idle_sessions(), idle);
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn get_info_test() -> TestResult {
@@ -610,6 +1906,19 @@ fn get_slot_info_test() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn get_mechanism_info_test() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let mechanisms = pkcs11.get_mechanism_list(slot)?;
+    assert!(!mechanisms.is_empty());
+    // Every listed mechanism, known to this crate or not, can be queried
+    for mechanism in mechanisms {
+        let _ = pkcs11.get_mechanism_info(slot, mechanism)?;
+    }
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn get_session_info_test() -> TestResult {
@@ -707,6 +2016,45 @@ fn set_pin_test() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn raw_pins() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+
+    // A PIN which is not valid UTF-8
+    let raw_pin = RawAuthPin::new(vec![0xff, 0xfe, 0x01, 0x02, 0x03, 0x04]);
+    session.login(UserType::So, Some(&AuthPin::new(SO_PIN.into())))?;
+    session.init_pin_with_raw(&raw_pin)?;
+    session.logout()?;
+
+    let new_raw_pin = RawAuthPin::new(vec![0x80; 8]);
+    session.login_with_raw(UserType::User, &raw_pin)?;
+    session.set_pin_with_raw(&raw_pin, &new_raw_pin)?;
+    session.logout()?;
+    session.login_with_raw(UserType::User, &new_raw_pin)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn login_with_protected_path() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+
+    // SoftHSM has no protected authentication path
+    assert!(!pkcs11.get_token_info(slot)?.protected_authentication_path());
+    let res = session.login_with_protected_path(UserType::User);
+    assert!(
+        matches!(res, Err(Error::Pkcs11(_, Function::Login))),
+        "res = {:?}",
+        res
+    );
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn get_attribute_info_test() -> TestResult {
@@ -1022,12 +2370,14 @@ fn session_copy_object() -> TestResult {
 
     // copy the object with a template
     let copy = rw_session.copy_object(object, &copy_template)?;
+    assert_ne!(copy, object);
+    let attrs = rw_session.get_attributes(copy, &[AttributeType::Label])?;
+    assert!(matches!(&attrs[..], [Attribute::Label(label)] if label == b"copy"));
     rw_session.destroy_object(copy)?;
 
     // try the copy with the insecure template. It should fail. Returning CKR_OK is considered a failure.
-    rw_session
-        .copy_object(object, &insecure_copy_template)
-        .unwrap_err();
+    let res = rw_session.copy_object(object, &insecure_copy_template);
+    assert!(matches!(res, Err(Error::Pkcs11(_, Function::CopyObject))));
 
     // delete keys
     rw_session.destroy_object(object)?;
@@ -1038,35 +2388,117 @@ fn session_copy_object() -> TestResult {
 
 #[test]
 #[serial]
-fn aes_cbc_encrypt() -> TestResult {
-    // Encrypt two blocks of zeros with AES-128-CBC, and zero IV
-    let key = vec![0; 16];
-    let iv = [0; 16];
-    let plain = [0; 32];
-    let expected_cipher = [
-        0x66, 0xe9, 0x4b, 0xd4, 0xef, 0x8a, 0x2c, 0x3b, 0x88, 0x4c, 0xfa, 0x59, 0xca, 0x34, 0x2b,
-        0x2e, 0xf7, 0x95, 0xbd, 0x4a, 0x52, 0xe2, 0x9e, 0xd7, 0x13, 0xd3, 0x13, 0xfa, 0x20, 0xe9,
-        0x8d, 0xbc,
-    ];
-
+fn copy_session_object_to_token() -> TestResult {
     let (pkcs11, slot) = init_pins();
     let session = pkcs11.open_rw_session(slot)?;
     session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    let template = [
-        Attribute::Class(ObjectClass::SECRET_KEY),
-        Attribute::KeyType(KeyType::AES),
-        Attribute::Value(key),
-        Attribute::Encrypt(true),
-    ];
-    let key_handle = session.create_object(&template)?;
-    let mechanism = Mechanism::AesCbc(iv);
-    let cipher = session.encrypt(&mechanism, key_handle, &plain)?;
-    assert_eq!(expected_cipher[..], cipher[..]);
-    Ok(())
-}
+    let object = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::Token(false),
+            Attribute::ValueLen(16.into()),
+            Attribute::Label(b"persisted".to_vec()),
+        ],
+    )?;
 
-#[test]
+    let copy = session.copy_object(object, &[Attribute::Token(true)])?;
+    assert_eq!(
+        session.get_attributes(copy, &[AttributeType::Token, AttributeType::Label])?,
+        [
+            Attribute::Token(true),
+            Attribute::Label(b"persisted".to_vec())
+        ]
+    );
+    assert_eq!(
+        session.get_attributes(object, &[AttributeType::Token])?,
+        [Attribute::Token(false)]
+    );
+
+    // The token object outlives the session
+    session.destroy_object(object)?;
+    drop(session);
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+    let found = session.find_objects(&[
+        Attribute::Token(true),
+        Attribute::Label(b"persisted".to_vec()),
+    ])?;
+    assert_eq!(found.len(), 1);
+    session.destroy_object(found[0])?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn get_object_size() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+
+    // open a session
+    let session = pkcs11.open_rw_session(slot)?;
+
+    // log in the session
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Token(false),
+        Attribute::Value(vec![0; 32]),
+    ])?;
+
+    // the size is token specific, and tokens may refuse to reveal it
+    match session.get_object_size(key) {
+        Ok(Some(size)) => assert_ne!(size, 0),
+        Ok(None) | Err(Error::Pkcs11(RvError::FunctionNotSupported, Function::GetObjectSize)) => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    session.destroy_object(key)?;
+    let res = session.get_object_size(key);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::ObjectHandleInvalid,
+            Function::GetObjectSize
+        ))
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_cbc_encrypt() -> TestResult {
+    // Encrypt two blocks of zeros with AES-128-CBC, and zero IV
+    let key = vec![0; 16];
+    let iv = [0; 16];
+    let plain = [0; 32];
+    let expected_cipher = [
+        0x66, 0xe9, 0x4b, 0xd4, 0xef, 0x8a, 0x2c, 0x3b, 0x88, 0x4c, 0xfa, 0x59, 0xca, 0x34, 0x2b,
+        0x2e, 0xf7, 0x95, 0xbd, 0x4a, 0x52, 0xe2, 0x9e, 0xd7, 0x13, 0xd3, 0x13, 0xfa, 0x20, 0xe9,
+        0x8d, 0xbc,
+    ];
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(key),
+        Attribute::Encrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::AesCbc(iv);
+    let cipher = session.encrypt(&mechanism, key_handle, &plain)?;
+    assert_eq!(expected_cipher[..], cipher[..]);
+    Ok(())
+}
+
+#[test]
 #[serial]
 fn aes_cbc_pad_encrypt() -> TestResult {
     // Encrypt two blocks of zeros with AES-128-CBC and PKCS#7 padding, and zero IV
@@ -1097,6 +2529,152 @@ fn aes_cbc_pad_encrypt() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn aes_cbc_pad_encrypt_multipart() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(vec![0x42; 16]),
+        Attribute::Encrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::AesCbcPad([0x01; 16]);
+
+    // A few MiB of data, neither the total nor the chunks being block aligned
+    let plain: Vec<u8> = (0..3 * 1024 * 1024 + 5).map(|i| i as u8).collect();
+    let expected_cipher = session.encrypt(&mechanism, key_handle, &plain)?;
+
+    let mut cipher = Vec::new();
+    session.encrypt_init(&mechanism, key_handle)?;
+    for chunk in plain.chunks(4093) {
+        cipher.extend(session.encrypt_update(chunk)?);
+    }
+    // an empty part produces no output
+    assert!(session.encrypt_update(&[])?.is_empty());
+    cipher.extend(session.encrypt_final()?);
+
+    assert_eq!(expected_cipher, cipher);
+
+    // single-part encryption is rejected while a multi-part one is active
+    session.encrypt_init(&mechanism, key_handle)?;
+    let _ = session.encrypt_update(&plain[..20])?;
+    let res = session.encrypt(&mechanism, key_handle, &plain[..16]);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationActive,
+            Function::EncryptInit
+        ))
+    ));
+    let _ = session.encrypt_final()?;
+
+    // no operation is active after the final call
+    let res = session.encrypt_update(&plain[..16]);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationNotInitialized,
+            Function::EncryptUpdate
+        ))
+    ));
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_cbc_pad_encrypt_streaming() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let mut session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(vec![0x42; 16]),
+        Attribute::Encrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::AesCbcPad([0x01; 16]);
+
+    let plain: Vec<u8> = (0..64 * 1024 + 5).map(|i| i as u8).collect();
+    let expected_cipher = session.encrypt(&mechanism, key_handle, &plain)?;
+
+    let mut encryptor = session.encrypt_streaming(&mechanism, key_handle)?;
+    let mut cipher = Vec::new();
+    for chunk in plain.chunks(4093) {
+        cipher.extend(encryptor.update(chunk)?);
+    }
+    cipher.extend(encryptor.finalize()?);
+    assert_eq!(expected_cipher, cipher);
+
+    // dropping an unfinished operation terminates it
+    let mut encryptor = session.encrypt_streaming(&mechanism, key_handle)?;
+    let _ = encryptor.update(&plain[..20])?;
+    drop(encryptor);
+    assert_eq!(
+        session.encrypt(&mechanism, key_handle, &plain)?,
+        expected_cipher
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_decrypt_multipart() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(vec![0x42; 16]),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let plain: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+
+    let iv = [0x01; 12];
+    let aad = [0x02; 7];
+    let mechanisms = [
+        Mechanism::AesCbcPad([0x01; 16]),
+        Mechanism::AesGcm(GcmParams::new(&iv, &aad, 128.into())?),
+    ];
+    for mechanism in &mechanisms {
+        let cipher = session.encrypt(mechanism, key_handle, &plain)?;
+
+        let mut decrypted = Vec::new();
+        session.decrypt_init(mechanism, key_handle)?;
+        for chunk in cipher.chunks(77) {
+            decrypted.extend(session.decrypt_update(chunk)?);
+        }
+        decrypted.extend(session.decrypt_final()?);
+        assert_eq!(plain, decrypted);
+    }
+
+    // a truncated ciphertext is only detected when finalizing
+    let mechanism = Mechanism::AesCbcPad([0x01; 16]);
+    let cipher = session.encrypt(&mechanism, key_handle, &plain)?;
+    session.decrypt_init(&mechanism, key_handle)?;
+    let _ = session.decrypt_update(&cipher[..cipher.len() - 1])?;
+    let res = session.decrypt_final();
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::EncryptedDataLenRange,
+            Function::DecryptFinal
+        ))
+    ));
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn update_attributes_key() -> TestResult {
@@ -1140,6 +2718,73 @@ fn update_attributes_key() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn update_attributes_read_only() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::Token(false),
+            Attribute::ValueLen(16.into()),
+            Attribute::Extractable(true),
+        ],
+    )?;
+
+    session.update_attributes(key, &[Attribute::Extractable(false)])?;
+    let result = session.update_attributes(key, &[Attribute::Extractable(true)]);
+    assert!(matches!(
+        result,
+        Err(Error::Pkcs11(
+            RvError::AttributeReadOnly,
+            Function::SetAttributeValue
+        ))
+    ));
+    assert_eq!(
+        session.get_attributes(key, &[AttributeType::Extractable])?,
+        [Attribute::Extractable(false)]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn vendor_defined_mechanism() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::Token(false),
+            Attribute::ValueLen(16.into()),
+            Attribute::Encrypt(true),
+        ],
+    )?;
+
+    // The mechanism reaches the token, which does not know it
+    let parameter = [0x5a; 16];
+    let mechanism = Mechanism::VendorDefined(VendorDefinedMechanism::new(
+        MechanismType::new_vendor_defined(0x17),
+        Some(&parameter),
+    ));
+    let result = session.encrypt(&mechanism, key, &[0; 16]);
+    assert!(matches!(
+        result,
+        Err(Error::Pkcs11(
+            RvError::MechanismInvalid,
+            Function::EncryptInit
+        ))
+    ));
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn sha256_digest() -> TestResult {
@@ -1167,82 +2812,590 @@ fn sha256_digest() -> TestResult {
 
 #[test]
 #[serial]
-// Currently empty AAD crashes SoftHSM, see: https://github.com/opendnssec/SoftHSMv2/issues/605
-#[ignore]
-fn aes_gcm_no_aad() -> TestResult {
-    // Encrypt two blocks of zeros with AES-128-GCM
-    let key = vec![0; 16];
-    let iv = [0; 12];
-    let aad = [];
-    let plain = [0; 32];
-    let expected_cipher_and_tag = [
-        0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92, 0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe,
-        0x78, 0xf7, 0x95, 0xaa, 0xab, 0x49, 0x4b, 0x59, 0x23, 0xf7, 0xfd, 0x89, 0xff, 0x94, 0x8b,
-        0xc1, 0xe0, 0x40, 0x49, 0x0a, 0xf4, 0x80, 0x56, 0x06, 0xb2, 0xa3, 0xa2, 0xe7, 0x93,
+fn sha3_digest_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::SHA3_224,
+            MechanismType::SHA3_256,
+            MechanismType::SHA3_384,
+            MechanismType::SHA3_512,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_ro_session(slot)?;
+
+    // "abc" test vectors from the NIST SHA-3 examples
+    let vectors = [
+        (
+            Mechanism::Sha3_224,
+            "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+        ),
+        (
+            Mechanism::Sha3_256,
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        ),
+        (
+            Mechanism::Sha3_384,
+            concat!(
+                "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c25",
+                "96da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+            ),
+        ),
+        (
+            Mechanism::Sha3_512,
+            concat!(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e",
+                "10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+            ),
+        ),
     ];
+    for (mechanism, want) in vectors {
+        let have = session.digest(&mechanism, b"abc")?;
+        assert_eq!(hex::encode(have), want);
+    }
+
+    Ok(())
+}
 
+#[test]
+#[serial]
+fn sha256_digest_multipart() -> TestResult {
     let (pkcs11, slot) = init_pins();
     let session = pkcs11.open_rw_session(slot)?;
     session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    let template = [
-        Attribute::Class(ObjectClass::SECRET_KEY),
-        Attribute::KeyType(KeyType::AES),
-        Attribute::Value(key),
-        Attribute::Encrypt(true),
-    ];
-    let key_handle = session.create_object(&template)?;
-    let mechanism = Mechanism::AesGcm(GcmParams::new(&iv, &aad, 96.into()));
-    let cipher_and_tag = session.encrypt(&mechanism, key_handle, &plain)?;
-    assert_eq!(expected_cipher_and_tag[..], cipher_and_tag[..]);
+    let data: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+    let want = session.digest(&Mechanism::Sha256, &data)?;
+
+    session.digest_init(&Mechanism::Sha256)?;
+    for part in data.chunks(999) {
+        session.digest_update(part)?;
+    }
+    let have = session.digest_final()?;
+    assert_eq!(want, have);
+
+    // only one digest operation can be active at a time
+    session.digest_init(&Mechanism::Sha256)?;
+    let res = session.digest_init(&Mechanism::Sha256);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationActive,
+            Function::DigestInit
+        ))
+    ));
+    let _ = session.digest_final()?;
+
     Ok(())
 }
 
 #[test]
 #[serial]
-fn aes_gcm_with_aad() -> TestResult {
-    // Encrypt a block of zeros with AES-128-GCM.
-    // Use another block of zeros for AAD.
-    let key = vec![0; 16];
-    let iv = [0; 12];
-    let aad = [0; 16];
-    let plain = [0; 16];
-    let expected_cipher_and_tag = [
-        0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92, 0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe,
-        0x78, 0xd2, 0x4e, 0x50, 0x3a, 0x1b, 0xb0, 0x37, 0x07, 0x1c, 0x71, 0xb3, 0x5d,
-    ];
-
+fn sha512_digest_multipart_large_input() -> TestResult {
     let (pkcs11, slot) = init_pins();
-    let session = pkcs11.open_rw_session(slot)?;
+    let mut session = pkcs11.open_rw_session(slot)?;
     session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    let template = [
-        Attribute::Class(ObjectClass::SECRET_KEY),
-        Attribute::KeyType(KeyType::AES),
-        Attribute::Value(key),
-        Attribute::Encrypt(true),
-    ];
-    let key_handle = session.create_object(&template)?;
-    let mechanism = Mechanism::AesGcm(GcmParams::new(&iv, &aad, 96.into()));
-    let cipher_and_tag = session.encrypt(&mechanism, key_handle, &plain)?;
-    assert_eq!(expected_cipher_and_tag[..], cipher_and_tag[..]);
+    // 1 MiB of zeros, in 4 KiB chunks
+    let data = vec![0; 1 << 20];
+    let want = session.digest(&Mechanism::Sha512, &data)?;
+
+    session.digest_init(&Mechanism::Sha512)?;
+    for part in data.chunks(4096) {
+        session.digest_update(part)?;
+    }
+    let have = session.digest_final()?;
+    assert_eq!(want, have);
+    let mut digester = session.digest_streaming(&Mechanism::Sha512)?;
+    for part in data.chunks(4096) {
+        digester.update(part)?;
+    }
+    assert_eq!(want, digester.finalize()?);
+    // only hash mechanisms can be used to digest
+    assert!(matches!(
+        session.digest_streaming(&Mechanism::Sha512RsaPkcs),
+        Err(Error::InvalidValue)
+    ));
+
+    // the token rejects mechanisms that are not digest mechanisms
+    let res = session.digest_init(&Mechanism::AesEcb);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::MechanismInvalid,
+            Function::DigestInit
+        ))
+    ));
+
     Ok(())
 }
 
 #[test]
 #[serial]
-fn rsa_pkcs_oaep_empty() -> TestResult {
+fn sha256_digest_key() -> TestResult {
     let (pkcs11, slot) = init_pins();
     let session = pkcs11.open_rw_session(slot)?;
     session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    let pub_key_template = [Attribute::ModulusBits(2048.into())];
-    let (pubkey, privkey) =
-        session.generate_key_pair(&Mechanism::RsaPkcsKeyPairGen, &pub_key_template, &[])?;
-    let oaep = PkcsOaepParams::new(
-        MechanismType::SHA1,
-        PkcsMgfType::MGF1_SHA1,
-        PkcsOaepSource::empty(),
+    let value = vec![0x5A; 32];
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+        Attribute::Value(value.clone()),
+    ])?;
+
+    let prefix = b"key confirmation";
+    let mut data = prefix.to_vec();
+    data.extend(&value);
+    let want = session.digest(&Mechanism::Sha256, &data)?;
+
+    session.digest_init(&Mechanism::Sha256)?;
+    session.digest_update(prefix)?;
+    session.digest_key(key)?;
+    let have = session.digest_final()?;
+    assert_eq!(want, have);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_ctr_known_answer() -> TestResult {
+    // CTR-AES128.Encrypt test vector from NIST SP 800-38A, section F.5.1
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c")?;
+    let counter_block = [
+        0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe,
+        0xff,
+    ];
+    let plain = hex::decode(concat!(
+        "6bc1bee22e409f96e93d7e117393172a",
+        "ae2d8a571e03ac9c9eb76fac45af8e51",
+        "30c81c46a35ce411e5fbc1191a0a52ef",
+        "f69f2445df4f9b17ad2b417be66c3710",
+    ))?;
+    let expected_cipher = hex::decode(concat!(
+        "874d6191b620e3261bef6864990db6ce",
+        "9806f66b7970fdff8617187bb9fffdff",
+        "5ae4df3edbd5d35e5b4f09020db03eab",
+        "1e031dda2fbe03d1792170a0f3009cee",
+    ))?;
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(key),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::AesCtr(AesCtrParams::new(128, counter_block)?);
+
+    let cipher = session.encrypt(&mechanism, key_handle, &plain)?;
+    assert_eq!(expected_cipher, cipher);
+
+    let decrypted = session.decrypt(&mechanism, key_handle, &expected_cipher)?;
+    assert_eq!(plain, decrypted);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_ctr_multipart() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(16.into()),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+        ],
+    )?;
+    let mechanism = Mechanism::AesCtr(AesCtrParams::with_nonce(&[0x42; 12])?);
+
+    // 1000 bytes is not a multiple of the block size: CTR mode needs no padding
+    let plain: Vec<u8> = (0..1000).map(|i| (i * 7) as u8).collect();
+    session.encrypt_init(&mechanism, key)?;
+    let mut cipher = Vec::new();
+    for part in plain.chunks(100) {
+        cipher.extend(session.encrypt_update(part)?);
+    }
+    cipher.extend(session.encrypt_final()?);
+    assert_eq!(cipher.len(), plain.len());
+    assert_eq!(cipher, session.encrypt(&mechanism, key, &plain)?);
+
+    // the ciphertext is the data XORed with the keystream, which is the
+    // encryption of zeros
+    let keystream = session.encrypt(&mechanism, key, &[0; 1000])?;
+    let recovered: Vec<u8> = cipher.iter().zip(&keystream).map(|(c, k)| c ^ k).collect();
+    assert_eq!(recovered, plain);
+
+    assert_eq!(session.decrypt(&mechanism, key, &cipher)?, plain);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+// Currently empty AAD crashes SoftHSM, see: https://github.com/opendnssec/SoftHSMv2/issues/605
+#[ignore]
+fn aes_gcm_no_aad() -> TestResult {
+    // Encrypt two blocks of zeros with AES-128-GCM
+    let key = vec![0; 16];
+    let iv = [0; 12];
+    let aad = [];
+    let plain = [0; 32];
+    let expected_cipher_and_tag = [
+        0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92, 0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe,
+        0x78, 0xf7, 0x95, 0xaa, 0xab, 0x49, 0x4b, 0x59, 0x23, 0xf7, 0xfd, 0x89, 0xff, 0x94, 0x8b,
+        0xc1, 0xe0, 0x40, 0x49, 0x0a, 0xf4, 0x80, 0x56, 0x06, 0xb2, 0xa3, 0xa2, 0xe7, 0x93,
+    ];
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(key),
+        Attribute::Encrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::AesGcm(GcmParams::new(&iv, &aad, 96.into())?);
+    let cipher_and_tag = session.encrypt(&mechanism, key_handle, &plain)?;
+    assert_eq!(expected_cipher_and_tag[..], cipher_and_tag[..]);
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_gcm_with_aad() -> TestResult {
+    // Encrypt a block of zeros with AES-128-GCM.
+    // Use another block of zeros for AAD.
+    let key = vec![0; 16];
+    let iv = [0; 12];
+    let aad = [0; 16];
+    let plain = [0; 16];
+    let expected_cipher_and_tag = [
+        0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92, 0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe,
+        0x78, 0xd2, 0x4e, 0x50, 0x3a, 0x1b, 0xb0, 0x37, 0x07, 0x1c, 0x71, 0xb3, 0x5d,
+    ];
+
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(key),
+        Attribute::Encrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::AesGcm(GcmParams::new(&iv, &aad, 96.into())?);
+    let cipher_and_tag = session.encrypt(&mechanism, key_handle, &plain)?;
+    assert_eq!(expected_cipher_and_tag[..], cipher_and_tag[..]);
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn chacha20_poly1305_known_answer() -> TestResult {
+    // Test vector from RFC 8439, section 2.8.2
+    let key: Vec<u8> = (0x80..=0x9f).collect();
+    let nonce = [
+        0x07, 0x00, 0x00, 0x00, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47,
+    ];
+    let aad = [
+        0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7,
+    ];
+    let plain = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    let expected_cipher_and_tag = [
+        0xd3, 0x1a, 0x8d, 0x34, 0x64, 0x8e, 0x60, 0xdb, 0x7b, 0x86, 0xaf, 0xbc, 0x53, 0xef, 0x7e,
+        0xc2, 0xa4, 0xad, 0xed, 0x51, 0x29, 0x6e, 0x08, 0xfe, 0xa9, 0xe2, 0xb5, 0xa7, 0x36, 0xee,
+        0x62, 0xd6, 0x3d, 0xbe, 0xa4, 0x5e, 0x8c, 0xa9, 0x67, 0x12, 0x82, 0xfa, 0xfb, 0x69, 0xda,
+        0x92, 0x72, 0x8b, 0x1a, 0x71, 0xde, 0x0a, 0x9e, 0x06, 0x0b, 0x29, 0x05, 0xd6, 0xa5, 0xb6,
+        0x7e, 0xcd, 0x3b, 0x36, 0x92, 0xdd, 0xbd, 0x7f, 0x2d, 0x77, 0x8b, 0x8c, 0x98, 0x03, 0xae,
+        0xe3, 0x28, 0x09, 0x1b, 0x58, 0xfa, 0xb3, 0x24, 0xe4, 0xfa, 0xd6, 0x75, 0x94, 0x55, 0x85,
+        0x80, 0x8b, 0x48, 0x31, 0xd7, 0xbc, 0x3f, 0xf4, 0xde, 0xf0, 0x8e, 0x4b, 0x7a, 0x9d, 0xe5,
+        0x76, 0xd2, 0x65, 0x86, 0xce, 0xc6, 0x4b, 0x61, 0x16, 0x1a, 0xe1, 0x0b, 0x59, 0x4f, 0x09,
+        0xe2, 0x6a, 0x7e, 0x90, 0x2e, 0xcb, 0xd0, 0x60, 0x06, 0x91,
+    ];
+
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::CHACHA20_POLY1305]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::CHACHA20),
+        Attribute::Value(key),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::ChaCha20Poly1305(ChaCha20Poly1305Params::new(&nonce, &aad)?);
+
+    let decrypted = session.decrypt(&mechanism, key_handle, &expected_cipher_and_tag)?;
+    assert_eq!(plain[..], decrypted[..]);
+
+    let cipher_and_tag = session.encrypt(&mechanism, key_handle, plain)?;
+    assert_eq!(expected_cipher_and_tag[..], cipher_and_tag[..]);
+
+    // Any change to the tag must be detected
+    let mut tampered = cipher_and_tag;
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(session.decrypt(&mechanism, key_handle, &tampered).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn chacha20_known_answer() -> TestResult {
+    // Test vector from RFC 8439, section 2.4.2
+    let key: Vec<u8> = (0x00..=0x1f).collect();
+    // The block counter is laid out as in the ChaCha20 state, little endian
+    let block_counter = [0x01, 0x00, 0x00, 0x00];
+    let nonce = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let plain = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    let expected_cipher = hex::decode(concat!(
+        "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b",
+        "f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8",
+        "07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736",
+        "5af90bbf74a35be6b40b8eedf2785e42874d",
+    ))?;
+
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::CHACHA20]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::CHACHA20),
+        Attribute::Value(key),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ];
+    let key_handle = session.create_object(&template)?;
+    let mechanism = Mechanism::ChaCha20(ChaCha20Params::new(&block_counter, &nonce)?);
+
+    let cipher = session.encrypt(&mechanism, key_handle, plain)?;
+    assert_eq!(expected_cipher, cipher);
+
+    let decrypted = session.decrypt(&mechanism, key_handle, &cipher)?;
+    assert_eq!(plain[..], decrypted[..]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_ccm_round_trip() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::AES_CCM]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(vec![0x42; 16]),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ];
+    let key = session.create_object(&template)?;
+
+    let nonce = [0x11; 13];
+    let aad = [0x22; 20];
+    let plain = [0x33; 40];
+    for (nonce_len, mac_len) in [(7, 4), (11, 8), (13, 16)] {
+        let params = CcmParams::new(
+            plain.len() as u64,
+            &nonce[..nonce_len],
+            &aad,
+            mac_len as u64,
+        )?;
+        let cipher_and_mac = session.encrypt(&Mechanism::AesCcm(params), key, &plain)?;
+        assert_eq!(cipher_and_mac.len(), plain.len() + mac_len);
+
+        let decrypted = session.decrypt(&Mechanism::AesCcm(params), key, &cipher_and_mac)?;
+        assert_eq!(plain[..], decrypted[..]);
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_gcm_message_based() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(16.into()),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+        ],
+    )?;
+    // The IV and tag are only given for each message
+    let mechanism = Mechanism::AesGcmMessage;
+
+    // The message-based functions were added in PKCS #11 3.0
+    if !pkcs11.is_fn_supported(Function::MessageEncryptInit) {
+        let res = session.message_encrypt_init(&mechanism, key);
+        assert!(matches!(res, Err(Error::LibraryVersionNotSupported)));
+        return Ok(());
+    }
+
+    let aad = b"record header";
+    let mut records = Vec::new();
+    session.message_encrypt_init(&mechanism, key)?;
+    for i in 0u32..100 {
+        let plain = format!("record {i}").into_bytes();
+        let mut iv = [0; 12];
+        iv[..4].copy_from_slice(&i.to_be_bytes());
+        let mut tag = [0; 16];
+        let param = MessageParam::AesGcmMessage(GcmMessageParams::new(
+            &mut iv,
+            0.into(),
+            GeneratorFunction::NO_GENERATE,
+            &mut tag,
+        )?);
+        let cipher = session.encrypt_message(&param, aad, &plain)?;
+        records.push((iv, plain, cipher, tag));
+    }
+    session.message_encrypt_final()?;
+
+    // The message-based API gives the same result as single-part encryption,
+    // which appends the tag to the ciphertext
+    for (iv, plain, cipher, tag) in &records {
+        let mechanism = Mechanism::AesGcm(GcmParams::new(iv, aad, 128.into())?);
+        let cipher_and_tag = session.encrypt(&mechanism, key, plain)?;
+        assert_eq!(cipher_and_tag, [&cipher[..], &tag[..]].concat());
+    }
+
+    session.message_decrypt_init(&mechanism, key)?;
+    for (mut iv, plain, cipher, mut tag) in records {
+        let param = MessageParam::AesGcmMessage(GcmMessageParams::new(
+            &mut iv,
+            0.into(),
+            GeneratorFunction::NO_GENERATE,
+            &mut tag,
+        )?);
+        assert_eq!(session.decrypt_message(&param, aad, &cipher)?, plain);
+    }
+    session.message_decrypt_final()?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn hmac_message_based() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Token(false),
+        Attribute::Sign(true),
+        Attribute::Verify(true),
+        Attribute::Value(vec![0x0b; 32]),
+    ])?;
+    let mechanism = Mechanism::Sha256Hmac;
+
+    // The message-based functions were added in PKCS #11 3.0
+    if !pkcs11.is_fn_supported(Function::MessageSignInit) {
+        let res = session.message_sign_init(&mechanism, key);
+        assert!(matches!(res, Err(Error::LibraryVersionNotSupported)));
+        return Ok(());
+    }
+
+    let messages: Vec<Vec<u8>> = (0u32..100)
+        .map(|i| format!("message {i}").into_bytes())
+        .collect();
+
+    session.message_sign_init(&mechanism, key)?;
+    let mut macs = Vec::new();
+    for message in &messages {
+        macs.push(session.sign_message(None, message)?);
+    }
+    // The last message is also signed in two parts
+    let (first, last) = messages[99].split_at(4);
+    session.sign_message_begin(None)?;
+    session.sign_message_next(None, first)?;
+    assert_eq!(session.sign_message_final(None, last)?, macs[99]);
+    session.message_sign_final()?;
+
+    // The message-based API gives the same result as single-part signing
+    for (message, mac) in messages.iter().zip(&macs) {
+        assert_eq!(&session.sign(&mechanism, key, message)?, mac);
+    }
+
+    session.message_verify_init(&mechanism, key)?;
+    for (message, mac) in messages.iter().zip(&macs) {
+        session.verify_message(None, message, mac)?;
+    }
+    session.verify_message_begin(None)?;
+    session.verify_message_next(None, first)?;
+    session.verify_message_final(None, last, &macs[99])?;
+    let res = session.verify_message(None, &messages[0], &macs[1]);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::SignatureInvalid,
+            Function::VerifyMessage
+        ))
+    ));
+    session.message_verify_final()?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn rsa_pkcs_oaep_empty() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [Attribute::ModulusBits(2048.into())];
+    let (pubkey, privkey) =
+        session.generate_key_pair(&Mechanism::RsaPkcsKeyPairGen, &pub_key_template, &[])?;
+    let oaep = PkcsOaepParams::new(
+        MechanismType::SHA1,
+        PkcsMgfType::MGF1_SHA1,
+        PkcsOaepSource::empty(),
     );
     assert_eq!(MechanismType::SHA1, oaep.hash_alg());
     let encrypt_mechanism: Mechanism = Mechanism::RsaPkcsOaep(oaep);
@@ -1255,6 +3408,33 @@ fn rsa_pkcs_oaep_empty() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn rsa_pkcs_oaep_sha256() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let pub_key_template = [Attribute::ModulusBits(2048.into())];
+    let (pubkey, privkey) =
+        session.generate_key_pair(&Mechanism::RsaPkcsKeyPairGen, &pub_key_template, &[])?;
+    let oaep = PkcsOaepParams::new(
+        MechanismType::SHA256,
+        PkcsMgfType::MGF1_SHA256,
+        PkcsOaepSource::empty(),
+    );
+    let mechanism = Mechanism::RsaPkcsOaep(oaep);
+
+    let key_blob = session.generate_random_vec(32)?;
+    let encrypted_data = session.encrypt(&mechanism, pubkey, &key_blob)?;
+    assert_eq!(encrypted_data.len(), 256);
+
+    let decrypted_data = session.decrypt(&mechanism, privkey, &encrypted_data)?;
+    assert_eq!(key_blob, decrypted_data);
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 #[ignore] // it's not clear why the test with data specified fails
@@ -1340,15 +3520,226 @@ fn generate_generic_secret_key() -> TestResult {
 
 #[test]
 #[serial]
-fn ekdf_aes_cbc_encrypt_data() -> TestResult {
+fn sp800_108_counter_kdf() -> TestResult {
     let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::SP800_108_COUNTER_KDF]) {
+        return Ok(());
+    }
     let session = pkcs11.open_rw_session(slot)?;
     session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
 
-    // key template
-    let key_template = vec![
+    let base_key = session.create_object(&[
         Attribute::Class(ObjectClass::SECRET_KEY),
-        Attribute::KeyType(KeyType::AES),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Value((0..32).collect()),
+        Attribute::Derive(true),
+    ])?;
+
+    // PRF input: counter || "label" || 0x00 || "context" || L, as in NIST
+    // SP 800-108 and in the KBKDF implementation of the Python cryptography
+    // package, which computed the expected value
+    let counter = KbkdfCounterFormat::new(false, 32)?;
+    let dkm_length = KbkdfDkmLengthFormat::new(DkmLengthMethod::SumOfKeys, false, 32)?;
+    let data_params = [
+        PrfDataParam::new(PrfDataParamType::IterationVariable(Some(&counter))),
+        PrfDataParam::new(PrfDataParamType::ByteArray(b"label")),
+        PrfDataParam::new(PrfDataParamType::ByteArray(&[0])),
+        PrfDataParam::new(PrfDataParamType::ByteArray(b"context")),
+        PrfDataParam::new(PrfDataParamType::DkmLength(&dkm_length)),
+    ];
+    let params = KbkdfParams::new(MechanismType::SHA256_HMAC, &data_params, None)?;
+
+    let derived_key = session.derive_key(
+        &Mechanism::Sp800108CounterKdf(params),
+        base_key,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(32.into()),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+    let value = match session.get_attributes(derived_key, &[AttributeType::Value])?[..] {
+        [Attribute::Value(ref value)] => value.clone(),
+        _ => panic!("Expected a value attribute."),
+    };
+    assert_eq!(
+        hex::encode(value),
+        "303790cfe363abe9682dbfff5941f23b32addc96da72f4c7e5b20e9f59a4e570"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sp800_108_feedback_kdf_additional_key() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::SP800_108_FEEDBACK_KDF]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let base_key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Value((0..32).collect()),
+        Attribute::Derive(true),
+    ])?;
+
+    // PRF input: K(i-1) || i || "label" || 0x00 || "context" || L, with
+    // K(0) = IV. L covers both keys, so the additional key is the first half
+    // of K(2). The expected values were computed with Python's hmac module.
+    let counter = KbkdfCounterFormat::new(false, 32)?;
+    let dkm_length = KbkdfDkmLengthFormat::new(DkmLengthMethod::SumOfKeys, false, 32)?;
+    let data_params = [
+        PrfDataParam::new(PrfDataParamType::IterationVariable(None)),
+        PrfDataParam::new(PrfDataParamType::Counter(&counter)),
+        PrfDataParam::new(PrfDataParamType::ByteArray(b"label")),
+        PrfDataParam::new(PrfDataParamType::ByteArray(&[0])),
+        PrfDataParam::new(PrfDataParamType::ByteArray(b"context")),
+        PrfDataParam::new(PrfDataParamType::DkmLength(&dkm_length)),
+    ];
+    let additional_template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::ValueLen(16.into()),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+    ];
+    let additional_keys = DerivedKeys::new(&[&additional_template])?;
+    let iv = [0xaa; 16];
+    let params = KbkdfFeedbackParams::new(
+        MechanismType::SHA256_HMAC,
+        &data_params,
+        Some(&iv),
+        Some(&additional_keys),
+    )?;
+
+    let derived_key = session.derive_key(
+        &Mechanism::Sp800108FeedbackKdf(params),
+        base_key,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(32.into()),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+    let additional_key = match additional_keys.handles()[..] {
+        [Some(handle)] => handle,
+        _ => panic!("Expected the handle of the additional key."),
+    };
+
+    let mut values = Vec::new();
+    for key in [derived_key, additional_key] {
+        match session.get_attributes(key, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => values.push(hex::encode(value)),
+            _ => panic!("Expected a value attribute."),
+        }
+    }
+    assert_eq!(
+        values,
+        [
+            "143090e4a9433ebd650af031833e2fe56f8ecd97687a644cea0ba092eed7e20a",
+            "0833f7ed46f4c6fe8cb235ca55ec02f0",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn pbkdf2_aes_key_gen() -> TestResult {
+    use cryptoki::mechanism::kdf::{Pbkdf2Params, Pbkdf2Salt, Pkcs5Prf};
+
+    let salt = b"NaCl and pepper";
+    assert!(Pbkdf2Params::new(
+        Pkcs5Prf::HMAC_SHA256,
+        Pbkdf2Salt::Specified(salt),
+        0,
+        Some(b"password"),
+    )
+    .is_err());
+
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::PKCS5_PBKD2]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let params = Pbkdf2Params::new(
+        Pkcs5Prf::HMAC_SHA256,
+        Pbkdf2Salt::Specified(salt),
+        10_000,
+        Some(b"password"),
+    )?;
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::ValueLen(32.into()),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ];
+    let key = session.generate_key(&Mechanism::Pkcs5Pbkd2(params), &template)?;
+
+    let attributes =
+        session.get_attributes(key, &[AttributeType::KeyType, AttributeType::ValueLen])?;
+    assert!(matches!(attributes[0], Attribute::KeyType(KeyType::AES)));
+    assert!(matches!(attributes[1], Attribute::ValueLen(len) if len == 32.into()));
+
+    // The HMAC-SHA1 vector is from RFC 6070, the HMAC-SHA256 one was computed
+    // with Python's hashlib
+    let vectors = [
+        (
+            Pkcs5Prf::HMAC_SHA1,
+            20,
+            "4b007901b765489abead49d926f721d065a429c1",
+        ),
+        (
+            Pkcs5Prf::HMAC_SHA256,
+            32,
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a",
+        ),
+    ];
+    for (prf, len, want) in vectors {
+        let params =
+            Pbkdf2Params::new(prf, Pbkdf2Salt::Specified(b"salt"), 4096, Some(b"password"))?;
+        let template = [
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(len.into()),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ];
+        let key = session.generate_key(&Mechanism::Pkcs5Pbkd2(params), &template)?;
+        let value = match session.get_attributes(key, &[AttributeType::Value])?[..] {
+            [Attribute::Value(ref value)] => value.clone(),
+            _ => panic!("Expected a value attribute."),
+        };
+        assert_eq!(hex::encode(value), want);
+        session.destroy_object(key)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn ekdf_aes_cbc_encrypt_data() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // key template
+    let key_template = vec![
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
         Attribute::Token(true),
         Attribute::Sensitive(true),
         Attribute::Private(true),
@@ -1380,7 +3771,7 @@ fn ekdf_aes_cbc_encrypt_data() -> TestResult {
     // derived private key the same for all child keys. If deriving a key for 256-bit AES, this means half of
     // the key to be static.
     // =======================================================================================================
-    let aes_cbc_derive_params = AesCbcDeriveParams::new([0u8; 16], [1u8; 32].as_slice());
+    let aes_cbc_derive_params = AesCbcDeriveParams::new([0u8; 16], [1u8; 32].as_slice())?;
     let derived_key = session.derive_key(
         &Mechanism::AesCbcEncryptData(aes_cbc_derive_params),
         master_key,
@@ -1394,6 +3785,53 @@ fn ekdf_aes_cbc_encrypt_data() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[serial]
+fn aes_encrypt_data_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let master_key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value((0..16).collect()),
+        Attribute::Derive(true),
+    ])?;
+    let derived_key_template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+    ];
+    let data: Vec<u8> = (16..48).collect();
+
+    // The value of the derived key is the cipher text of the data
+    assert!(AesEcbDeriveParams::new(&data[..20]).is_err());
+    let params = AesEcbDeriveParams::new(&data)?;
+    let derived_key = session.derive_key(
+        &Mechanism::AesEcbEncryptData(params),
+        master_key,
+        &derived_key_template,
+    )?;
+    let value = session.get_attributes(derived_key, &[AttributeType::Value])?;
+    let expected = hex::decode("07feef74e1d5036e900eee118e9492935be87e2e5b447c944b21c9af7756c0d8")?;
+    assert_eq!(value, [Attribute::Value(expected)]);
+
+    assert!(AesCbcDeriveParams::new([0xaa; 16], &data[..20]).is_err());
+    let params = AesCbcDeriveParams::new([0xaa; 16], &data)?;
+    let derived_key = session.derive_key(
+        &Mechanism::AesCbcEncryptData(params),
+        master_key,
+        &derived_key_template,
+    )?;
+    let value = session.get_attributes(derived_key, &[AttributeType::Value])?;
+    let expected = hex::decode("31482d98b7b4f2cf447183b7794a890fee65547d2a9069eeff91adb8efb50bd2")?;
+    assert_eq!(value, [Attribute::Value(expected)]);
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn sign_verify_sha1_hmac() -> TestResult {
@@ -1591,6 +4029,45 @@ fn aes_cmac_sign() -> TestResult {
     aes_cmac_sign_impl(key, &message_len64, expected_mac_len64)
 }
 
+#[test]
+#[serial]
+fn aes_cmac_general_sign_verify() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::AES_CMAC_GENERAL]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.create_object(&[
+        Attribute::Token(false),
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES),
+        Attribute::Value(hex::decode("2b7e151628aed2a6abf7158809cf4f3c")?),
+        Attribute::Sign(true),
+        Attribute::Verify(true),
+    ])?;
+
+    // The 16-byte example of RFC 4493, with the MAC truncated to 8 bytes
+    let message = hex::decode("6bc1bee22e409f96e93d7e117393172a")?;
+    let mechanism = Mechanism::aes_cmac_general(8)?;
+    let mac = session.sign(&mechanism, key, &message)?;
+    assert_eq!(mac, hex::decode("070a16b46b4d4144")?);
+    session.verify(&mechanism, key, &message, &mac)?;
+
+    // The 64-byte example, with the MAC truncated to 4 bytes
+    let message = hex::decode(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )?;
+    let mechanism = Mechanism::aes_cmac_general(4)?;
+    let mac = session.sign(&mechanism, key, &message)?;
+    assert_eq!(mac, hex::decode("51f0bebf")?);
+    session.verify(&mechanism, key, &message, &mac)?;
+
+    Ok(())
+}
+
 fn aes_cmac_sign_impl(key: [u8; 16], message: &[u8], expected_mac: [u8; 16]) -> TestResult {
     let (pkcs11, slot) = init_pins();
     let session = pkcs11.open_rw_session(slot)?;
@@ -1682,3 +4159,809 @@ fn aes_cmac_verify_impl(key: [u8; 16], message: &[u8], expected_mac: [u8; 16]) -
     session.verify(&Mechanism::AesCMac, key, message, &expected_mac)?;
     Ok(())
 }
+
+// The expected values were computed with the TLS 1.2 PRF of RFC 5246, not
+// checked against a token: SoftHSM does not support the TLS 1.2 mechanisms,
+// so this test is skipped there.
+#[test]
+#[serial]
+fn tls12_key_block_derivation() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::TLS12_MASTER_KEY_DERIVE,
+            MechanismType::TLS12_KEY_AND_MAC_DERIVE,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // A pre-master secret for TLS 1.2, as sent in the ClientKeyExchange
+    let mut pre_master_secret = vec![3, 3];
+    pre_master_secret.extend_from_slice(&[0x5a; 46]);
+    let pre_master_key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Value(pre_master_secret),
+        Attribute::Derive(true),
+    ])?;
+
+    let client_random = [0x11; 32];
+    let server_random = [0x22; 32];
+    let random_info = TlsRandomData::new(&client_random, &server_random)?;
+
+    let mut version = Version::default();
+    let params =
+        Tls12MasterKeyDeriveParams::new(MechanismType::SHA256, random_info, Some(&mut version));
+    let master_key = session.derive_key(
+        &Mechanism::Tls12MasterKeyDerive(params),
+        pre_master_key,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::Derive(true),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+    assert_eq!((version.major(), version.minor()), (3, 3));
+
+    let value = session.get_attributes(master_key, &[AttributeType::Value])?;
+    let expected = hex::decode(
+        "29db30f36c3fad7bcbd1d08078cdb4655cb1550e00d4bdfa7aa71f1822e3b79c\
+         cda7567d5a7b65961374ef14b79c2271",
+    )?;
+    assert_eq!(value, [Attribute::Value(expected)]);
+
+    // Keys for AES-128-GCM: no MAC secrets, and 4-byte implicit IVs
+    let params = Tls12KeyMaterialParams::new(MechanismType::SHA256, random_info, 0, 128, 32)?;
+    let output = session.derive_tls_key_material(
+        &Mechanism::Tls12KeyAndMacDerive(params),
+        master_key,
+        &[
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+
+    assert!(output.client_mac_secret().is_none());
+    assert!(output.server_mac_secret().is_none());
+    let client_key = output.client_key().ok_or("no client key")?;
+    let server_key = output.server_key().ok_or("no server key")?;
+    let value = session.get_attributes(client_key, &[AttributeType::Value])?;
+    assert_eq!(
+        value,
+        [Attribute::Value(hex::decode(
+            "a611e5ba72ed4692b5dee81f8579021f"
+        )?)]
+    );
+    let value = session.get_attributes(server_key, &[AttributeType::Value])?;
+    assert_eq!(
+        value,
+        [Attribute::Value(hex::decode(
+            "87af0a0ac3f5ecfc579e8c766f284806"
+        )?)]
+    );
+    assert_eq!(output.client_iv(), hex::decode("b448bf93")?);
+    assert_eq!(output.server_iv(), hex::decode("6491f66e")?);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_xts_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[MechanismType::AES_XTS_KEY_GEN, MechanismType::AES_XTS],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::AES_XTS),
+        Attribute::Value((0..32).collect()),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ])?;
+
+    // A sector of 40 bytes, which is not a multiple of the block size
+    let plain = [0x33; 40];
+    let mechanism = Mechanism::AesXts(AesXtsParams::with_data_unit(5));
+    let cipher = session.encrypt(&mechanism, key, &plain)?;
+    let expected = hex::decode(
+        "d55a5f3f6b3cdeb75e62ef2d7eb1a66ce2c238df6f4c01e01eebeff7dcda64c4b66370005095a5a4",
+    )?;
+    assert_eq!(cipher, expected);
+    assert_eq!(session.decrypt(&mechanism, key, &cipher)?, plain);
+
+    // Generated keys are double-length keys
+    let key = session.generate_key(
+        &Mechanism::AesXtsKeyGen,
+        &[
+            Attribute::ValueLen(64.into()),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+        ],
+    )?;
+    let cipher = session.encrypt(&mechanism, key, &plain)?;
+    assert_eq!(session.decrypt(&mechanism, key, &cipher)?, plain);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn aes_xts_ieee1619_vectors() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::AES_XTS]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // Vectors 2 and 3 of IEEE 1619, annex B
+    let vectors = [
+        (
+            "1111111111111111111111111111111122222222222222222222222222222222",
+            "c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0",
+        ),
+        (
+            "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f022222222222222222222222222222222",
+            "af85336b597afc1a900b2eb21ec949d292df4c047e0b21532186a5971a227a89",
+        ),
+    ];
+    let mechanism = Mechanism::AesXts(AesXtsParams::with_data_unit(0x3333333333));
+    let plain = [0x44; 32];
+    for (key, cipher) in vectors {
+        let key = session.create_object(&[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::AES_XTS),
+            Attribute::Value(hex::decode(key)?),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+        ])?;
+        let cipher = hex::decode(cipher)?;
+        assert_eq!(session.encrypt(&mechanism, key, &plain)?, cipher);
+        assert_eq!(session.decrypt(&mechanism, key, &cipher)?, plain);
+
+        // A data unit is at least one block long
+        assert!(matches!(
+            session.encrypt(&mechanism, key, &plain[..8]),
+            Err(Error::Pkcs11(RvError::DataLenRange, Function::Encrypt))
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn hkdf_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::HKDF_KEY_GEN,
+            MechanismType::HKDF_DERIVE,
+            MechanismType::HKDF_DATA,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let ikm = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Value(vec![0x0b; 22]),
+        Attribute::Derive(true),
+    ])?;
+
+    // Test cases 1 and 3 of RFC 5869
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let params = HkdfParams::new(
+        MechanismType::SHA256,
+        Some(HkdfSalt::Data(&salt)),
+        Some(&info),
+    );
+    let okm = session.derive_data(&Mechanism::HkdfData(params), ikm, 42)?;
+    assert_eq!(
+        okm,
+        hex::decode(
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+             34007208d5b887185865"
+        )?
+    );
+
+    let params = HkdfParams::new(MechanismType::SHA256, Some(HkdfSalt::Null), Some(&[]));
+    let okm = session.derive_data(&Mechanism::HkdfData(params), ikm, 42)?;
+    assert_eq!(
+        okm,
+        hex::decode(
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+             9d201395faa4b61a96c8"
+        )?
+    );
+
+    // The same derivation, to a key
+    let key = session.derive_key(
+        &Mechanism::HkdfDerive(params),
+        ikm,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(42.into()),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+    let value = session.get_attributes(key, &[AttributeType::Value])?;
+    assert_eq!(value, [Attribute::Value(okm)]);
+
+    // A generated salt key
+    let salt_key = session.generate_key(
+        &Mechanism::HkdfKeyGen,
+        &[Attribute::ValueLen(32.into()), Attribute::Derive(true)],
+    )?;
+    let params = HkdfParams::new(
+        MechanismType::SHA256,
+        Some(HkdfSalt::Key(salt_key)),
+        Some(&info),
+    );
+    let okm = session.derive_data(&Mechanism::HkdfData(params), ikm, 32)?;
+    assert_eq!(okm.len(), 32);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn des3_cbc_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::DES3),
+        Attribute::Value(hex::decode(
+            "0123456789abcdeffedcba987654321089abcdef01234567",
+        )?),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ])?;
+    let iv = [0x11; 8];
+
+    // Without padding, the data is a multiple of the block size
+    let plain = b"legacy!!pcidss 3";
+    let cipher = session.encrypt(&Mechanism::Des3Cbc(iv), key, plain)?;
+    assert_eq!(cipher, hex::decode("15ede5cd2d777b8819a2a86fd8e183f5")?);
+    assert_eq!(
+        session.decrypt(&Mechanism::Des3Cbc(iv), key, &cipher)?,
+        plain
+    );
+    assert!(session
+        .encrypt(&Mechanism::Des3Cbc(iv), key, b"Hello, 3DES!")
+        .is_err());
+
+    let plain = b"Hello, 3DES!";
+    let cipher = session.encrypt(&Mechanism::Des3CbcPad(iv), key, plain)?;
+    assert_eq!(cipher, hex::decode("c569a3ddde1fb54a23d78432a30417d9")?);
+    assert_eq!(
+        session.decrypt(&Mechanism::Des3CbcPad(iv), key, &cipher)?,
+        plain
+    );
+
+    // Generated keys work the same
+    let key = session.generate_key(
+        &Mechanism::Des3KeyGen,
+        &[Attribute::Encrypt(true), Attribute::Decrypt(true)],
+    )?;
+    let cipher = session.encrypt(&Mechanism::Des3CbcPad(iv), key, plain)?;
+    assert_eq!(
+        session.decrypt(&Mechanism::Des3CbcPad(iv), key, &cipher)?,
+        plain
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn operation_state() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_ro_session(slot)?;
+
+    let data = [0x5a; 100];
+    let expected = session.digest(&Mechanism::Sha256, &data)?;
+
+    session.digest_init(&Mechanism::Sha256)?;
+    session.digest_update(&data[..50])?;
+    let state = match session.get_operation_state() {
+        Ok(state) => state,
+        // Saving the operation state is optional
+        Err(Error::Pkcs11(
+            RvError::FunctionNotSupported | RvError::StateUnsaveable,
+            Function::GetOperationState,
+        )) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Resume the digest in another session
+    let other = pkcs11.open_ro_session(slot)?;
+    other.set_operation_state(&state, None, None)?;
+    other.digest_update(&data[50..])?;
+    assert_eq!(other.digest_final()?, expected);
+
+    // The original operation is still active
+    session.digest_update(&data[50..])?;
+    assert_eq!(session.digest_final()?, expected);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn simple_key_derivation() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::CONCATENATE_BASE_AND_KEY,
+            MechanismType::CONCATENATE_BASE_AND_DATA,
+            MechanismType::CONCATENATE_DATA_AND_BASE,
+            MechanismType::XOR_BASE_AND_DATA,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let secret = |value: Vec<u8>| {
+        session.create_object(&[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::Value(value),
+            Attribute::Derive(true),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ])
+    };
+    let derive = |mechanism: &Mechanism, base_key, value_len: Option<u64>| -> TestResult<_> {
+        let mut template = vec![
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ];
+        if let Some(value_len) = value_len {
+            template.push(Attribute::ValueLen(value_len.into()));
+        }
+        let key = session.derive_key(mechanism, base_key, &template)?;
+        match session.get_attributes(key, &[AttributeType::Value])?.pop() {
+            Some(Attribute::Value(value)) => Ok(value),
+            _ => Err("no value".into()),
+        }
+    };
+
+    let base_key = secret(vec![0x0f; 16])?;
+    let other_key = secret(vec![0xf0; 8])?;
+    let data = [0xff; 12];
+    let params = KeyDerivationStringData::new(&data)?;
+
+    // The length is implied by the combined data
+    let value = derive(&Mechanism::ConcatenateBaseAndKey(other_key), base_key, None)?;
+    assert_eq!(value, [[0x0f; 16].as_ref(), &[0xf0; 8]].concat());
+    let value = derive(&Mechanism::ConcatenateDataAndBase(params), base_key, None)?;
+    assert_eq!(value, [data.as_ref(), &[0x0f; 16]].concat());
+    let value = derive(&Mechanism::XorBaseAndData(params), base_key, None)?;
+    assert_eq!(value, [0xf0; 12]);
+
+    // Or set in the template
+    let value = derive(
+        &Mechanism::ConcatenateBaseAndData(params),
+        base_key,
+        Some(20),
+    )?;
+    assert_eq!(value, [[0x0f; 16].as_ref(), &[0xff; 4]].concat());
+    let value = derive(&Mechanism::XorBaseAndData(params), base_key, Some(8))?;
+    assert_eq!(value, [0xf0; 8]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sha3_hmac_generated_key() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::SHA3_256_KEY_GEN,
+            MechanismType::SHA3_256_HMAC,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::Sha3_256KeyGen,
+        &[
+            Attribute::ValueLen(32.into()),
+            Attribute::Sign(true),
+            Attribute::Verify(true),
+        ],
+    )?;
+    let attributes = session.get_attributes(key, &[AttributeType::KeyType])?;
+    assert_eq!(attributes, [Attribute::KeyType(KeyType::SHA3_256_HMAC)]);
+
+    let data = b"message";
+    let mac = session.sign(&Mechanism::Sha3_256Hmac, key, data)?;
+    assert_eq!(mac.len(), 32);
+    session.verify(&Mechanism::Sha3_256Hmac, key, data, &mac)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn extract_key_from_key() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::EXTRACT_KEY_FROM_KEY]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let value: Vec<u8> = (0..32).collect();
+    let base_key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::Value(value.clone()),
+        Attribute::Derive(true),
+    ])?;
+
+    // Bytes 8 to 24 of the base key
+    let key = session.derive_key(
+        &Mechanism::ExtractKeyFromKey(ExtractParams::new(64)?),
+        base_key,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(16.into()),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+    let attributes = session.get_attributes(key, &[AttributeType::Value])?;
+    assert_eq!(attributes, [Attribute::Value(value[8..24].to_vec())]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sha256_hmac_generated_key() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::SHA256_KEY_GEN,
+            MechanismType::SHA256_HMAC,
+            MechanismType::SHA256_HMAC_GENERAL,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::Sha256KeyGen,
+        &[
+            Attribute::ValueLen(32.into()),
+            Attribute::Sign(true),
+            Attribute::Verify(true),
+        ],
+    )?;
+    let attributes = session.get_attributes(key, &[AttributeType::KeyType])?;
+    assert_eq!(attributes, [Attribute::KeyType(KeyType::SHA256_HMAC)]);
+
+    let data = b"message";
+    let mac = session.sign(&Mechanism::Sha256Hmac, key, data)?;
+    assert_eq!(mac.len(), 32);
+    session.verify(&Mechanism::Sha256Hmac, key, data, &mac)?;
+
+    // A truncated MAC
+    let mechanism = Mechanism::sha256_hmac_general(16)?;
+    let truncated = session.sign(&mechanism, key, data)?;
+    assert_eq!(truncated, mac[..16]);
+    session.verify(&mechanism, key, data, &truncated)?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn camellia_known_answer() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::CAMELLIA_KEY_GEN,
+            MechanismType::CAMELLIA_ECB,
+            MechanismType::CAMELLIA_CBC,
+            MechanismType::CAMELLIA_CBC_PAD,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let value = hex::decode("0123456789abcdeffedcba9876543210")?;
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::CAMELLIA),
+        Attribute::Value(value.clone()),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+    ])?;
+
+    // The 128-bit test vector of RFC 3713
+    let cipher = session.encrypt(&Mechanism::CamelliaEcb, key, &value)?;
+    assert_eq!(cipher, hex::decode("67673138549669730857065648eabe43")?);
+
+    let iv = [0x11; 16];
+    let plain = [0x33; 32];
+    let cipher = session.encrypt(&Mechanism::CamelliaCbc(iv), key, &plain)?;
+    assert_eq!(
+        cipher,
+        hex::decode("1f5b7d6c021037dfab75d5c1675e0762f3337f95f7a954927343f99b19f0a7c1")?
+    );
+    assert_eq!(
+        session.decrypt(&Mechanism::CamelliaCbc(iv), key, &cipher)?,
+        plain
+    );
+
+    let key = session.generate_key(
+        &Mechanism::CamelliaKeyGen,
+        &[
+            Attribute::ValueLen(32.into()),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+        ],
+    )?;
+    let cipher = session.encrypt(&Mechanism::CamelliaCbcPad(iv), key, b"Hello")?;
+    assert_eq!(cipher.len(), 16);
+    assert_eq!(
+        session.decrypt(&Mechanism::CamelliaCbcPad(iv), key, &cipher)?,
+        b"Hello"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn camellia_ctr_mac() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(
+        &pkcs11,
+        slot,
+        &[
+            MechanismType::CAMELLIA_CTR,
+            MechanismType::CAMELLIA_MAC_GENERAL,
+        ],
+    ) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.create_object(&[
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::CAMELLIA),
+        Attribute::Value(hex::decode("0123456789abcdeffedcba9876543210")?),
+        Attribute::Encrypt(true),
+        Attribute::Decrypt(true),
+        Attribute::Sign(true),
+        Attribute::Verify(true),
+    ])?;
+
+    let mut cb = [0x22; 16];
+    cb[12..].fill(0);
+    let mechanism = Mechanism::CamelliaCtr(CamelliaCtrParams::new(32, cb)?);
+    let plain = [0x33; 20];
+    let cipher = session.encrypt(&mechanism, key, &plain)?;
+    assert_eq!(
+        cipher,
+        hex::decode("d8e1998f4d543e1421f8a149cc17341ff58ce543")?
+    );
+    assert_eq!(session.decrypt(&mechanism, key, &cipher)?, plain);
+
+    // CBC-MAC with a zero initialization vector
+    let data = [0x33; 32];
+    let mac = session.sign(&Mechanism::camellia_mac_general(16)?, key, &data)?;
+    assert_eq!(mac, hex::decode("b274d59eaa6403fa0dd1c1544074b3a8")?);
+    assert_eq!(session.sign(&Mechanism::CamelliaMac, key, &data)?, mac[..8]);
+    session.verify(&Mechanism::CamelliaMac, key, &data, &mac[..8])?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+#[ignore = "SoftHSM does not allow a digest and an encryption operation side by side"]
+fn dual_function_digest_encrypt() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let key = session.generate_key(
+        &Mechanism::AesKeyGen,
+        &[
+            Attribute::ValueLen(16.into()),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+        ],
+    )?;
+    let iv = [0x42; 16];
+    let data = [0x5a; 64];
+
+    session.digest_init(&Mechanism::Sha256)?;
+    session.encrypt_init(&Mechanism::AesCbc(iv), key)?;
+    let mut encrypted = session.digest_encrypt_update(&data[..32])?;
+    encrypted.extend(session.digest_encrypt_update(&data[32..])?);
+    encrypted.extend(session.encrypt_final()?);
+    let digest = session.digest_final()?;
+
+    assert_eq!(
+        encrypted,
+        session.encrypt(&Mechanism::AesCbc(iv), key, &data)?
+    );
+    assert_eq!(digest, session.digest(&Mechanism::Sha256, &data)?);
+
+    session.decrypt_init(&Mechanism::AesCbc(iv), key)?;
+    session.digest_init(&Mechanism::Sha256)?;
+    let mut decrypted = session.decrypt_digest_update(&encrypted)?;
+    decrypted.extend(session.decrypt_final()?);
+    assert_eq!(decrypted, data);
+    assert_eq!(session.digest_final()?, digest);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dual_function_not_initialized() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // Tokens which do not implement the dual-function operations at all, like
+    // SoftHSM, reject them before looking for the active operations
+    let data = [0x5a; 16];
+    let res = session.digest_encrypt_update(&data);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationNotInitialized | RvError::FunctionNotSupported,
+            Function::DigestEncryptUpdate
+        ))
+    ));
+    let res = session.decrypt_digest_update(&data);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationNotInitialized | RvError::FunctionNotSupported,
+            Function::DecryptDigestUpdate
+        ))
+    ));
+    let res = session.sign_encrypt_update(&data);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationNotInitialized | RvError::FunctionNotSupported,
+            Function::SignEncryptUpdate
+        ))
+    ));
+    let res = session.decrypt_verify_update(&data);
+    assert!(matches!(
+        res,
+        Err(Error::Pkcs11(
+            RvError::OperationNotInitialized | RvError::FunctionNotSupported,
+            Function::DecryptVerifyUpdate
+        ))
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn gost_sign_verify() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::GOSTR3410_KEY_PAIR_GEN]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    let (public, private) = session.generate_key_pair(
+        &Mechanism::GostR3410KeyPairGen,
+        &[
+            GostR3410ParamSet::CryptoProA.into(),
+            Attribute::Verify(true),
+        ],
+        &[Attribute::Sign(true)],
+    )?;
+    let data = [0xFF, 0x55, 0xDD];
+    let signature = session.sign(&Mechanism::GostR3410WithGostR3411, private, &data)?;
+    session.verify(
+        &Mechanism::GostR3410WithGostR3411,
+        public,
+        &data,
+        &signature,
+    )?;
+
+    let digest = session.digest(&Mechanism::GostR3411, &data)?;
+    assert_eq!(digest.len(), 32);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn gost28147_encrypt_mac() -> TestResult {
+    let (pkcs11, slot) = init_pins();
+    if !require_mechanisms(&pkcs11, slot, &[MechanismType::GOST28147_KEY_GEN]) {
+        return Ok(());
+    }
+    let session = pkcs11.open_rw_session(slot)?;
+    session.login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))?;
+
+    // id-Gost28147-89-CryptoPro-A-ParamSet (1.2.643.2.2.31.1)
+    let key = session.generate_key(
+        &Mechanism::Gost28147KeyGen,
+        &[
+            Attribute::Gost28147Params(vec![0x06, 0x07, 0x2A, 0x85, 0x03, 0x02, 0x02, 0x1F, 0x01]),
+            Attribute::Encrypt(true),
+            Attribute::Decrypt(true),
+            Attribute::Sign(true),
+            Attribute::Verify(true),
+        ],
+    )?;
+
+    let plain = [0x42; 32];
+    let mechanism = Mechanism::Gost28147([0x11; 8]);
+    let cipher = session.encrypt(&mechanism, key, &plain)?;
+    assert_eq!(session.decrypt(&mechanism, key, &cipher)?, plain);
+
+    let mac = session.sign(&Mechanism::Gost28147Mac(None), key, &plain)?;
+    session.verify(&Mechanism::Gost28147Mac(None), key, &plain, &mac)?;
+
+    Ok(())
+}
@@ -1,10 +1,12 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::MechanismType;
 use cryptoki::session::UserType;
 use cryptoki::slot::Slot;
 use cryptoki::types::AuthPin;
 use std::env;
+use std::io::Write;
 
 // The default user pin
 pub static USER_PIN: &str = "fedcba";
@@ -41,3 +43,24 @@ pub fn init_pins() -> (Pkcs11, Slot) {
 
     (pkcs11, slot)
 }
+
+// Whether the token supports all the mechanisms a test needs. The test should
+// be skipped when it does not, which is reported on the standard error: it is
+// written directly so that the test harness does not capture it.
+pub fn require_mechanisms(pkcs11: &Pkcs11, slot: Slot, mechanisms: &[MechanismType]) -> bool {
+    let supported = pkcs11.get_mechanism_list(slot).unwrap();
+    let missing: Vec<String> = mechanisms
+        .iter()
+        .filter(|mechanism| !supported.contains(mechanism))
+        .map(|mechanism| mechanism.to_string())
+        .collect();
+    if missing.is_empty() {
+        return true;
+    }
+    let _ = writeln!(
+        std::io::stderr(),
+        "skipping test: the token does not support {}",
+        missing.join(", ")
+    );
+    false
+}